@@ -46,7 +46,7 @@ impl AppContext {
             ppi: self.config.ppi,
             scale: self.config.scale,
             fonts: self.font_cache,
-            allow_remote_images: self.cli_overrides.allow_remote_images,
+            allow_remote_images: self.config.allow_remote_images,
             fast_png,
         }
     }
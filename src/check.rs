@@ -0,0 +1,309 @@
+//! `mlux check`: report problems in a document without displaying it.
+//!
+//! Links are checked here, against the filesystem. Everything that needs
+//! the compile pipeline (images, Mermaid diagrams, LaTeX math and the Typst
+//! compile itself) runs in the sandboxed child of
+//! [`crate::renderer::build_check`]. Every issue carries the Markdown line
+//! it was found on, when it can be located.
+
+use std::path::Path;
+
+use anyhow::Result;
+use pulldown_cmark::{CodeBlockKind, Event, Parser, Tag, TagEnd};
+
+use crate::compile::{
+    ImageError, Issue, IssueKind, LoadedImages, Prescan, Severity, collect_diagnostics,
+    diagram_key, extract_img_srcs, extract_link_hrefs, parser_options, typst_issues,
+};
+use crate::frame::byte_offset_to_line;
+use crate::log::LogBuffer;
+use crate::pipeline::{BuildParams, PreparedContent, prepare_content};
+use crate::url;
+use crate::viewer::query::heading_anchors;
+
+/// Check `params.markdown`. Issues are ordered by line; those that could
+/// not be located come last.
+pub fn check(params: &BuildParams, no_sandbox: bool, log_buffer: &LogBuffer) -> Result<Vec<Issue>> {
+    let mut issues = check_links(&params.markdown, params.file_path.as_deref());
+    issues.extend(crate::renderer::build_check(
+        params, no_sandbox, log_buffer,
+    )?);
+    issues.sort_by_key(|i| (i.line.is_none(), i.line));
+    Ok(issues)
+}
+
+/// Report links to local files or headings that do not exist.
+///
+/// External URLs are not fetched. Without `file_path` (stdin input) only
+/// same-document anchors are checked.
+pub fn check_links(markdown: &str, file_path: Option<&Path>) -> Vec<Issue> {
+    let mut issues = Vec::new();
+    for (dest, offset) in scan(markdown).links {
+        let problem = if let Some(fragment) = dest.strip_prefix('#') {
+            (!fragment.is_empty() && !has_anchor(markdown, fragment))
+                .then(|| format!("no heading for anchor {dest}"))
+        } else if is_external(&dest) {
+            None
+        } else {
+            file_path
+                .and_then(|file| url::resolve_link_path(&url::percent_decode(&dest), file))
+                .and_then(|target| link_target_problem(&dest, &target))
+        };
+        if let Some(message) = problem {
+            let line = byte_offset_to_line(markdown, offset);
+            issues.push(Issue::new(
+                Some(line),
+                Severity::Error,
+                IssueKind::Link,
+                message,
+            ));
+        }
+    }
+    issues
+}
+
+/// Why the local link `dest`, resolved to `target`, is broken, if it is.
+fn link_target_problem(dest: &str, target: &Path) -> Option<String> {
+    if !target.exists() {
+        return Some(format!("{dest}: no such file"));
+    }
+    let fragment = url::link_fragment(dest)?;
+    if !matches!(
+        url::LinkTarget::classify(dest),
+        url::LinkTarget::LocalMarkdown(_)
+    ) {
+        return None;
+    }
+    let text = std::fs::read_to_string(target).ok()?;
+    (!has_anchor(&text, fragment)).then(|| format!("{dest}: no heading for anchor #{fragment}"))
+}
+
+/// Whether `markdown` has a heading slug, or an HTML `id`/`name`, matching
+/// the link fragment `fragment`.
+fn has_anchor(markdown: &str, fragment: &str) -> bool {
+    let wanted = url::percent_decode(fragment);
+    let slug = wanted.to_lowercase();
+    heading_anchors(markdown).iter().any(|(s, _)| *s == slug)
+        || markdown.contains(&format!("id=\"{wanted}\""))
+        || markdown.contains(&format!("name=\"{wanted}\""))
+}
+
+/// Whether `dest` points off the filesystem: it has a URL scheme
+/// (`https:`, `mailto:`, …) or is protocol-relative (`//host/path`).
+fn is_external(dest: &str) -> bool {
+    dest.starts_with("//")
+        || dest.split_once(':').is_some_and(|(scheme, _)| {
+            scheme.len() > 1
+                && scheme.starts_with(|c: char| c.is_ascii_alphabetic())
+                && scheme
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '.'))
+        })
+}
+
+/// Check images, diagrams, math and the Typst compile.
+///
+/// Runs in the sandboxed child of [`crate::renderer::build_check`] with the
+/// images it loaded and the errors for those it could not.
+pub(crate) fn check_compiled(
+    params: &BuildParams,
+    prescan: &Prescan,
+    images: LoadedImages,
+    image_errors: &[ImageError],
+) -> Result<Vec<Issue>> {
+    let markdown = params.markdown.as_str();
+    let elements = scan(markdown);
+    let line = |offset| byte_offset_to_line(markdown, offset);
+    let mut issues = Vec::new();
+
+    for err in image_errors {
+        let (severity, message) = match err {
+            // Fetched by the parent; only failures are left here.
+            ImageError::RemoteUrl(p) if params.allow_remote_images && p.contains("://") => {
+                (Severity::Error, format!("failed to fetch {p}"))
+            }
+            ImageError::RemoteUrl(_) => (Severity::Warning, image_message(err)),
+            _ => (Severity::Error, image_message(err)),
+        };
+        let mut lines: Vec<Option<usize>> = elements
+            .images
+            .iter()
+            .filter(|(src, _)| src == err.path())
+            .map(|&(_, offset)| Some(line(offset)))
+            .collect();
+        if lines.is_empty() {
+            lines.push(None);
+        }
+        for l in lines {
+            issues.push(Issue::new(l, severity, IssueKind::Image, message.clone()));
+        }
+    }
+
+    for (latex, offset) in &elements.math {
+        if let Err(e) = mitex::convert_math(latex, None) {
+            issues.push(Issue::new(
+                Some(line(*offset)),
+                Severity::Error,
+                IssueKind::Math,
+                format!("cannot convert LaTeX: {e}"),
+            ));
+        }
+    }
+
+    let PreparedContent {
+        world,
+        content_index,
+        diagram_errors,
+        ..
+    } = prepare_content(params, prescan, images, None)?;
+
+    for (key, error) in &diagram_errors {
+        for (_, offset) in elements.diagrams.iter().filter(|(k, _)| k == key) {
+            issues.push(Issue::new(
+                Some(line(*offset)),
+                Severity::Error,
+                IssueKind::Mermaid,
+                format!("cannot render diagram: {error}"),
+            ));
+        }
+    }
+
+    issues.extend(typst_issues(
+        &collect_diagnostics(&world),
+        &world,
+        &content_index,
+        markdown,
+    ));
+
+    Ok(issues)
+}
+
+/// An image error without the `image: ` prefix its kind already says.
+fn image_message(err: &ImageError) -> String {
+    let text = err.to_string();
+    text.strip_prefix("image: ").unwrap_or(&text).to_string()
+}
+
+/// Checkable elements of a document, each with its Markdown byte offset.
+#[derive(Default)]
+struct Elements {
+    links: Vec<(String, usize)>,
+    images: Vec<(String, usize)>,
+    math: Vec<(String, usize)>,
+    /// Mermaid blocks, by [`diagram_key`].
+    diagrams: Vec<(String, usize)>,
+}
+
+fn scan(markdown: &str) -> Elements {
+    let mut elements = Elements::default();
+    let mut mermaid: Option<(String, usize)> = None;
+    for (event, range) in Parser::new_ext(markdown, parser_options()).into_offset_iter() {
+        match event {
+            Event::Start(Tag::Link { dest_url, .. }) => {
+                elements.links.push((dest_url.to_string(), range.start));
+            }
+            Event::Start(Tag::Image { dest_url, .. }) => {
+                elements.images.push((dest_url.to_string(), range.start));
+            }
+            Event::Html(html) | Event::InlineHtml(html) => {
+                for href in extract_link_hrefs(&html) {
+                    elements.links.push((href, range.start));
+                }
+                for src in extract_img_srcs(&html) {
+                    elements.images.push((src, range.start));
+                }
+            }
+            Event::InlineMath(latex) | Event::DisplayMath(latex) => {
+                elements.math.push((latex.to_string(), range.start));
+            }
+            Event::Start(Tag::CodeBlock(CodeBlockKind::Fenced(lang)))
+                if lang.as_ref() == "mermaid" =>
+            {
+                mermaid = Some((String::new(), range.start));
+            }
+            Event::Text(text) => {
+                if let Some((source, _)) = mermaid.as_mut() {
+                    source.push_str(&text);
+                }
+            }
+            Event::End(TagEnd::CodeBlock) => {
+                if let Some((source, start)) = mermaid.take() {
+                    elements.diagrams.push((diagram_key(&source), start));
+                }
+            }
+            _ => {}
+        }
+    }
+    elements
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn messages(issues: &[Issue]) -> Vec<(Option<usize>, &str)> {
+        issues
+            .iter()
+            .map(|i| (i.line, i.message.as_str()))
+            .collect()
+    }
+
+    #[test]
+    fn external_links_are_not_checked() {
+        assert!(is_external("https://example.com/a.md"));
+        assert!(is_external("mailto:a@example.com"));
+        assert!(is_external("//example.com/x"));
+        assert!(!is_external("docs/guide.md"));
+        assert!(!is_external("C:/guide.md"));
+        let md = "[a](https://example.com/missing.md) [b](mailto:x@y)\n";
+        assert!(check_links(md, Some(Path::new("/nonexistent/doc.md"))).is_empty());
+    }
+
+    #[test]
+    fn missing_anchor_in_same_document() {
+        let md = "# Setup\n\nSee [setup](#setup), [usage](#usage) and [top](#).\n";
+        let issues = check_links(md, None);
+        assert_eq!(
+            messages(&issues),
+            vec![(Some(3), "no heading for anchor #usage")]
+        );
+    }
+
+    #[test]
+    fn html_anchor_counts_as_heading() {
+        let md = "<a id=\"faq\"></a>\n\n[faq](#faq)\n";
+        assert!(check_links(md, None).is_empty());
+    }
+
+    #[test]
+    fn local_links_resolve_against_the_file() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("guide.md"), "# Install\n").unwrap();
+        let doc = dir.path().join("doc.md");
+        let md = "[ok](guide.md#install)\n\n[gone](missing.md)\n\n[bad](guide.md#usage)\n";
+        let issues = check_links(md, Some(&doc));
+        assert_eq!(
+            messages(&issues),
+            vec![
+                (Some(3), "missing.md: no such file"),
+                (Some(5), "guide.md#usage: no heading for anchor #usage"),
+            ]
+        );
+        assert!(issues.iter().all(|i| i.severity == Severity::Error));
+    }
+
+    #[test]
+    fn scan_locates_elements() {
+        let md =
+            "![a](a.png)\n\n$x^2$\n\n```mermaid\ngraph LR\n  A --> B\n```\n\n<img src=\"b.png\">\n";
+        let elements = scan(md);
+        let lines = |v: &[(String, usize)]| -> Vec<usize> {
+            v.iter().map(|&(_, o)| byte_offset_to_line(md, o)).collect()
+        };
+        assert_eq!(lines(&elements.images), vec![1, 10]);
+        assert_eq!(elements.images[1].0, "b.png");
+        assert_eq!(lines(&elements.math), vec![3]);
+        assert_eq!(lines(&elements.diagrams), vec![5]);
+        assert_eq!(elements.diagrams[0].0, diagram_key("graph LR\n  A --> B\n"));
+    }
+}
@@ -40,6 +40,19 @@ pub struct TextSpan {
     pub kind: SpanKind,
 }
 
+/// A footnote label with the Markdown locations of its references and definition.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Footnote {
+    /// Label as written in the source (`note` for `[^note]`).
+    pub label: String,
+    /// Display number, assigned in order of first appearance.
+    pub number: usize,
+    /// Byte ranges of each `[^label]` reference in the Markdown source.
+    pub references: Vec<Range<usize>>,
+    /// Byte range of the `[^label]: ...` definition, if the document has one.
+    pub definition: Option<Range<usize>>,
+}
+
 /// Bidirectional index between Markdown source and Typst output.
 ///
 /// Built during `markdown_to_typst()`.
@@ -50,6 +63,7 @@ pub struct TextSpan {
 pub struct ContentIndex {
     text_spans: Vec<TextSpan>,
     block_spans: Vec<BlockMapping>,
+    footnotes: Vec<Footnote>,
 }
 
 impl ContentIndex {
@@ -57,9 +71,16 @@ impl ContentIndex {
         Self {
             text_spans,
             block_spans,
+            footnotes: Vec::new(),
         }
     }
 
+    /// Attach the document's footnotes (ordered by display number).
+    pub fn with_footnotes(mut self, footnotes: Vec<Footnote>) -> Self {
+        self.footnotes = footnotes;
+        self
+    }
+
     pub fn text_spans(&self) -> &[TextSpan] {
         &self.text_spans
     }
@@ -68,6 +89,15 @@ impl ContentIndex {
         &self.block_spans
     }
 
+    pub fn footnotes(&self) -> &[Footnote] {
+        &self.footnotes
+    }
+
+    /// Look up a footnote by its label.
+    pub fn footnote(&self, label: &str) -> Option<&Footnote> {
+        self.footnotes.iter().find(|f| f.label == label)
+    }
+
     /// Find the TextSpan whose typst_range contains `typst_offset`.
     fn find_text_span_by_typst_offset(&self, typst_offset: usize) -> Option<&TextSpan> {
         let idx = self
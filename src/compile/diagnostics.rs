@@ -0,0 +1,142 @@
+//! Problems found while building a document, located on Markdown lines.
+//!
+//! Shared by the build pipeline (compile errors shown in the viewer) and
+//! `mlux check`.
+
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+use typst::diag::SourceDiagnostic;
+
+use super::content_index::{BoundIndex, ContentIndex};
+use super::typst::format_diagnostic;
+use super::world::MluxWorld;
+use crate::frame::byte_offset_to_line;
+
+/// How serious an issue is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Warning,
+    Error,
+}
+
+/// What an issue is about.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum IssueKind {
+    /// A local link whose file or heading does not exist.
+    Link,
+    /// An image that could not be loaded or was rejected.
+    Image,
+    /// A Mermaid diagram that failed to render.
+    Mermaid,
+    /// LaTeX math that mitex could not convert.
+    Math,
+    /// A Typst compile error or warning.
+    Typst,
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Severity::Warning => "warning",
+            Severity::Error => "error",
+        })
+    }
+}
+
+impl fmt::Display for IssueKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            IssueKind::Link => "link",
+            IssueKind::Image => "image",
+            IssueKind::Mermaid => "mermaid",
+            IssueKind::Math => "math",
+            IssueKind::Typst => "typst",
+        })
+    }
+}
+
+/// One problem found in a document.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Issue {
+    /// 1-based Markdown line, if the problem could be located.
+    pub line: Option<usize>,
+    pub severity: Severity,
+    pub kind: IssueKind,
+    pub message: String,
+    /// Further detail (Typst source location, hints, trace).
+    pub notes: Vec<String>,
+}
+
+impl Issue {
+    pub(crate) fn new(
+        line: Option<usize>,
+        severity: Severity,
+        kind: IssueKind,
+        message: String,
+    ) -> Self {
+        Self {
+            line,
+            severity,
+            kind,
+            message,
+            notes: Vec::new(),
+        }
+    }
+}
+
+/// Typst diagnostics as issues on the Markdown lines they came from,
+/// located through the content index's block mappings.
+pub(crate) fn typst_issues(
+    diagnostics: &[SourceDiagnostic],
+    world: &MluxWorld,
+    content_index: &ContentIndex,
+    markdown: &str,
+) -> Vec<Issue> {
+    let bound = BoundIndex::new(
+        content_index,
+        world.main_source(),
+        world.content_offset(),
+        markdown,
+    );
+    diagnostics
+        .iter()
+        .map(|diag| {
+            let severity = match diag.severity {
+                typst::diag::Severity::Error => Severity::Error,
+                typst::diag::Severity::Warning => Severity::Warning,
+            };
+            // Errors raised inside theme functions point at the theme; the
+            // trace leads back to the content that called them.
+            let line = std::iter::once(diag.span)
+                .chain(diag.trace.iter().map(|t| t.span))
+                .find_map(|span| bound.resolve_span(span))
+                .map(|pos| byte_offset_to_line(markdown, pos.offset));
+            let mut issue = Issue::new(line, severity, IssueKind::Typst, diag.message.to_string());
+            // The first line repeats the message.
+            issue.notes = format_diagnostic(diag, world)
+                .lines()
+                .skip(1)
+                .map(|l| l.trim().to_string())
+                .collect();
+            issue
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn issue_json_shape() {
+        let issue = Issue::new(Some(3), Severity::Error, IssueKind::Link, "x".into());
+        let json = serde_json::to_string(&issue).unwrap();
+        assert_eq!(
+            json,
+            r#"{"line":3,"severity":"error","kind":"link","message":"x","notes":[]}"#
+        );
+    }
+}
@@ -134,37 +134,41 @@ fn mermaid_options(colors: &MermaidColors) -> mermaid_rs_renderer::RenderOptions
     opts
 }
 
+/// A diagram that failed to render: `(key, error)`.
+pub type DiagramError = (String, String);
+
 /// Render diagram blocks to SVG bytes.
 ///
 /// Uses `catch_unwind` to handle panics from the renderer gracefully.
-/// Failed diagrams are logged and omitted from the result.
+/// Failed diagrams are logged and omitted from the rendered list; they are
+/// returned separately as `(key, error)`.
 pub fn render_diagrams(
     diagrams: &[(String, String)],
     colors: &MermaidColors,
-) -> Vec<(String, Bytes)> {
+) -> (Vec<(String, Bytes)>, Vec<DiagramError>) {
     let opts = mermaid_options(colors);
-    diagrams
-        .iter()
-        .filter_map(|(key, source)| {
-            let opts = opts.clone();
-            match std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
-                mermaid_rs_renderer::render_with_options(source, opts)
-            })) {
-                Ok(Ok(svg)) => {
-                    let fixed = fix_svg_font_family(&svg);
-                    Some((key.clone(), Bytes::new(fixed.into_bytes())))
-                }
-                Ok(Err(e)) => {
-                    log::warn!("diagram {key}: {e}");
-                    None
-                }
-                Err(_) => {
-                    log::warn!("diagram {key}: renderer panicked");
-                    None
-                }
+    let mut rendered = Vec::new();
+    let mut failed = Vec::new();
+    for (key, source) in diagrams {
+        let opts = opts.clone();
+        match std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            mermaid_rs_renderer::render_with_options(source, opts)
+        })) {
+            Ok(Ok(svg)) => {
+                let fixed = fix_svg_font_family(&svg);
+                rendered.push((key.clone(), Bytes::new(fixed.into_bytes())));
             }
-        })
-        .collect()
+            Ok(Err(e)) => {
+                log::warn!("diagram {key}: {e}");
+                failed.push((key.clone(), e.to_string()));
+            }
+            Err(_) => {
+                log::warn!("diagram {key}: renderer panicked");
+                failed.push((key.clone(), "renderer panicked".to_string()));
+            }
+        }
+    }
+    (rendered, failed)
 }
 
 #[cfg(test)]
@@ -214,8 +218,9 @@ mod tests {
     #[test]
     fn test_render_diagrams_valid() {
         let diagrams = vec![("test.svg".to_string(), "graph LR\n  A --> B".to_string())];
-        let results = render_diagrams(&diagrams, light_colors());
+        let (results, failed) = render_diagrams(&diagrams, light_colors());
         assert_eq!(results.len(), 1);
+        assert!(failed.is_empty());
         let svg = std::str::from_utf8(results[0].1.as_slice()).unwrap();
         assert!(
             svg.contains("<svg"),
@@ -244,9 +249,9 @@ mod tests {
             "bad.svg".to_string(),
             "not a valid diagram at all %%%".to_string(),
         )];
-        let results = render_diagrams(&diagrams, light_colors());
-        // Should not panic; may produce empty or may still render
-        // The important thing is no panic
-        let _ = results;
+        let (results, failed) = render_diagrams(&diagrams, light_colors());
+        // Should not panic; may produce empty or may still render.
+        // Either way every diagram is accounted for.
+        assert_eq!(results.len() + failed.len(), 1);
     }
 }
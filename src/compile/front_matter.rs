@@ -0,0 +1,351 @@
+//! Front matter (`---` YAML / `+++` TOML metadata block) parsing.
+//!
+//! Only the fields mlux displays are extracted (`title`, `author`, `date`,
+//! `tags`), so YAML is read with a small line-based scanner instead of a
+//! full parser. Each value remembers where it appears verbatim in the block
+//! so the rendered header stays searchable.
+
+use std::ops::Range;
+
+use pulldown_cmark::{Event, MetadataBlockKind, Options, Parser, Tag};
+use serde::{Deserialize, Serialize};
+
+use super::content_index::{SpanKind, TextSpan};
+use super::markup_util::escape_typst;
+
+/// Front matter fields shown in the document header and status bar.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FrontMatter {
+    pub title: Option<String>,
+    pub author: Option<String>,
+    pub date: Option<String>,
+    pub tags: Vec<String>,
+}
+
+impl FrontMatter {
+    /// Status bar label: `title · author · date [tags] (filename)`.
+    ///
+    /// Falls back to the bare filename when no field is set.
+    pub fn status_label(&self, filename: &str) -> String {
+        let parts: Vec<&str> = [&self.title, &self.author, &self.date]
+            .into_iter()
+            .flatten()
+            .map(String::as_str)
+            .collect();
+        let mut label = parts.join(" · ");
+        if !self.tags.is_empty() {
+            if !label.is_empty() {
+                label.push(' ');
+            }
+            label.push_str(&format!("[{}]", self.tags.join(", ")));
+        }
+        if label.is_empty() {
+            filename.to_string()
+        } else {
+            format!("{label} ({filename})")
+        }
+    }
+}
+
+/// A front matter value and its byte range within the metadata block text,
+/// when it appears there verbatim.
+pub(super) struct MetaValue {
+    pub text: String,
+    pub range: Option<Range<usize>>,
+}
+
+/// Front matter values with source locations, as used by `markdown_to_typst`.
+#[derive(Default)]
+pub(super) struct MetaFields {
+    pub title: Option<MetaValue>,
+    pub author: Option<MetaValue>,
+    pub date: Option<MetaValue>,
+    pub tags: Vec<MetaValue>,
+}
+
+impl MetaFields {
+    pub(super) fn is_empty(&self) -> bool {
+        self.title.is_none() && self.author.is_none() && self.date.is_none() && self.tags.is_empty()
+    }
+
+    fn to_front_matter(&self) -> FrontMatter {
+        FrontMatter {
+            title: self.title.as_ref().map(|v| v.text.clone()),
+            author: self.author.as_ref().map(|v| v.text.clone()),
+            date: self.date.as_ref().map(|v| v.text.clone()),
+            tags: self.tags.iter().map(|v| v.text.clone()).collect(),
+        }
+    }
+}
+
+/// Extract the front matter of a Markdown document, if it has any.
+pub fn front_matter(markdown: &str) -> Option<FrontMatter> {
+    let mut options = Options::empty();
+    options.insert(Options::ENABLE_YAML_STYLE_METADATA_BLOCKS);
+    options.insert(Options::ENABLE_PLUSES_DELIMITED_METADATA_BLOCKS);
+    let mut parser = Parser::new_ext(markdown, options);
+    let Some(Event::Start(Tag::MetadataBlock(kind))) = parser.next() else {
+        return None;
+    };
+    let mut text = String::new();
+    for event in parser {
+        match event {
+            Event::Text(t) => text.push_str(&t),
+            _ => break,
+        }
+    }
+    let fields = parse_fields(kind, &text);
+    (!fields.is_empty()).then(|| fields.to_front_matter())
+}
+
+/// Parse the displayed fields out of a metadata block's text.
+///
+/// Unknown keys are ignored; a block that fails to parse yields no fields.
+pub(super) fn parse_fields(kind: MetadataBlockKind, text: &str) -> MetaFields {
+    let raw = match kind {
+        MetadataBlockKind::YamlStyle => parse_yaml(text),
+        MetadataBlockKind::PlusesStyle => parse_toml(text),
+    };
+    let mut fields = MetaFields::default();
+    for (key, values) in raw {
+        let mut located = locate_values(text, &key, values);
+        match key.to_ascii_lowercase().as_str() {
+            "title" => fields.title = located.into_iter().next(),
+            "date" => fields.date = located.into_iter().next(),
+            "author" | "authors" => {
+                fields.author = if located.len() > 1 {
+                    let joined: Vec<String> = located.drain(..).map(|v| v.text).collect();
+                    Some(MetaValue {
+                        text: joined.join(", "),
+                        range: None,
+                    })
+                } else {
+                    located.into_iter().next()
+                };
+            }
+            "tags" => fields.tags = located,
+            _ => {}
+        }
+    }
+    fields
+}
+
+/// Find each value verbatim in `text`, searching forward from `key`'s line.
+fn locate_values(text: &str, key: &str, values: Vec<String>) -> Vec<MetaValue> {
+    let mut cursor = text
+        .match_indices(key)
+        .map(|(i, _)| i)
+        .find(|&i| i == 0 || text.as_bytes()[i - 1] == b'\n')
+        .map_or(0, |i| i + key.len());
+    values
+        .into_iter()
+        .filter(|v| !v.is_empty())
+        .map(|v| {
+            let range = text[cursor..].find(&v).map(|i| {
+                let start = cursor + i;
+                cursor = start + v.len();
+                start..cursor
+            });
+            MetaValue { text: v, range }
+        })
+        .collect()
+}
+
+/// Scan top-level `key: value` pairs from YAML front matter.
+///
+/// Handles plain and quoted scalars, flow lists (`[a, b]`) and block lists
+/// (`- a` lines following `key:`). Nested mappings are skipped.
+fn parse_yaml(text: &str) -> Vec<(String, Vec<String>)> {
+    let mut pairs: Vec<(String, Vec<String>)> = Vec::new();
+    for line in text.lines() {
+        let trimmed = line.trim_start();
+        if trimmed.is_empty() || trimmed.starts_with('#') {
+            continue;
+        }
+        if line.starts_with([' ', '\t', '-']) {
+            // Block list item under the previous key
+            if let Some(item) = trimmed.strip_prefix("- ")
+                && let Some((_, values)) = pairs.last_mut()
+            {
+                values.push(unquote(item.trim()).to_string());
+            }
+            continue;
+        }
+        let Some((key, rest)) = line.split_once(':') else {
+            continue;
+        };
+        let rest = rest.trim();
+        let values = if let Some(inner) = rest.strip_prefix('[').and_then(|r| r.strip_suffix(']')) {
+            inner
+                .split(',')
+                .map(|s| unquote(s.trim()).to_string())
+                .filter(|s| !s.is_empty())
+                .collect()
+        } else if rest.is_empty() || rest == "|" || rest == ">" {
+            Vec::new()
+        } else {
+            vec![unquote(rest).to_string()]
+        };
+        pairs.push((key.trim().to_string(), values));
+    }
+    pairs
+}
+
+/// Read top-level keys from TOML front matter.
+fn parse_toml(text: &str) -> Vec<(String, Vec<String>)> {
+    let Ok(table) = text.parse::<toml::Table>() else {
+        return Vec::new();
+    };
+    table
+        .into_iter()
+        .map(|(key, value)| {
+            let values = match value {
+                toml::Value::Array(items) => items.iter().filter_map(toml_scalar).collect(),
+                other => toml_scalar(&other).into_iter().collect(),
+            };
+            (key, values)
+        })
+        .collect()
+}
+
+fn toml_scalar(value: &toml::Value) -> Option<String> {
+    match value {
+        toml::Value::String(s) => Some(s.clone()),
+        toml::Value::Datetime(d) => Some(d.to_string()),
+        toml::Value::Integer(i) => Some(i.to_string()),
+        toml::Value::Float(f) => Some(f.to_string()),
+        toml::Value::Boolean(b) => Some(b.to_string()),
+        toml::Value::Array(_) | toml::Value::Table(_) => None,
+    }
+}
+
+/// Strip one pair of matching single or double quotes.
+fn unquote(s: &str) -> &str {
+    for q in ['"', '\''] {
+        if s.len() >= 2 && s.starts_with(q) && s.ends_with(q) {
+            return &s[1..s.len() - 1];
+        }
+    }
+    s
+}
+
+/// Emit the `#front-matter(...)` header call for the theme.
+///
+/// `md_base` is the Markdown byte offset of the metadata block text; values
+/// found verbatim in it get a `Plain` span so search highlights them.
+pub(super) fn typst_front_matter(
+    fields: &MetaFields,
+    md_base: usize,
+    output: &mut String,
+    text_spans: &mut Vec<TextSpan>,
+) {
+    let mut push_value = |output: &mut String, value: &MetaValue| {
+        output.push('[');
+        let typst_start = output.len();
+        output.push_str(&escape_typst(&value.text));
+        if let Some(r) = &value.range {
+            text_spans.push(TextSpan {
+                typst_range: typst_start..output.len(),
+                md_range: (md_base + r.start)..(md_base + r.end),
+                kind: SpanKind::Plain,
+            });
+        }
+        output.push(']');
+    };
+    output.push_str("#front-matter(");
+    for (name, value) in [
+        ("title", &fields.title),
+        ("author", &fields.author),
+        ("date", &fields.date),
+    ] {
+        if let Some(value) = value {
+            output.push_str(name);
+            output.push_str(": ");
+            push_value(output, value);
+            output.push_str(", ");
+        }
+    }
+    if !fields.tags.is_empty() {
+        // Trailing comma keeps a one-element array an array.
+        output.push_str("tags: (");
+        for tag in &fields.tags {
+            push_value(output, tag);
+            output.push_str(", ");
+        }
+        output.push(')');
+    }
+    output.push_str(")\n");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn yaml_scalars_and_lists() {
+        let text = "title: \"Hello: world\"\nauthor: Ada\ndate: 2026-01-02\ntags: [rust, 'cli']\nextra:\n  nested: 1\n";
+        let f = parse_fields(MetadataBlockKind::YamlStyle, text).to_front_matter();
+        assert_eq!(f.title.as_deref(), Some("Hello: world"));
+        assert_eq!(f.author.as_deref(), Some("Ada"));
+        assert_eq!(f.date.as_deref(), Some("2026-01-02"));
+        assert_eq!(f.tags, vec!["rust", "cli"]);
+    }
+
+    #[test]
+    fn yaml_block_list_and_multiple_authors() {
+        let text = "authors:\n  - Ada\n  - Grace\ntags:\n- one\n- two\n";
+        let f = parse_fields(MetadataBlockKind::YamlStyle, text).to_front_matter();
+        assert_eq!(f.author.as_deref(), Some("Ada, Grace"));
+        assert_eq!(f.tags, vec!["one", "two"]);
+    }
+
+    #[test]
+    fn toml_fields() {
+        let text = "title = \"T\"\ndate = 2026-01-02\ntags = [\"a\", \"b\"]\n";
+        let f = parse_fields(MetadataBlockKind::PlusesStyle, text).to_front_matter();
+        assert_eq!(f.title.as_deref(), Some("T"));
+        assert_eq!(f.date.as_deref(), Some("2026-01-02"));
+        assert_eq!(f.tags, vec!["a", "b"]);
+    }
+
+    #[test]
+    fn invalid_toml_yields_nothing() {
+        assert!(parse_fields(MetadataBlockKind::PlusesStyle, "title = ").is_empty());
+    }
+
+    #[test]
+    fn values_are_located_in_source() {
+        let text = "tags: [x]\ntitle: x marks\n";
+        let fields = parse_fields(MetadataBlockKind::YamlStyle, text);
+        let title = fields.title.unwrap();
+        assert_eq!(&text[title.range.unwrap()], "x marks");
+        let tag = &fields.tags[0];
+        assert_eq!(tag.range.clone().unwrap(), 7..8);
+    }
+
+    #[test]
+    fn front_matter_requires_leading_block() {
+        let md = "---\ntitle: Doc\n---\n\nBody\n";
+        assert_eq!(
+            front_matter(md).and_then(|f| f.title).as_deref(),
+            Some("Doc")
+        );
+        assert!(front_matter("Body\n\n---\ntitle: x\n---\n").is_none());
+        assert!(front_matter("---\nunrelated: 1\n---\n").is_none());
+    }
+
+    #[test]
+    fn status_label_formats_fields() {
+        let f = FrontMatter {
+            title: Some("Guide".into()),
+            author: None,
+            date: Some("2026-01-02".into()),
+            tags: vec!["a".into(), "b".into()],
+        };
+        assert_eq!(
+            f.status_label("guide.md"),
+            "Guide · 2026-01-02 [a, b] (guide.md)"
+        );
+        assert_eq!(FrontMatter::default().status_label("x.md"), "x.md");
+    }
+}
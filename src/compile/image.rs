@@ -51,6 +51,20 @@ pub enum ImageError {
     IoError(String, std::io::Error),
 }
 
+impl ImageError {
+    /// The image path or URL as written in the document.
+    pub fn path(&self) -> &str {
+        match self {
+            ImageError::AbsolutePath(p)
+            | ImageError::RemoteUrl(p)
+            | ImageError::FetchError(p, _)
+            | ImageError::OutsideBase(p)
+            | ImageError::TooLarge(p, _)
+            | ImageError::IoError(p, _) => p,
+        }
+    }
+}
+
 impl fmt::Display for ImageError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
@@ -3,9 +3,14 @@ use std::ops::Range;
 use std::time::Instant;
 
 use log::info;
-use pulldown_cmark::{CodeBlockKind, Event, Options, Parser, Tag, TagEnd};
+use pulldown_cmark::{
+    Alignment, BlockQuoteKind, CodeBlockKind, Event, MetadataBlockKind, Options, Parser, Tag,
+    TagEnd,
+};
 
-use super::content_index::{BlockMapping, ContentIndex, SpanKind, TextSpan};
+use super::content_index::{BlockMapping, ContentIndex, Footnote, SpanKind, TextSpan};
+use super::front_matter::{parse_fields, typst_front_matter};
+use super::markup_html::HtmlRenderer;
 use super::markup_util::{
     escape_typst, escape_typst_string_literal, typst_image, typst_image_placeholder,
 };
@@ -42,6 +47,7 @@ enum Container {
     TableHead,
     TableRow,
     TableCell,
+    FootnoteDefinition,
 }
 
 const MAX_BLOCKQUOTE_DEPTH: usize = 10;
@@ -64,15 +70,28 @@ fn is_cjk(c: char) -> bool {
     )
 }
 
-/// Pre-scan Markdown source to collect metadata without full conversion.
+/// pulldown-cmark extensions mlux renders.
 ///
-/// Collects image paths and detects CJK content in a single pass.
-pub fn prescan(markdown: &str) -> Prescan {
+/// Shared by every pass that must agree on document structure (conversion,
+/// prescan, viewer-side heading lookup).
+pub fn parser_options() -> Options {
     let mut options = Options::empty();
     options.insert(Options::ENABLE_TABLES);
     options.insert(Options::ENABLE_STRIKETHROUGH);
     options.insert(Options::ENABLE_MATH);
-    let parser = Parser::new_ext(markdown, options);
+    options.insert(Options::ENABLE_FOOTNOTES);
+    options.insert(Options::ENABLE_TASKLISTS);
+    options.insert(Options::ENABLE_GFM);
+    options.insert(Options::ENABLE_YAML_STYLE_METADATA_BLOCKS);
+    options.insert(Options::ENABLE_PLUSES_DELIMITED_METADATA_BLOCKS);
+    options
+}
+
+/// Pre-scan Markdown source to collect metadata without full conversion.
+///
+/// Collects image paths and detects CJK content in a single pass.
+pub fn prescan(markdown: &str) -> Prescan {
+    let parser = Parser::new_ext(markdown, parser_options());
 
     let mut paths = Vec::new();
     let mut seen = HashSet::new();
@@ -119,11 +138,7 @@ pub fn markdown_to_typst(
     available_images: Option<&HashSet<String>>,
 ) -> (String, ContentIndex) {
     let start = Instant::now();
-    let mut options = Options::empty();
-    options.insert(Options::ENABLE_TABLES);
-    options.insert(Options::ENABLE_STRIKETHROUGH);
-    options.insert(Options::ENABLE_MATH);
-    let parser = Parser::new_ext(markdown, options);
+    let parser = Parser::new_ext(markdown, parser_options());
 
     let mut output = String::new();
     let mut stack: Vec<Container> = Vec::new();
@@ -136,6 +151,7 @@ pub fn markdown_to_typst(
     // Collected cells for current table
     let mut table_cells: Vec<String> = Vec::new();
     let mut table_col_count: usize = 0;
+    let mut table_alignments: Vec<Alignment> = Vec::new();
 
     // Source mapping: track top-level block boundaries
     let mut source_map_blocks: Vec<BlockMapping> = Vec::new();
@@ -153,11 +169,33 @@ pub fn markdown_to_typst(
     let mut cell_spans_pending: Vec<PendingSpan> = Vec::new();
     // Collected cell spans for all cells in current table (parallel to table_cells)
     let mut table_cell_spans: Vec<Vec<PendingSpan>> = Vec::new();
+    // Footnotes, numbered in order of first appearance (reference or definition)
+    let mut footnotes: Vec<Footnote> = Vec::new();
+    // Front matter being collected: (kind, block md_range, text md start, text)
+    let mut metadata: Option<(MetadataBlockKind, Range<usize>, usize, String)> = None;
+    // Open HTML elements (`<kbd>`, `<details>`, ...) spanning events
+    let mut html = HtmlRenderer::default();
 
     for (event, md_range) in parser.into_offset_iter() {
+        // HTML elements left open inside a container close with it, before
+        // the container's own closing markup.
+        let html_closing = match &event {
+            Event::End(TagEnd::Paragraph) => html.close_from(stack.len(), true),
+            Event::End(TagEnd::HtmlBlock | TagEnd::MetadataBlock(_)) => String::new(),
+            Event::End(_) => html.close_from(stack.len(), false),
+            _ => String::new(),
+        };
+        push_to_target(&mut output, &mut cell_buf, &html_closing);
+        // Inline `<script>`/`<style>` bodies arrive as Markdown text.
+        if html.skipping() && matches!(event, Event::Text(_) | Event::Code(_)) {
+            continue;
+        }
+
         match event {
             // === Block-level Start tags ===
-            Event::Start(Tag::Paragraph) => {
+            // HTML blocks are laid out like paragraphs; their tags are
+            // converted event by event below.
+            Event::Start(Tag::Paragraph | Tag::HtmlBlock) => {
                 if block_depth == 0 {
                     block_starts.push((output.len(), md_range));
                 }
@@ -206,7 +244,7 @@ pub fn markdown_to_typst(
                 output.push(' ');
                 stack.push(Container::Heading);
             }
-            Event::Start(Tag::BlockQuote(_)) => {
+            Event::Start(Tag::BlockQuote(kind)) => {
                 if block_depth == 0 {
                     block_starts.push((output.len(), md_range));
                 }
@@ -222,12 +260,37 @@ pub fn markdown_to_typst(
                     .filter(|c| matches!(c, Container::BlockQuote | Container::BlockQuoteCapped))
                     .count();
                 if bq_depth < MAX_BLOCKQUOTE_DEPTH {
-                    output.push_str("#quote(block: true)[");
+                    // GitHub alerts (`> [!NOTE]`) close exactly like a quote.
+                    match kind {
+                        Some(kind) => {
+                            output.push_str(&format!("#alert(\"{}\")[", alert_name(kind)))
+                        }
+                        None => output.push_str("#quote(block: true)["),
+                    }
                     stack.push(Container::BlockQuote);
                 } else {
                     stack.push(Container::BlockQuoteCapped);
                 }
             }
+            Event::Start(Tag::MetadataBlock(kind)) => {
+                metadata = Some((kind, md_range, 0, String::new()));
+            }
+            Event::Start(Tag::FootnoteDefinition(label)) => {
+                if block_depth == 0 {
+                    block_starts.push((output.len(), md_range.clone()));
+                }
+                block_depth += 1;
+                if !output.is_empty() && !output.ends_with('\n') {
+                    output.push('\n');
+                }
+                if !output.is_empty() {
+                    output.push('\n');
+                }
+                let footnote = footnote_entry(&mut footnotes, &label);
+                footnote.definition = Some(md_range);
+                output.push_str(&format!("#footnote-def(\"{}\")[", footnote.number));
+                stack.push(Container::FootnoteDefinition);
+            }
             Event::Start(Tag::CodeBlock(kind)) => {
                 if block_depth == 0 {
                     block_starts.push((output.len(), md_range));
@@ -310,6 +373,7 @@ pub fn markdown_to_typst(
                     output.push('\n');
                 }
                 table_col_count = alignments.len();
+                table_alignments = alignments;
                 table_cells.clear();
                 stack.push(Container::Table {
                     _col_count: table_col_count,
@@ -358,7 +422,7 @@ pub fn markdown_to_typst(
             }
 
             // === End tags ===
-            Event::End(TagEnd::Paragraph) => {
+            Event::End(TagEnd::Paragraph | TagEnd::HtmlBlock) => {
                 if !output.ends_with('\n') {
                     output.push('\n');
                 }
@@ -411,6 +475,36 @@ pub fn markdown_to_typst(
                     });
                 }
             }
+            Event::End(TagEnd::MetadataBlock(_)) => {
+                let Some((kind, block_range, text_start, text)) = metadata.take() else {
+                    continue;
+                };
+                let fields = parse_fields(kind, &text);
+                if fields.is_empty() {
+                    continue;
+                }
+                let typst_start = output.len();
+                typst_front_matter(&fields, text_start, &mut output, &mut text_spans);
+                source_map_blocks.push(BlockMapping {
+                    typst_byte_range: typst_start..output.len(),
+                    md_byte_range: block_range,
+                });
+            }
+            Event::End(TagEnd::FootnoteDefinition) => {
+                let trimmed = output.trim_end().len();
+                output.truncate(trimmed);
+                output.push_str("]\n");
+                pop_expect(&mut stack, "FootnoteDefinition");
+                block_depth -= 1;
+                if block_depth == 0
+                    && let Some((typst_start, md_range_start)) = block_starts.pop()
+                {
+                    source_map_blocks.push(BlockMapping {
+                        typst_byte_range: typst_start..output.len(),
+                        md_byte_range: md_range_start,
+                    });
+                }
+            }
             Event::End(TagEnd::CodeBlock) => {
                 in_code_block = false;
                 if code_block_lang == "mermaid" {
@@ -543,7 +637,11 @@ pub fn markdown_to_typst(
                 // We need to update the typst_start to the current position
                 // before emitting so the range covers the actual output.
                 let table_typst_start = output.len();
-                output.push_str(&format!("#table(columns: {table_col_count},\n"));
+                output.push_str(&format!("#table(columns: {table_col_count},"));
+                if let Some(align) = table_align_arg(&table_alignments) {
+                    output.push_str(&format!(" align: {align},"));
+                }
+                output.push('\n');
                 for (i, cell) in table_cells.iter().enumerate() {
                     output.push_str("  [");
                     let cell_content_start = output.len();
@@ -648,6 +746,13 @@ pub fn markdown_to_typst(
 
             // === Leaf events ===
             Event::Text(text) => {
+                if let Some((_, _, text_start, buf)) = metadata.as_mut() {
+                    if buf.is_empty() {
+                        *text_start = md_range.start;
+                    }
+                    buf.push_str(&text);
+                    continue;
+                }
                 // Suppress alt text inside images
                 if stack.iter().any(|c| matches!(c, Container::Image { .. })) {
                     continue;
@@ -766,6 +871,38 @@ pub fn markdown_to_typst(
                     });
                 }
             }
+            Event::TaskListMarker(checked) => {
+                // Trailing space keeps the call from swallowing the item text.
+                let typst_start = output.len();
+                output.push_str(&format!("#task-box({checked})"));
+                let typst_end = output.len();
+                output.push(' ');
+                text_spans.push(TextSpan {
+                    typst_range: typst_start..typst_end,
+                    md_range,
+                    kind: SpanKind::Opaque,
+                });
+            }
+            Event::FootnoteReference(label) => {
+                let footnote = footnote_entry(&mut footnotes, &label);
+                footnote.references.push(md_range.clone());
+                let s = format!("#footnote-ref(\"{}\");", footnote.number);
+                if let Some(buf) = cell_buf.as_mut() {
+                    let buf_start = buf.len();
+                    buf.push_str(&s);
+                    let buf_end = buf.len();
+                    cell_spans_pending.push((md_range, buf_start..buf_end, SpanKind::Opaque));
+                } else {
+                    let typst_start = output.len();
+                    output.push_str(&s);
+                    let typst_end = output.len();
+                    text_spans.push(TextSpan {
+                        typst_range: typst_start..typst_end,
+                        md_range,
+                        kind: SpanKind::Opaque,
+                    });
+                }
+            }
             Event::InlineMath(latex) => {
                 let typst_math = latex_to_typst_math(&latex);
                 let s = format!("${typst_math}$");
@@ -813,34 +950,37 @@ pub fn markdown_to_typst(
                     });
                 }
             }
-            Event::Html(html) | Event::InlineHtml(html) => {
-                let snippets = super::markup_html::render_html_imgs(&html, available_images);
-                if !snippets.is_empty() {
-                    if block_depth == 0 {
-                        block_starts.push((output.len(), md_range.clone()));
+            Event::Html(ref fragment) | Event::InlineHtml(ref fragment) => {
+                // Alt text is suppressed, including any HTML in it
+                if stack.iter().any(|c| matches!(c, Container::Image { .. })) {
+                    continue;
+                }
+                let inline = matches!(event, Event::InlineHtml(_));
+                let frag = html.render(
+                    fragment,
+                    md_range.start,
+                    stack.len(),
+                    inline,
+                    available_images,
+                );
+                if let Some(buf) = cell_buf.as_mut() {
+                    let buf_start = buf.len();
+                    buf.push_str(&frag.text);
+                    for (span_md, local, kind) in frag.spans {
+                        cell_spans_pending.push((
+                            span_md,
+                            (buf_start + local.start)..(buf_start + local.end),
+                            kind,
+                        ));
                     }
-                    if cell_buf.is_none() {
-                        let typst_start = output.len();
-                        for s in &snippets {
-                            output.push_str(s);
-                        }
-                        let typst_end = output.len();
+                } else {
+                    let typst_start = output.len();
+                    output.push_str(&frag.text);
+                    for (span_md, local, kind) in frag.spans {
                         text_spans.push(TextSpan {
-                            typst_range: typst_start..typst_end,
-                            md_range,
-                            kind: SpanKind::Opaque,
-                        });
-                    } else {
-                        for s in &snippets {
-                            push_to_target(&mut output, &mut cell_buf, s);
-                        }
-                    }
-                    if block_depth == 0
-                        && let Some((typst_start, md_range_start)) = block_starts.pop()
-                    {
-                        source_map_blocks.push(BlockMapping {
-                            typst_byte_range: typst_start..output.len(),
-                            md_byte_range: md_range_start,
+                            typst_range: (typst_start + local.start)..(typst_start + local.end),
+                            md_range: span_md,
+                            kind,
                         });
                     }
                 }
@@ -849,11 +989,13 @@ pub fn markdown_to_typst(
         }
     }
 
+    // Unterminated block elements (a `<details>` never closed)
+    output.push_str(&html.close_from(0, false));
     if !output.is_empty() && !output.ends_with('\n') {
         output.push('\n');
     }
 
-    let content_index = ContentIndex::new(text_spans, source_map_blocks);
+    let content_index = ContentIndex::new(text_spans, source_map_blocks).with_footnotes(footnotes);
     info!(
         "convert: completed in {:.1}ms (input: {} bytes, output: {} bytes)",
         start.elapsed().as_secs_f64() * 1000.0,
@@ -863,6 +1005,56 @@ pub fn markdown_to_typst(
     (output, content_index)
 }
 
+/// Build the `align:` argument for `#table()` from GFM column alignments.
+///
+/// Returns `None` when no column specifies an alignment, leaving the
+/// theme's default in effect.  Unaligned columns in a mixed table use
+/// `start`, matching what an unaligned table renders as.
+fn table_align_arg(alignments: &[Alignment]) -> Option<String> {
+    if alignments.iter().all(|a| *a == Alignment::None) {
+        return None;
+    }
+    let cols: Vec<&str> = alignments
+        .iter()
+        .map(|a| match a {
+            Alignment::None => "start",
+            Alignment::Left => "left",
+            Alignment::Center => "center",
+            Alignment::Right => "right",
+        })
+        .collect();
+    // A one-element array needs a trailing comma in Typst.
+    Some(format!("({},)", cols.join(", ")))
+}
+
+/// Theme `alert-styles` key for a GitHub alert kind.
+fn alert_name(kind: BlockQuoteKind) -> &'static str {
+    match kind {
+        BlockQuoteKind::Note => "note",
+        BlockQuoteKind::Tip => "tip",
+        BlockQuoteKind::Important => "important",
+        BlockQuoteKind::Warning => "warning",
+        BlockQuoteKind::Caution => "caution",
+    }
+}
+
+/// Find the footnote for `label`, registering it with the next number on first sight.
+fn footnote_entry<'a>(footnotes: &'a mut Vec<Footnote>, label: &str) -> &'a mut Footnote {
+    let idx = match footnotes.iter().position(|f| f.label == label) {
+        Some(idx) => idx,
+        None => {
+            footnotes.push(Footnote {
+                label: label.to_string(),
+                number: footnotes.len() + 1,
+                references: Vec::new(),
+                definition: None,
+            });
+            footnotes.len() - 1
+        }
+    };
+    &mut footnotes[idx]
+}
+
 /// Push string to the cell buffer if active, otherwise to the main output.
 fn push_to_target(output: &mut String, cell_buf: &mut Option<String>, s: &str) {
     if let Some(buf) = cell_buf.as_mut() {
@@ -893,6 +1085,7 @@ fn pop_expect(stack: &mut Vec<Container>, expected: &str) {
                     | (Container::TableHead, "TableHead")
                     | (Container::TableRow, "TableRow")
                     | (Container::TableCell, "TableCell")
+                    | (Container::FootnoteDefinition, "FootnoteDefinition")
             ),
             "Expected {expected}, got {container:?}"
         );
@@ -1224,6 +1417,168 @@ mod tests {
         assert!(typst.contains("quoted text"));
     }
 
+    #[test]
+    fn test_github_alerts() {
+        for (marker, name) in [
+            ("NOTE", "note"),
+            ("TIP", "tip"),
+            ("IMPORTANT", "important"),
+            ("WARNING", "warning"),
+            ("CAUTION", "caution"),
+        ] {
+            let md = format!("> [!{marker}]\n> body text\n");
+            let typst = md_to_typst(&md);
+            assert!(
+                typst.contains(&format!("#alert(\"{name}\")[")),
+                "got: {typst}"
+            );
+            assert!(!typst.contains("#quote("), "got: {typst}");
+            assert!(
+                !typst.contains(marker),
+                "marker should be consumed: {typst}"
+            );
+            assert!(typst.trim_end().ends_with(']'), "got: {typst}");
+        }
+    }
+
+    #[test]
+    fn test_github_alert_spans() {
+        let md = "> [!WARNING]\n> Be *careful* here.\n";
+        let (typst, ci) = markdown_to_typst(md, None);
+        for word in ["Be ", "careful", " here."] {
+            let span = ci
+                .text_spans()
+                .iter()
+                .find(|s| &md[s.md_range.clone()] == word)
+                .unwrap_or_else(|| panic!("span for {word:?}"));
+            assert_eq!(&typst[span.typst_range.clone()], word);
+        }
+        let block = &ci.block_spans()[0];
+        assert_eq!(&md[block.md_byte_range.clone()], md);
+    }
+
+    #[test]
+    fn test_unknown_alert_marker_stays_quote() {
+        let typst = md_to_typst("> [!FOO]\n> text\n");
+        assert!(typst.contains("#quote(block: true)["), "got: {typst}");
+    }
+
+    #[test]
+    fn test_inline_html_elements() {
+        let typst = md_to_typst("Press <kbd>Ctrl</kbd>, H<sub>2</sub>O, <b>bold</b>\n");
+        assert!(
+            typst.contains("Press #kbd[Ctrl], H#sub[2]O, #strong[bold]"),
+            "got: {typst}"
+        );
+    }
+
+    #[test]
+    fn test_inline_script_and_style_bodies_skipped() {
+        let typst = md_to_typst("Before <script>alert(1)</script> after.\n");
+        assert!(typst.contains("Before  after."), "got: {typst}");
+        assert!(!typst.contains("alert"), "got: {typst}");
+        let typst = md_to_typst("Inline <style>.x{}</style> tail.\n");
+        assert!(!typst.contains(".x"), "got: {typst}");
+        assert!(typst.contains("tail."), "got: {typst}");
+    }
+
+    #[test]
+    fn test_unclosed_inline_html_closes_with_paragraph() {
+        let typst = md_to_typst("**<kbd>x** y\n\nnext\n");
+        assert!(typst.contains("#strong[#kbd[x]] y\n"), "got: {typst}");
+    }
+
+    #[test]
+    fn test_html_details_expanded() {
+        let md = "<details>\n<summary>More</summary>\n\nHidden *text*.\n\n</details>\n\nAfter\n";
+        let typst = md_to_typst(md);
+        assert!(typst.contains("#summary[More]"), "got: {typst}");
+        assert!(typst.contains("\n\nHidden #emph[text].\n"), "got: {typst}");
+        assert!(!typst.contains("details"), "got: {typst}");
+    }
+
+    #[test]
+    fn test_html_block_wraps_markdown_blocks() {
+        let md = "<div align=\"center\">\n\n**Hi** there\n\n</div>\n\nAfter\n";
+        let typst = md_to_typst(md);
+        let open = typst.find("#align(center)[").expect(&typst);
+        let hidden = typst.find("#strong[Hi] there").expect(&typst);
+        let close = typst[hidden..].find(']').map(|i| hidden + i);
+        let after = typst.find("After").expect(&typst);
+        assert!(
+            open < hidden && close.is_some_and(|c| c < after),
+            "got: {typst}"
+        );
+    }
+
+    #[test]
+    fn test_html_block_text_searchable() {
+        let md = "<p align=\"center\">\n  Hello <b>world</b>\n</p>\n";
+        let (typst, ci) = markdown_to_typst(md, None);
+        assert!(typst.contains("#align(center)["), "got: {typst}");
+        for word in ["Hello", "world"] {
+            let span = ci
+                .text_spans()
+                .iter()
+                .find(|s| &md[s.md_range.clone()] == word)
+                .unwrap_or_else(|| panic!("span for {word:?}"));
+            assert_eq!(&typst[span.typst_range.clone()], word);
+        }
+        let block = &ci.block_spans()[0];
+        assert_eq!(&md[block.md_byte_range.clone()], md);
+    }
+
+    #[test]
+    fn test_html_in_table_cell() {
+        let typst = md_to_typst("| k |\n|---|\n| <kbd>Tab</kbd> |\n");
+        assert!(typst.contains("[#kbd[Tab]],"), "got: {typst}");
+    }
+
+    #[test]
+    fn test_yaml_front_matter_header() {
+        let md = "---\ntitle: My Doc\nauthor: Ada\ntags: [x]\nlayout: post\n---\n\nBody\n";
+        let (typst, ci) = markdown_to_typst(md, None);
+        assert!(
+            typst.starts_with("#front-matter(title: [My Doc], author: [Ada], tags: ([x], ))\n"),
+            "got: {typst}"
+        );
+        assert!(
+            !typst.contains("#line("),
+            "front matter must not become a rule: {typst}"
+        );
+        assert!(!typst.contains("layout"), "got: {typst}");
+        let span = ci
+            .text_spans()
+            .iter()
+            .find(|s| &typst[s.typst_range.clone()] == "My Doc")
+            .expect("title span");
+        assert_eq!(&md[span.md_range.clone()], "My Doc");
+        let block = &ci.block_spans()[0];
+        assert!(md[block.md_byte_range.clone()].starts_with("---\ntitle: My Doc"));
+    }
+
+    #[test]
+    fn test_toml_front_matter_header() {
+        let typst = md_to_typst("+++\ntitle = \"T#1\"\n+++\n\nBody\n");
+        assert!(
+            typst.contains("#front-matter(title: [T\\#1], )"),
+            "got: {typst}"
+        );
+    }
+
+    #[test]
+    fn test_front_matter_without_known_fields_is_dropped() {
+        let typst = md_to_typst("---\nlayout: post\n---\n\nBody\n");
+        assert!(!typst.contains("front-matter"), "got: {typst}");
+        assert!(!typst.contains("layout"), "got: {typst}");
+        assert!(typst.contains("Body"));
+    }
+
+    #[test]
+    fn test_prescan_front_matter_cjk() {
+        assert!(prescan("---\ntitle: 日本語\n---\n\nBody\n").has_cjk);
+    }
+
     #[test]
     fn test_horizontal_rule() {
         let md = "before\n\n---\n\nafter";
@@ -1279,6 +1634,147 @@ mod tests {
         assert!(typst.contains("[2]"));
     }
 
+    #[test]
+    fn test_table_alignment() {
+        let md = "| L | C | R | N |\n|:--|:-:|--:|---|\n| 1 | 2 | 3 | 4 |";
+        let typst = md_to_typst(md);
+        assert!(
+            typst.contains("#table(columns: 4, align: (left, center, right, start,),"),
+            "got: {typst}"
+        );
+        assert!(typst.contains("[C]"));
+        assert!(typst.contains("[3]"));
+    }
+
+    #[test]
+    fn test_table_single_aligned_column() {
+        let md = "| A |\n|:-:|\n| 1 |";
+        let typst = md_to_typst(md);
+        assert!(
+            typst.contains("#table(columns: 1, align: (center,),"),
+            "got: {typst}"
+        );
+    }
+
+    #[test]
+    fn test_table_without_alignment_omits_align() {
+        let md = "| A | B |\n|---|---|\n| 1 | 2 |";
+        let typst = md_to_typst(md);
+        assert!(!typst.contains("align:"), "got: {typst}");
+    }
+
+    #[test]
+    fn test_table_alignment_cell_spans() {
+        let md = "| Head |\n|-----:|\n| cell |";
+        let (typst, ci) = markdown_to_typst(md, None);
+        for word in ["Head", "cell"] {
+            let span = ci
+                .text_spans()
+                .iter()
+                .find(|s| &md[s.md_range.clone()] == word)
+                .unwrap_or_else(|| panic!("span for {word}"));
+            assert_eq!(&typst[span.typst_range.clone()], word);
+        }
+        let block = &ci.block_spans()[0];
+        assert!(typst[block.typst_byte_range.clone()].starts_with("#table(columns: 1, align:"));
+    }
+
+    #[test]
+    fn test_task_list_markers() {
+        let md = "- [x] done\n- [ ] todo\n";
+        let (typst, ci) = markdown_to_typst(md, None);
+        assert!(typst.contains("- #task-box(true) done"), "got: {typst}");
+        assert!(typst.contains("- #task-box(false) todo"), "got: {typst}");
+        assert!(!typst.contains("\\["), "brackets should not leak: {typst}");
+        let markers: Vec<_> = ci
+            .text_spans()
+            .iter()
+            .filter(|s| s.kind == SpanKind::Opaque)
+            .map(|s| &md[s.md_range.clone()])
+            .collect();
+        assert_eq!(markers, vec!["[x]", "[ ]"]);
+    }
+
+    #[test]
+    fn test_task_list_loose_item() {
+        let md = "- [ ] first\n\n- [x] second\n";
+        let typst = md_to_typst(md);
+        assert!(typst.contains("- #task-box(false) first"), "got: {typst}");
+        assert!(typst.contains("#task-box(true) second"), "got: {typst}");
+    }
+
+    #[test]
+    fn test_footnote_reference_and_definition() {
+        let md = "Text[^a] more.\n\n[^a]: The note.\n";
+        let typst = md_to_typst(md);
+        assert!(
+            typst.contains("Text#footnote-ref(\"1\"); more"),
+            "got: {typst}"
+        );
+        assert!(typst.contains("#footnote-def(\"1\")["), "got: {typst}");
+        assert!(typst.contains("The note.]\n"), "got: {typst}");
+        assert!(!typst.contains("[^a]"), "got: {typst}");
+    }
+
+    #[test]
+    fn test_footnote_ref_followed_by_dot_ident() {
+        // `#f(...)` followed by `.ident` would parse as a field access.
+        let typst = md_to_typst("x[^1].Next\n\n[^1]: n\n");
+        assert!(typst.contains("#footnote-ref(\"1\");.Next"), "got: {typst}");
+    }
+
+    #[test]
+    fn test_footnote_numbering_by_first_appearance() {
+        let md = "A[^z] B[^y] C[^z]\n\n[^y]: why\n\n[^z]: zed\n";
+        let (typst, ci) = markdown_to_typst(md, None);
+        let two = typst.find("#footnote-def(\"2\")").expect("def 2");
+        let one = typst.find("#footnote-def(\"1\")").expect("def 1");
+        assert!(
+            two < typst.find("why").unwrap() && one > two,
+            "got: {typst}"
+        );
+        let z = ci.footnote("z").expect("footnote z");
+        assert_eq!(z.number, 1);
+        assert_eq!(z.references.len(), 2);
+        for r in &z.references {
+            assert_eq!(&md[r.clone()], "[^z]");
+        }
+        let def = z.definition.clone().expect("definition");
+        assert!(md[def].starts_with("[^z]: zed"));
+    }
+
+    #[test]
+    fn test_footnote_undefined_stays_literal() {
+        let (typst, ci) = markdown_to_typst("See [^missing].", None);
+        assert!(!typst.contains("footnote-ref"), "got: {typst}");
+        assert!(ci.footnotes().is_empty());
+    }
+
+    #[test]
+    fn test_footnote_definition_block_mapping() {
+        let md = "Body[^n]\n\n[^n]: Note text.\n";
+        let (typst, ci) = markdown_to_typst(md, None);
+        let def_block = ci
+            .block_spans()
+            .iter()
+            .find(|b| typst[b.typst_byte_range.clone()].contains("#footnote-def("))
+            .expect("footnote definition block");
+        assert!(md[def_block.md_byte_range.clone()].starts_with("[^n]: Note text."));
+        let span = ci
+            .text_spans()
+            .iter()
+            .find(|s| s.kind == SpanKind::Opaque)
+            .expect("opaque span for reference");
+        assert_eq!(&md[span.md_range.clone()], "[^n]");
+    }
+
+    #[test]
+    fn test_footnote_in_table_cell() {
+        let md = "| h |\n|---|\n| c[^1] |\n\n[^1]: n\n";
+        let typst = md_to_typst(md);
+        assert!(typst.contains("[c#footnote-ref(\"1\");]"), "got: {typst}");
+    }
+
     #[test]
     fn test_code_block_no_escape() {
         // Characters inside code blocks should NOT be escaped
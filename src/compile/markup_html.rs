@@ -1,8 +1,11 @@
 use std::collections::HashSet;
+use std::ops::Range;
+use std::sync::LazyLock;
 
 use regex::Regex;
 
-use super::markup_util;
+use super::content_index::SpanKind;
+use super::markup_util::{self, escape_typst};
 
 /// Extract `src` attribute values from `<img>` tags in an HTML fragment.
 ///
@@ -24,25 +27,324 @@ pub fn extract_img_srcs(html: &str) -> Vec<String> {
     srcs
 }
 
-/// Render `<img>` tags in an HTML fragment as Typst snippets.
+/// Extract `href` attribute values from `<a>` tags in an HTML fragment.
+pub fn extract_link_hrefs(html: &str) -> Vec<String> {
+    let re = Regex::new(r#"(?i)<a\b[^>]*\bhref\s*=\s*(?:"([^"]*)"|'([^']*)')"#).unwrap();
+    re.captures_iter(html)
+        .filter_map(|caps| caps.get(1).or_else(|| caps.get(2)))
+        .map(|m| m.as_str().to_string())
+        .filter(|href| !href.is_empty())
+        .collect()
+}
+
+/// An HTML start or end tag at the beginning of the input.
+static TAG_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r#"\A<(/?)([A-Za-z][A-Za-z0-9-]*)((?:\s+[^\s"'=<>/]+(?:\s*=\s*(?:"[^"]*"|'[^']*'|[^\s"'=<>`]+))?)*)\s*/?>"#,
+    )
+    .unwrap()
+});
+
+/// A single attribute inside a start tag.
+static ATTR_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#"([^\s"'=<>/]+)(?:\s*=\s*(?:"([^"]*)"|'([^']*)'|([^\s"'=<>`]+)))?"#).unwrap()
+});
+
+/// Typst markup produced for one HTML event.
+///
+/// Spans are `(md_range, range within text, kind)`, the same shape
+/// `markdown_to_typst` buffers for table cells.
+#[derive(Debug, Default)]
+pub(super) struct HtmlFragment {
+    pub text: String,
+    pub spans: Vec<(Range<usize>, Range<usize>, SpanKind)>,
+}
+
+impl HtmlFragment {
+    fn push(&mut self, s: &str, md_range: Range<usize>, kind: SpanKind) {
+        let start = self.text.len();
+        self.text.push_str(s);
+        self.spans.push((md_range, start..self.text.len(), kind));
+    }
+}
+
+/// An element whose Typst wrapper is still open (`#kbd[` awaiting `]`).
+#[derive(Debug)]
+struct OpenElement {
+    name: String,
+    /// Markdown container depth the element was opened at.
+    depth: usize,
+    /// Opened from inline HTML (inside a paragraph) rather than an HTML block.
+    inline: bool,
+}
+
+/// Converts the supported HTML subset to Typst across the events of a document.
 ///
-/// For each `<img src="...">` found:
-/// - If `src` is in `available_images`, emit `#align(center)[#image("path")]`
-/// - Otherwise, emit a placeholder block
+/// Start and end tags usually arrive in different events (`<kbd>`, text,
+/// `</kbd>`; or `<p align="center">`, Markdown blocks, then `</p>`), so open
+/// elements are tracked here and closed by their end tag or, failing that,
+/// when the Markdown container they were opened in ends. `<details>` itself
+/// is transparent (always expanded) so its Markdown body keeps per-line
+/// source mapping; unknown tags are dropped but their text is kept;
+/// comments and `<script>`/`<style>` bodies are skipped.
+#[derive(Debug, Default)]
+pub(super) struct HtmlRenderer {
+    open: Vec<OpenElement>,
+    in_comment: bool,
+    /// Raw-text element whose body is being skipped.
+    skip_until: Option<String>,
+}
+
+impl HtmlRenderer {
+    /// Convert one `Html` / `InlineHtml` event.
+    ///
+    /// `md_start` is the Markdown byte offset of `html`; `depth` is the
+    /// converter's container depth.
+    pub(super) fn render(
+        &mut self,
+        html: &str,
+        md_start: usize,
+        depth: usize,
+        inline: bool,
+        available_images: Option<&HashSet<String>>,
+    ) -> HtmlFragment {
+        let mut frag = HtmlFragment::default();
+        let mut pos = 0;
+        while pos < html.len() {
+            let rest = &html[pos..];
+            if self.in_comment {
+                match rest.find("-->") {
+                    Some(i) => {
+                        self.in_comment = false;
+                        pos += i + 3;
+                        continue;
+                    }
+                    None => break,
+                }
+            }
+            if rest.starts_with("<!--") {
+                self.in_comment = true;
+                pos += 4;
+                continue;
+            }
+            if let Some(caps) = TAG_RE.captures(rest) {
+                let tag_len = caps[0].len();
+                let name = caps[2].to_ascii_lowercase();
+                let md_range = (md_start + pos)..(md_start + pos + tag_len);
+                if caps[1].is_empty() {
+                    let attrs = parse_attrs(&caps[3]);
+                    self.start_tag(
+                        &mut frag,
+                        &name,
+                        &attrs,
+                        md_range,
+                        depth,
+                        inline,
+                        available_images,
+                    );
+                } else {
+                    self.end_tag(&mut frag, &name, depth, inline);
+                }
+                pos += tag_len;
+                continue;
+            }
+            // Text up to the next `<` (or a lone `<` that starts no tag)
+            let len = rest[1..].find('<').map_or(rest.len(), |i| i + 1);
+            if self.skip_until.is_none() {
+                push_text(&mut frag, &rest[..len], md_start + pos, inline);
+            }
+            pos += len;
+        }
+        frag
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn start_tag(
+        &mut self,
+        frag: &mut HtmlFragment,
+        name: &str,
+        attrs: &[(String, String)],
+        md_range: Range<usize>,
+        depth: usize,
+        inline: bool,
+        available_images: Option<&HashSet<String>>,
+    ) {
+        if self.skip_until.is_some() {
+            return;
+        }
+        let attr = |key: &str| {
+            attrs
+                .iter()
+                .find(|(k, _)| k == key)
+                .map(|(_, v)| v.as_str())
+                .filter(|v| !v.is_empty())
+        };
+        let open = match name {
+            "script" | "style" => {
+                self.skip_until = Some(name.to_string());
+                return;
+            }
+            "br" => {
+                frag.push("\\ ", md_range, SpanKind::Break);
+                return;
+            }
+            "img" => {
+                if let Some(src) = attr("src") {
+                    let is_available = available_images.is_some_and(|set| set.contains(src));
+                    let snippet = if is_available {
+                        markup_util::typst_image(src)
+                    } else {
+                        markup_util::typst_image_placeholder(src)
+                    };
+                    frag.push(&snippet, md_range, SpanKind::Opaque);
+                }
+                return;
+            }
+            "kbd" => "#kbd[".to_string(),
+            "sub" => "#sub[".to_string(),
+            "sup" => "#super[".to_string(),
+            "b" | "strong" => "#strong[".to_string(),
+            "i" | "em" => "#emph[".to_string(),
+            "code" => "#html-code[".to_string(),
+            "summary" => "#summary[".to_string(),
+            "a" => match attr("href") {
+                Some(href) => format!(
+                    "#link(\"{}\")[",
+                    markup_util::escape_typst_string_literal(href)
+                ),
+                None => return,
+            },
+            "p" | "div" => match attr("align").map(str::to_ascii_lowercase).as_deref() {
+                Some(align @ ("center" | "left" | "right")) => format!("#align({align})["),
+                _ => return,
+            },
+            _ => return,
+        };
+        frag.text.push_str(&open);
+        self.open.push(OpenElement {
+            name: name.to_string(),
+            depth,
+            inline,
+        });
+    }
+
+    fn end_tag(&mut self, frag: &mut HtmlFragment, name: &str, depth: usize, inline: bool) {
+        if let Some(skipped) = &self.skip_until {
+            if skipped == name {
+                self.skip_until = None;
+            }
+            return;
+        }
+        // Only an element opened in the same Markdown context may be closed,
+        // otherwise the Typst brackets would interleave with the converter's.
+        let Some(idx) = self
+            .open
+            .iter()
+            .rposition(|e| e.name == name && e.depth == depth && e.inline == inline)
+        else {
+            return;
+        };
+        if !inline {
+            trim_trailing_space(&mut frag.text);
+        }
+        frag.text.push_str(&"]".repeat(self.open.len() - idx));
+        self.open.truncate(idx);
+    }
+
+    /// Whether a `<script>`/`<style>` body is being skipped, so the
+    /// converter must drop its `Text`/`Code` events too.
+    pub(super) fn skipping(&self) -> bool {
+        self.skip_until.is_some()
+    }
+
+    /// Close elements opened at `depth` or deeper, returning the `]`s to emit.
+    ///
+    /// With `inline_only`, elements opened from HTML blocks stay open (a
+    /// paragraph ending inside `<details>` must not close it).
+    pub(super) fn close_from(&mut self, depth: usize, inline_only: bool) -> String {
+        let keep = self
+            .open
+            .iter()
+            .position(|e| e.depth >= depth && (e.inline || !inline_only))
+            .unwrap_or(self.open.len());
+        let closing = "]".repeat(self.open.len() - keep);
+        self.open.truncate(keep);
+        closing
+    }
+}
+
+fn parse_attrs(s: &str) -> Vec<(String, String)> {
+    ATTR_RE
+        .captures_iter(s)
+        .map(|caps| {
+            let value = caps
+                .get(2)
+                .or_else(|| caps.get(3))
+                .or_else(|| caps.get(4))
+                .map_or("", |m| m.as_str());
+            (caps[1].to_ascii_lowercase(), value.to_string())
+        })
+        .collect()
+}
+
+/// Emit HTML text, collapsing whitespace runs to one space and decoding
+/// the common character references.
 ///
-/// Returns an empty Vec if no `<img>` tags are found.
-pub fn render_html_imgs(html: &str, available_images: Option<&HashSet<String>>) -> Vec<String> {
-    let srcs = extract_img_srcs(html);
-    let mut snippets = Vec::new();
-    for src in srcs {
-        let is_available = available_images.is_some_and(|set| set.contains(&src));
-        if is_available {
-            snippets.push(markup_util::typst_image(&src));
-        } else {
-            snippets.push(markup_util::typst_image_placeholder(&src));
+/// Words keep a `Plain` span over their exact source bytes so search can
+/// highlight them; whitespace and references get `Break` / `Opaque` spans.
+fn push_text(frag: &mut HtmlFragment, text: &str, md_start: usize, inline: bool) {
+    let mut pos = 0;
+    while pos < text.len() {
+        let rest = &text[pos..];
+        let md_range = |len: usize| (md_start + pos)..(md_start + pos + len);
+        let ws_len = rest.len() - rest.trim_start().len();
+        if ws_len > 0 {
+            // In HTML blocks, whitespace at a line or wrapper start is layout only.
+            let leading = frag.text.is_empty() || frag.text.ends_with([' ', '\n', '[']);
+            if !leading || (inline && !frag.text.ends_with(' ')) {
+                frag.push(" ", md_range(ws_len), SpanKind::Break);
+            }
+            pos += ws_len;
+            continue;
+        }
+        if let Some((decoded, len)) = decode_entity(rest) {
+            frag.push(&escape_typst(decoded), md_range(len), SpanKind::Opaque);
+            pos += len;
+            continue;
         }
+        let len = rest
+            .char_indices()
+            .skip(1)
+            .find(|&(_, c)| c.is_whitespace() || c == '&')
+            .map_or(rest.len(), |(i, _)| i);
+        frag.push(&escape_typst(&rest[..len]), md_range(len), SpanKind::Plain);
+        pos += len;
     }
-    snippets
+}
+
+/// Decode a character reference at the start of `s`: `(text, source length)`.
+fn decode_entity(s: &str) -> Option<(&'static str, usize)> {
+    const ENTITIES: &[(&str, &str)] = &[
+        ("&amp;", "&"),
+        ("&lt;", "<"),
+        ("&gt;", ">"),
+        ("&quot;", "\""),
+        ("&#39;", "'"),
+        ("&apos;", "'"),
+        ("&nbsp;", "\u{a0}"),
+        ("&copy;", "©"),
+        ("&mdash;", "—"),
+        ("&ndash;", "–"),
+    ];
+    ENTITIES
+        .iter()
+        .find(|(entity, _)| s.starts_with(entity))
+        .map(|(entity, text)| (*text, entity.len()))
+}
+
+fn trim_trailing_space(s: &mut String) {
+    let trimmed = s.trim_end_matches(' ').len();
+    s.truncate(trimmed);
 }
 
 #[cfg(test)]
@@ -99,25 +401,120 @@ mod tests {
         assert!(extract_img_srcs(r#"<img src="">"#).is_empty());
     }
 
+    fn render(html: &str, available: Option<&HashSet<String>>) -> HtmlFragment {
+        HtmlRenderer::default().render(html, 0, 0, false, available)
+    }
+
     #[test]
     fn render_available() {
         let available: HashSet<String> = ["photo.png".to_string()].into_iter().collect();
-        let result = render_html_imgs(r#"<img src="photo.png">"#, Some(&available));
-        assert_eq!(result.len(), 1);
-        assert!(result[0].contains("#image(\"photo.png\")"));
+        let frag = render(r#"<img src="photo.png">"#, Some(&available));
+        assert!(frag.text.contains("#image(\"photo.png\")"));
+        assert_eq!(frag.spans.len(), 1);
+        assert_eq!(frag.spans[0].2, SpanKind::Opaque);
     }
 
     #[test]
     fn render_unavailable() {
         let available: HashSet<String> = HashSet::new();
-        let result = render_html_imgs(r#"<img src="missing.png">"#, Some(&available));
-        assert_eq!(result.len(), 1);
-        assert!(result[0].contains("#image-placeholder("));
+        let frag = render(r#"<img src="missing.png">"#, Some(&available));
+        assert!(frag.text.contains("#image-placeholder("));
     }
 
     #[test]
     fn render_no_img() {
-        let result = render_html_imgs("<div>hello</div>", None);
-        assert!(result.is_empty());
+        let frag = render("<div>hello</div>", None);
+        assert_eq!(frag.text, "hello");
+    }
+
+    #[test]
+    fn render_inline_elements() {
+        let frag = render(
+            "<kbd>Ctrl</kbd>+<sub>2</sub><sup>n</sup><b>b</b><i>i</i>",
+            None,
+        );
+        assert_eq!(frag.text, "#kbd[Ctrl]+#sub[2]#super[n]#strong[b]#emph[i]");
+    }
+
+    #[test]
+    fn render_link_and_code() {
+        let frag = render(r#"<a href="https://a.invalid/"><code>run</code></a>"#, None);
+        assert_eq!(frag.text, "#link(\"https://a.invalid/\")[#html-code[run]]");
+        assert_eq!(render("<a name=\"top\">x</a>", None).text, "x");
+    }
+
+    #[test]
+    fn render_centered_paragraph() {
+        let frag = render("<p align=\"center\">\n  Hello   world\n</p>", None);
+        assert_eq!(frag.text, "#align(center)[Hello world]");
+        assert_eq!(render("<p>plain</p>", None).text, "plain");
+    }
+
+    #[test]
+    fn render_text_spans_point_at_source() {
+        let html = "<b>a &amp; b</b>";
+        let frag = HtmlRenderer::default().render(html, 10, 0, true, None);
+        assert_eq!(frag.text, "#strong[a & b]");
+        let plain: Vec<&str> = frag
+            .spans
+            .iter()
+            .filter(|(_, _, kind)| *kind == SpanKind::Plain)
+            .map(|(md, _, _)| &html[md.start - 10..md.end - 10])
+            .collect();
+        assert_eq!(plain, vec!["a", "b"]);
+    }
+
+    #[test]
+    fn render_escapes_text() {
+        assert_eq!(render("<kbd>#[x]</kbd>", None).text, "#kbd[\\#\\[x\\]]");
+    }
+
+    #[test]
+    fn render_br_and_comments() {
+        assert_eq!(render("a<br>b<!-- hidden -->c", None).text, "a\\ bc");
+        let mut r = HtmlRenderer::default();
+        assert_eq!(r.render("<!-- start\n", 0, 0, false, None).text, "");
+        assert_eq!(
+            r.render("still hidden -->shown\n", 0, 0, false, None).text,
+            "shown "
+        );
+    }
+
+    #[test]
+    fn render_skips_script_body() {
+        assert_eq!(render("<script>alert(1)</script>ok", None).text, "ok");
+    }
+
+    #[test]
+    fn block_element_spans_events() {
+        let mut r = HtmlRenderer::default();
+        let open = r.render(
+            "<div align=\"right\">\n<summary>More</summary>\n",
+            0,
+            0,
+            false,
+            None,
+        );
+        // The line break stays a space: the next line of the block follows directly.
+        assert_eq!(open.text, "#align(right)[#summary[More] ");
+        // A paragraph ending inside the <div> leaves it open.
+        assert_eq!(r.close_from(0, true), "");
+        assert_eq!(r.render("</div>\n", 0, 0, false, None).text, "] ");
+    }
+
+    #[test]
+    fn unclosed_elements_close_with_container() {
+        let mut r = HtmlRenderer::default();
+        assert_eq!(r.render("<kbd>", 0, 2, true, None).text, "#kbd[");
+        // End tag from a different depth does not match.
+        assert_eq!(r.render("</kbd>", 0, 3, true, None).text, "");
+        assert_eq!(r.close_from(2, true), "]");
+        assert_eq!(r.close_from(0, false), "");
+    }
+
+    #[test]
+    fn extract_hrefs() {
+        let html = r#"<a href="https://a.invalid/">x</a> <a name="n"></a> <A HREF='b.md'>"#;
+        assert_eq!(extract_link_hrefs(html), vec!["https://a.invalid/", "b.md"]);
     }
 }
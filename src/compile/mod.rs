@@ -1,5 +1,7 @@
 mod content_index;
+mod diagnostics;
 mod diagram;
+mod front_matter;
 mod image;
 mod markup;
 mod markup_html;
@@ -8,10 +10,18 @@ mod typst;
 mod world;
 
 pub use content_index::{
-    BlockMapping, BoundIndex, ContentIndex, MdPosition, SpanKind, TextSpan, rendered_to_source_byte,
+    BlockMapping, BoundIndex, ContentIndex, Footnote, MdPosition, SpanKind, TextSpan,
+    rendered_to_source_byte,
 };
-pub use diagram::{diagram_key, extract_diagrams, render_diagrams};
+pub(crate) use diagnostics::typst_issues;
+pub use diagnostics::{Issue, IssueKind, Severity};
+pub use diagram::{DiagramError, diagram_key, extract_diagrams, render_diagrams};
+pub use front_matter::{FrontMatter, front_matter};
 pub use image::{ImageError, LoadedImages, load_images};
-pub use markup::{Prescan, markdown_to_typst, prescan};
-pub use typst::{compile_document, dump_document};
+pub use markup::{Prescan, markdown_to_typst, parser_options, prescan};
+pub use markup_html::{extract_img_srcs, extract_link_hrefs};
+pub use typst::{
+    collect_diagnostics, compile_document, dump_document, export_pdf, format_diagnostic,
+    try_compile_document,
+};
 pub use world::{FontCache, MluxWorld};
@@ -54,38 +54,80 @@ pub fn format_diagnostic(diag: &SourceDiagnostic, world: &MluxWorld) -> String {
 
 /// Compile Typst sources into a PagedDocument (no rendering).
 pub fn compile_document(world: &MluxWorld) -> Result<PagedDocument> {
+    match try_compile_document(world) {
+        Ok(doc) => Ok(doc),
+        Err(errors) => {
+            let mut detail = String::new();
+            for err in &errors {
+                detail.push_str(&format_diagnostic(err, world));
+            }
+            bail!(
+                "[BUG] typst compilation failed — this is a bug in mlux, not your input\n\
+                 {} error(s):\n{detail}",
+                errors.len()
+            );
+        }
+    }
+}
+
+/// Like [`compile_document`], but hands back the errors for the caller to
+/// report.
+pub fn try_compile_document(world: &MluxWorld) -> Result<PagedDocument, Vec<SourceDiagnostic>> {
     let start = Instant::now();
     let warned = typst::compile::<PagedDocument>(world);
 
     for warning in &warned.warnings {
-        // Typst warns for every font in the theme that isn't installed.
-        // This is cosmetic, not fatal — demote to debug log.
-        // Missing CJK fonts are reported separately by FontCache::new().
-        if warning.message.as_str().contains("unknown font family") {
+        if is_cosmetic(warning) {
             log::debug!("suppressed typst warning: {}", warning.message);
             continue;
         }
         eprint!("{}", format_diagnostic(warning, world));
     }
 
-    match warned.output {
-        Ok(doc) => {
+    let doc = warned
+        .output
+        .map_err(|errors| errors.into_iter().collect::<Vec<_>>())?;
+    info!(
+        "render: typst::compile completed in {:.1}ms",
+        start.elapsed().as_secs_f64() * 1000.0
+    );
+    Ok(doc)
+}
+
+/// Compile Typst sources and return every diagnostic (errors, then
+/// warnings) instead of failing. Used by `mlux check`.
+pub fn collect_diagnostics(world: &MluxWorld) -> Vec<SourceDiagnostic> {
+    let warned = typst::compile::<PagedDocument>(world);
+    let mut diagnostics: Vec<_> = warned.output.err().into_iter().flatten().collect();
+    diagnostics.extend(warned.warnings.into_iter().filter(|w| !is_cosmetic(w)));
+    diagnostics
+}
+
+/// Typst warns for every font in the theme that isn't installed.
+/// This is cosmetic, not fatal; missing CJK fonts are reported separately
+/// by FontCache::new().
+fn is_cosmetic(warning: &SourceDiagnostic) -> bool {
+    warning.message.as_str().contains("unknown font family")
+}
+
+/// Export a compiled (paginated) document as PDF bytes.
+pub fn export_pdf(document: &PagedDocument, world: &MluxWorld) -> Result<Vec<u8>> {
+    let start = Instant::now();
+    match typst_pdf::pdf(document, &typst_pdf::PdfOptions::default()) {
+        Ok(bytes) => {
             info!(
-                "render: typst::compile completed in {:.1}ms",
-                start.elapsed().as_secs_f64() * 1000.0
+                "render: typst_pdf::pdf completed in {:.1}ms ({} page(s))",
+                start.elapsed().as_secs_f64() * 1000.0,
+                document.pages.len()
             );
-            Ok(doc)
+            Ok(bytes)
         }
         Err(errors) => {
             let mut detail = String::new();
             for err in &errors {
                 detail.push_str(&format_diagnostic(err, world));
             }
-            bail!(
-                "[BUG] typst compilation failed — this is a bug in mlux, not your input\n\
-                 {} error(s):\n{detail}",
-                errors.len()
-            );
+            bail!("PDF export failed — {} error(s):\n{detail}", errors.len());
         }
     }
 }
@@ -1,6 +1,10 @@
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
 use std::time::Duration;
 
-use log::debug;
+use anyhow::{Context, Result};
+use log::{debug, info};
+use serde::{Deserialize, Serialize};
 
 // ---------------------------------------------------------------------------
 // Config — resolved (all fields concrete)
@@ -11,13 +15,16 @@ pub struct Config {
     pub width: f64,
     pub ppi: f32,
     pub scale: f64,
+    /// Allow fetching remote images (http/https URLs) in Markdown.
+    pub allow_remote_images: bool,
     pub viewer: ViewerConfig,
 }
 
 /// User-facing scroll-behavior choice.  Config carries only the
 /// selection; the viewer maps each variant to a concrete strategy
 /// implementation.
-#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
 pub enum ScrollMode {
     /// Constant step per keypress (classic behavior).
     #[default]
@@ -30,7 +37,8 @@ pub enum ScrollMode {
 /// `ScrollAnimator` variant used to advance `current → target` each
 /// frame. Independent of [`ScrollMode`] (which governs the upstream
 /// target-delta accumulation layer).
-#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
 pub enum ScrollAnimation {
     /// Exponential decay (closed-form), the v1 baseline.
     #[default]
@@ -48,6 +56,41 @@ pub enum ScrollAnimation {
     Kinetic,
 }
 
+/// Terminal image protocol used by the viewer.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum GraphicsMode {
+    /// Probe the terminal at startup.
+    #[default]
+    Auto,
+    /// Kitty Graphics Protocol (Kitty, Ghostty, WezTerm).
+    Kitty,
+    /// Kitty graphics through Unicode placeholders; what `kitty` becomes
+    /// inside tmux.
+    KittyPlaceholder,
+    /// DEC Sixel (foot, WezTerm, xterm, mlterm, ...).
+    Sixel,
+    /// iTerm2 inline images (OSC 1337; iTerm2, WezTerm).
+    Iterm2,
+    /// No images: the document is reflowed into styled ANSI text.
+    Text,
+}
+
+/// How Kitty image data is handed to the terminal.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum TransferMode {
+    /// Probe the terminal at startup.
+    #[default]
+    Auto,
+    /// Base64 through the tty (`t=d`); works over SSH.
+    Direct,
+    /// Temporary file the terminal reads and deletes (`t=t`).
+    File,
+    /// POSIX shared memory object (`t=s`).
+    Shm,
+}
+
 /// Experimental preset bundling several scroll-related settings.
 /// Behavior is subject to change between versions; explicit individual
 /// flags (`scroll_mode`, `scroll_animation`) override preset values.
@@ -82,6 +125,53 @@ pub struct ViewerConfig {
     /// Wheel notches per cell-height step. Independent from `scroll_step`
     /// so adaptive keyboard scrolling isn't polluted by wheel cadence.
     pub wheel_step: u32,
+    pub graphics: GraphicsMode,
+    pub transfer: TransferMode,
+    /// User key bindings layered over the built-in keymap.  Kept as raw
+    /// strings here; the viewer parses and validates them at startup.
+    pub keys: KeysConfig,
+}
+
+/// `[keys]` section: per-mode `"chord" = "action"` tables.
+///
+/// Chords are written like `"j"`, `"ctrl-d"`, `"shift-down"`; a
+/// space-separated list (`"g g"`) is a multi-key sequence.  The special
+/// action `"none"` removes a built-in binding.
+#[derive(Clone, Debug, Default, PartialEq, Deserialize, Serialize)]
+#[serde(deny_unknown_fields, rename_all = "kebab-case")]
+pub struct KeysConfig {
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub normal: BTreeMap<String, String>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub toc: BTreeMap<String, String>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub url: BTreeMap<String, String>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub grep: BTreeMap<String, String>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub log: BTreeMap<String, String>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub inline_search: BTreeMap<String, String>,
+}
+
+impl KeysConfig {
+    /// Layer `other` on top of `self`; entries for the same chord are replaced.
+    pub fn merge(&mut self, other: &KeysConfig) {
+        for (dst, src) in [
+            (&mut self.normal, &other.normal),
+            (&mut self.toc, &other.toc),
+            (&mut self.url, &other.url),
+            (&mut self.grep, &other.grep),
+            (&mut self.log, &other.log),
+            (&mut self.inline_search, &other.inline_search),
+        ] {
+            dst.extend(src.iter().map(|(k, v)| (k.clone(), v.clone())));
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
 }
 
 impl Default for Config {
@@ -91,6 +181,7 @@ impl Default for Config {
             width: 660.0,
             ppi: 144.0,
             scale: 1.0,
+            allow_remote_images: false,
             viewer: ViewerConfig::default(),
         }
     }
@@ -109,11 +200,120 @@ impl Default for ViewerConfig {
             watch_interval: Duration::from_millis(200),
             mouse: false,
             wheel_step: 2,
+            graphics: GraphicsMode::default(),
+            transfer: TransferMode::default(),
+            keys: KeysConfig::default(),
         }
     }
 }
 
 impl Config {
+    /// Apply values from a config file.  Keys absent from the file leave
+    /// the current value untouched, so files can be layered.
+    pub fn apply_file(&mut self, file: &FileConfig) {
+        if let Some(ref v) = file.theme {
+            debug!("config: file override theme={v}");
+            self.theme = v.clone();
+        }
+        if let Some(v) = file.width {
+            debug!("config: file override width={v}");
+            self.width = v;
+        }
+        if let Some(v) = file.ppi {
+            debug!("config: file override ppi={v}");
+            self.ppi = v;
+        }
+        if let Some(v) = file.scale {
+            debug!("config: file override scale={v}");
+            self.scale = v;
+        }
+        if let Some(v) = file.allow_remote_images {
+            debug!("config: file override allow_remote_images={v}");
+            self.allow_remote_images = v;
+        }
+        let viewer = &file.viewer;
+        let dst = &mut self.viewer;
+        if let Some(v) = viewer.scroll_step {
+            debug!("config: file override viewer.scroll_step={v}");
+            dst.scroll_step = v;
+        }
+        if let Some(v) = viewer.scroll_mode {
+            debug!("config: file override viewer.scroll_mode={v:?}");
+            dst.scroll_mode = v;
+        }
+        if let Some(v) = viewer.scroll_animation {
+            debug!("config: file override viewer.scroll_animation={v:?}");
+            dst.scroll_animation = v;
+        }
+        if let Some(v) = viewer.frame_budget {
+            debug!("config: file override viewer.frame_budget={v}ms");
+            dst.frame_budget = Duration::from_millis(v);
+        }
+        if let Some(v) = viewer.tile_height {
+            debug!("config: file override viewer.tile_height={v}");
+            dst.tile_height = v;
+        }
+        if let Some(v) = viewer.sidebar_cols {
+            debug!("config: file override viewer.sidebar_cols={v}");
+            dst.sidebar_cols = v;
+        }
+        if let Some(v) = viewer.evict_distance {
+            debug!("config: file override viewer.evict_distance={v}");
+            dst.evict_distance = v;
+        }
+        if let Some(v) = viewer.watch_interval {
+            debug!("config: file override viewer.watch_interval={v}ms");
+            dst.watch_interval = Duration::from_millis(v);
+        }
+        if let Some(v) = viewer.mouse {
+            debug!("config: file override viewer.mouse={v}");
+            dst.mouse = v;
+        }
+        if let Some(v) = viewer.wheel_step {
+            debug!("config: file override viewer.wheel_step={v}");
+            dst.wheel_step = v;
+        }
+        if let Some(v) = viewer.graphics {
+            debug!("config: file override viewer.graphics={v:?}");
+            dst.graphics = v;
+        }
+        if let Some(v) = viewer.transfer {
+            debug!("config: file override viewer.transfer={v:?}");
+            dst.transfer = v;
+        }
+        if !file.keys.is_empty() {
+            debug!("config: file override keys={:?}", file.keys);
+            dst.keys.merge(&file.keys);
+        }
+    }
+
+    /// Serialize the resolved config in config-file syntax (`mlux config --print`).
+    pub fn to_toml(&self) -> String {
+        let file = FileConfig {
+            theme: Some(self.theme.clone()),
+            width: Some(self.width),
+            ppi: Some(self.ppi),
+            scale: Some(self.scale),
+            allow_remote_images: Some(self.allow_remote_images),
+            viewer: ViewerFileConfig {
+                scroll_step: Some(self.viewer.scroll_step),
+                scroll_mode: Some(self.viewer.scroll_mode),
+                scroll_animation: Some(self.viewer.scroll_animation),
+                frame_budget: Some(self.viewer.frame_budget.as_millis() as u64),
+                tile_height: Some(self.viewer.tile_height),
+                sidebar_cols: Some(self.viewer.sidebar_cols),
+                evict_distance: Some(self.viewer.evict_distance),
+                watch_interval: Some(self.viewer.watch_interval.as_millis() as u64),
+                mouse: Some(self.viewer.mouse),
+                wheel_step: Some(self.viewer.wheel_step),
+                graphics: Some(self.viewer.graphics),
+                transfer: Some(self.viewer.transfer),
+            },
+            keys: self.viewer.keys.clone(),
+        };
+        toml::to_string(&file).expect("[BUG] resolved config must serialize")
+    }
+
     /// Apply CLI overrides to this config.
     pub fn apply_cli(&mut self, cli: &CliOverrides) {
         if let Some(ref v) = cli.theme {
@@ -157,11 +357,202 @@ impl Config {
             debug!("config: CLI override scroll_animation={anim:?}");
             self.viewer.scroll_animation = anim;
         }
+        if let Some(v) = cli.graphics {
+            debug!("config: CLI override graphics={v:?}");
+            self.viewer.graphics = v;
+        }
         if cli.mouse {
             debug!("config: CLI override mouse=true");
             self.viewer.mouse = true;
         }
+        if cli.allow_remote_images {
+            debug!("config: CLI override allow_remote_images=true");
+            self.allow_remote_images = true;
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// FileConfig — values from a TOML config file (all fields optional)
+// ---------------------------------------------------------------------------
+
+/// Partial config as read from a TOML file.
+///
+/// Mirrors [`Config`] / [`ViewerConfig`] with every field optional.
+/// Durations are given in milliseconds.  Unknown keys are rejected so
+/// typos surface as errors instead of being silently ignored.
+#[derive(Debug, Default, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct FileConfig {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub theme: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub width: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ppi: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scale: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub allow_remote_images: Option<bool>,
+    #[serde(default)]
+    pub viewer: ViewerFileConfig,
+    #[serde(default, skip_serializing_if = "KeysConfig::is_empty")]
+    pub keys: KeysConfig,
+}
+
+/// `[viewer]` table of [`FileConfig`].
+#[derive(Debug, Default, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct ViewerFileConfig {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scroll_step: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scroll_mode: Option<ScrollMode>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scroll_animation: Option<ScrollAnimation>,
+    /// Milliseconds.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub frame_budget: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tile_height: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sidebar_cols: Option<u16>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub evict_distance: Option<usize>,
+    /// Milliseconds.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub watch_interval: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mouse: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub wheel_step: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub graphics: Option<GraphicsMode>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub transfer: Option<TransferMode>,
+}
+
+impl FileConfig {
+    /// Parse and validate config file contents.
+    ///
+    /// Errors name the offending key (e.g. `viewer.scroll_step`).
+    pub fn parse(text: &str) -> Result<Self> {
+        let file: FileConfig = toml::from_str(text).map_err(|e| anyhow::anyhow!("{e}"))?;
+        file.validate()?;
+        Ok(file)
+    }
+
+    /// Read and parse a config file.
+    pub fn load(path: &Path) -> Result<Self> {
+        let text = std::fs::read_to_string(path)
+            .with_context(|| format!("failed to read config {}", path.display()))?;
+        Self::parse(&text).with_context(|| format!("invalid config {}", path.display()))
+    }
+
+    fn validate(&self) -> Result<()> {
+        fn positive(key: &str, v: Option<f64>) -> Result<()> {
+            match v {
+                Some(v) if !(v.is_finite() && v > 0.0) => {
+                    anyhow::bail!("`{key}` must be a positive number, got {v}")
+                }
+                _ => Ok(()),
+            }
+        }
+        fn nonzero(key: &str, v: Option<u64>) -> Result<()> {
+            match v {
+                Some(0) => anyhow::bail!("`{key}` must be at least 1"),
+                _ => Ok(()),
+            }
+        }
+        if let Some(ref theme) = self.theme
+            && !crate::theme::is_valid_theme_spec(theme)
+        {
+            anyhow::bail!("`theme`: unknown theme '{theme}'");
+        }
+        positive("width", self.width)?;
+        positive("ppi", self.ppi.map(f64::from))?;
+        positive("scale", self.scale)?;
+        let v = &self.viewer;
+        nonzero("viewer.scroll_step", v.scroll_step.map(u64::from))?;
+        nonzero("viewer.frame_budget", v.frame_budget)?;
+        positive("viewer.tile_height", v.tile_height)?;
+        nonzero("viewer.watch_interval", v.watch_interval)?;
+        nonzero("viewer.wheel_step", v.wheel_step.map(u64::from))?;
+        Ok(())
+    }
+}
+
+/// Location of the per-user config file:
+/// `$XDG_CONFIG_HOME/mlux/config.toml`, falling back to
+/// `~/.config/mlux/config.toml`.
+pub fn user_config_path() -> Option<PathBuf> {
+    let base = std::env::var_os("XDG_CONFIG_HOME")
+        .filter(|v| !v.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| {
+            std::env::var_os("HOME")
+                .filter(|v| !v.is_empty())
+                .map(|h| PathBuf::from(h).join(".config"))
+        })?;
+    Some(base.join("mlux").join("config.toml"))
+}
+
+/// File name of the per-project config checked into a repository.
+pub const PROJECT_CONFIG_NAME: &str = ".mlux.toml";
+
+/// Find the nearest `.mlux.toml` walking up from `start`.
+///
+/// The walk stops at the git root (inclusive) so a config in an
+/// unrelated parent directory never leaks into a repository.  Outside a
+/// git repository only `start` itself is checked.
+pub fn project_config_path(start: &Path) -> Option<PathBuf> {
+    let Some(root) = crate::fork_sandbox::find_git_root(start) else {
+        let candidate = start.join(PROJECT_CONFIG_NAME);
+        return candidate.is_file().then_some(candidate);
+    };
+    let mut dir = start;
+    loop {
+        let candidate = dir.join(PROJECT_CONFIG_NAME);
+        if candidate.is_file() {
+            return Some(candidate);
+        }
+        if dir == root {
+            return None;
+        }
+        dir = dir.parent()?;
+    }
+}
+
+/// Build the effective config: defaults → user file → project file → CLI.
+///
+/// `explicit` is a path given via `--config`; it must exist and replaces
+/// the user config file.  `project_dir` is where the `.mlux.toml` search
+/// starts (the input file's directory, or the cwd for stdin).  Returns
+/// the resolved config and the list of files that were applied, in order.
+pub fn resolve(
+    explicit: Option<&Path>,
+    project_dir: Option<&Path>,
+    cli: &CliOverrides,
+) -> Result<(Config, Vec<PathBuf>)> {
+    let mut config = Config::default();
+    let mut applied = Vec::new();
+    let user = match explicit {
+        Some(p) => Some(p.to_path_buf()),
+        None => user_config_path().filter(|p| p.is_file()),
+    };
+    let project = project_dir.and_then(project_config_path);
+    for (layer, path) in [("user", user), ("project", project)] {
+        let Some(path) = path else { continue };
+        let file = FileConfig::load(&path)?;
+        config.apply_file(&file);
+        info!("config: applied {layer} config {}", path.display());
+        applied.push(path);
+    }
+    if applied.is_empty() {
+        info!("config: no config file found, using built-in defaults");
     }
+    config.apply_cli(cli);
+    Ok((config, applied))
 }
 
 // ---------------------------------------------------------------------------
@@ -181,6 +572,7 @@ pub struct CliOverrides {
     pub exp_preset: Option<ExpPreset>,
     /// Presence-flag (`--mouse`); `true` flips `viewer.mouse` on, `false` is no-op.
     pub mouse: bool,
+    pub graphics: Option<GraphicsMode>,
 }
 
 #[cfg(test)]
@@ -199,6 +591,7 @@ mod tests {
         assert_eq!(config.viewer.evict_distance, 4);
         assert!(!config.viewer.mouse);
         assert_eq!(config.viewer.wheel_step, 2);
+        assert!(!config.allow_remote_images);
     }
 
     #[test]
@@ -226,6 +619,7 @@ mod tests {
             scroll_animation: None,
             exp_preset: None,
             mouse: false,
+            graphics: None,
         };
         config.apply_cli(&cli);
         assert_eq!(config.theme, "dark");
@@ -302,4 +696,171 @@ mod tests {
             assert_eq!(config.viewer.scroll_step, 3); // unchanged
         }
     }
+
+    #[test]
+    fn file_config_covers_all_fields() {
+        let file = FileConfig::parse(
+            r#"
+            theme = "catppuccin-latte"
+            width = 800
+            ppi = 288
+            scale = 1.25
+            allow_remote_images = true
+
+            [viewer]
+            scroll_step = 5
+            scroll_mode = "adaptive"
+            scroll_animation = "exp-decay-adaptive"
+            frame_budget = 16
+            tile_height = 400
+            sidebar_cols = 8
+            evict_distance = 2
+            watch_interval = 500
+            mouse = true
+            wheel_step = 3
+            graphics = "sixel"
+            transfer = "shm"
+            "#,
+        )
+        .unwrap();
+        let mut config = Config::default();
+        config.apply_file(&file);
+        assert_eq!(config.theme, "catppuccin-latte");
+        assert_eq!(config.width, 800.0);
+        assert_eq!(config.ppi, 288.0);
+        assert_eq!(config.scale, 1.25);
+        assert!(config.allow_remote_images);
+        let v = &config.viewer;
+        assert_eq!(v.scroll_step, 5);
+        assert_eq!(v.scroll_mode, ScrollMode::Adaptive);
+        assert_eq!(v.scroll_animation, ScrollAnimation::ExpDecayAdaptive);
+        assert_eq!(v.frame_budget, Duration::from_millis(16));
+        assert_eq!(v.tile_height, 400.0);
+        assert_eq!(v.sidebar_cols, 8);
+        assert_eq!(v.evict_distance, 2);
+        assert_eq!(v.watch_interval, Duration::from_millis(500));
+        assert!(v.mouse);
+        assert_eq!(v.wheel_step, 3);
+        assert_eq!(v.graphics, GraphicsMode::Sixel);
+        assert_eq!(v.transfer, TransferMode::Shm);
+    }
+
+    #[test]
+    fn file_config_partial_keeps_defaults() {
+        let file = FileConfig::parse("[viewer]\nmouse = true\n").unwrap();
+        let mut config = Config::default();
+        config.apply_file(&file);
+        assert!(config.viewer.mouse);
+        assert_eq!(config.theme, "auto");
+        assert_eq!(config.viewer.scroll_step, 3);
+    }
+
+    #[test]
+    fn file_config_errors_name_the_key() {
+        let err = FileConfig::parse("[viewer]\nscrol_step = 4\n").unwrap_err();
+        assert!(format!("{err:#}").contains("scrol_step"), "{err:#}");
+
+        let err = FileConfig::parse("[viewer]\nscroll_step = 0\n").unwrap_err();
+        assert!(format!("{err:#}").contains("viewer.scroll_step"), "{err:#}");
+
+        let err = FileConfig::parse("width = \"wide\"\n").unwrap_err();
+        assert!(format!("{err:#}").contains("width"), "{err:#}");
+
+        let err = FileConfig::parse("theme = \"nope\"\n").unwrap_err();
+        assert!(format!("{err:#}").contains("theme"), "{err:#}");
+    }
+
+    #[test]
+    fn cli_overrides_win_over_file() {
+        let file = FileConfig::parse("theme = \"catppuccin\"\nppi = 96\n").unwrap();
+        let mut config = Config::default();
+        config.apply_file(&file);
+        config.apply_cli(&CliOverrides {
+            theme: Some("catppuccin-latte".into()),
+            ..Default::default()
+        });
+        assert_eq!(config.theme, "catppuccin-latte");
+        assert_eq!(config.ppi, 96.0);
+    }
+
+    #[test]
+    fn to_toml_round_trips() {
+        let mut config = Config::default();
+        config.viewer.scroll_animation = ScrollAnimation::Kinetic;
+        config.allow_remote_images = true;
+        let text = config.to_toml();
+        let mut back = Config::default();
+        back.apply_file(&FileConfig::parse(&text).unwrap());
+        assert_eq!(back.to_toml(), text);
+        assert_eq!(back.viewer.scroll_animation, ScrollAnimation::Kinetic);
+        assert!(back.allow_remote_images);
+    }
+
+    #[test]
+    fn project_config_stops_at_git_root() {
+        let tmp = tempfile::tempdir().unwrap();
+        let repo = tmp.path().join("repo");
+        let sub = repo.join("docs").join("guide");
+        std::fs::create_dir_all(&sub).unwrap();
+        std::fs::create_dir(repo.join(".git")).unwrap();
+
+        // Above the git root: ignored.
+        std::fs::write(tmp.path().join(PROJECT_CONFIG_NAME), "").unwrap();
+        assert_eq!(project_config_path(&sub), None);
+
+        std::fs::write(repo.join(PROJECT_CONFIG_NAME), "").unwrap();
+        assert_eq!(
+            project_config_path(&sub),
+            Some(repo.join(PROJECT_CONFIG_NAME))
+        );
+
+        // Nearest file wins.
+        std::fs::write(sub.join(PROJECT_CONFIG_NAME), "").unwrap();
+        assert_eq!(
+            project_config_path(&sub),
+            Some(sub.join(PROJECT_CONFIG_NAME))
+        );
+    }
+
+    #[test]
+    fn project_config_layers_between_user_and_cli() {
+        let tmp = tempfile::tempdir().unwrap();
+        std::fs::create_dir(tmp.path().join(".git")).unwrap();
+        let user = tmp.path().join("user.toml");
+        std::fs::write(&user, "theme = \"catppuccin\"\nwidth = 700\nppi = 96\n").unwrap();
+        std::fs::write(
+            tmp.path().join(PROJECT_CONFIG_NAME),
+            "width = 800\nscale = 1.5\n",
+        )
+        .unwrap();
+        let cli = CliOverrides {
+            scale: Some(2.0),
+            ..Default::default()
+        };
+        let (config, applied) = resolve(Some(&user), Some(tmp.path()), &cli).unwrap();
+        assert_eq!(applied, vec![user, tmp.path().join(PROJECT_CONFIG_NAME)]);
+        assert_eq!(config.theme, "catppuccin"); // user
+        assert_eq!(config.ppi, 96.0); // user
+        assert_eq!(config.width, 800.0); // project beats user
+        assert_eq!(config.scale, 2.0); // CLI beats project
+    }
+
+    #[test]
+    fn keys_merge_per_chord() {
+        let user = FileConfig::parse(
+            "[keys.normal]\nJ = \"scroll-down\"\nK = \"scroll-up\"\n[keys.toc]\nl = \"confirm\"\n",
+        )
+        .unwrap();
+        let project = FileConfig::parse("[keys.normal]\nK = \"none\"\n").unwrap();
+        let mut config = Config::default();
+        config.apply_file(&user);
+        config.apply_file(&project);
+        let keys = &config.viewer.keys;
+        assert_eq!(keys.normal["J"], "scroll-down");
+        assert_eq!(keys.normal["K"], "none");
+        assert_eq!(keys.toc["l"], "confirm");
+
+        let err = FileConfig::parse("[keys.visual]\nj = \"scroll-down\"\n").unwrap_err();
+        assert!(format!("{err:#}").contains("visual"), "{err:#}");
+    }
 }
@@ -0,0 +1,273 @@
+//! Restricting a document to one section or line range (`mlux render
+//! --section` / `--lines`).
+//!
+//! Sections are resolved through the TOC headings and their anchor slugs,
+//! and line ranges are widened to the Markdown blocks mapped by the visual
+//! lines, so fences, lists and tables stay intact. The excerpt is preceded
+//! by blank lines so that Markdown line numbers (and with them the git diff
+//! markers) still match the file.
+
+use std::ops::Range;
+
+use anyhow::{Result, bail};
+
+use crate::frame::{DocumentMeta, byte_offset_to_line};
+use crate::viewer::mode_toc::collect_headings;
+use crate::viewer::query::{DocumentQuery, heading_anchors};
+
+/// Which part of the document to keep.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Excerpt {
+    /// A heading (matched by text or anchor slug) and everything below it
+    /// up to the next heading of the same or a higher level.
+    Section(String),
+    /// A 1-based inclusive Markdown line range.
+    Lines { start: usize, end: usize },
+}
+
+impl Excerpt {
+    /// Parse a `--lines` value: `40:120`, `40:` or `:120`.
+    pub fn parse_lines(spec: &str) -> Result<Self> {
+        let Some((start, end)) = spec.split_once(':') else {
+            bail!("invalid line range '{spec}' (expected START:END)");
+        };
+        let parse = |s: &str, default: usize| -> Result<usize> {
+            if s.trim().is_empty() {
+                return Ok(default);
+            }
+            match s.trim().parse::<usize>() {
+                Ok(n) if n > 0 => Ok(n),
+                _ => bail!("invalid line number '{s}' in '{spec}'"),
+            }
+        };
+        let start = parse(start, 1)?;
+        let end = parse(end, usize::MAX)?;
+        if start > end {
+            bail!("line range '{spec}' is empty");
+        }
+        Ok(Self::Lines { start, end })
+    }
+}
+
+/// Extract `excerpt` from `markdown`, padded with leading newlines so the
+/// excerpt keeps its original line numbers.
+///
+/// `meta` must come from a build of the whole `markdown`: headings and block
+/// boundaries are taken from its visual lines.
+pub fn extract(markdown: &str, excerpt: &Excerpt, meta: &DocumentMeta) -> Result<String> {
+    let doc = DocumentQuery::new(
+        markdown,
+        &meta.visual_lines,
+        &meta.content_index,
+        meta.content_offset,
+    );
+    extract_from(&doc, excerpt)
+}
+
+fn extract_from(doc: &DocumentQuery, excerpt: &Excerpt) -> Result<String> {
+    let markdown = doc.markdown;
+    let range = match excerpt {
+        Excerpt::Section(name) => section_range(doc, name)?,
+        Excerpt::Lines { start, end } => line_block_range(doc, *start, *end)?,
+    };
+    let first_line = byte_offset_to_line(markdown, range.start);
+    let mut out = "\n".repeat(first_line - 1);
+    out.push_str(&markdown[range]);
+    Ok(out)
+}
+
+/// Byte offset of the start of 1-based `line`.
+fn line_start(markdown: &str, line: usize) -> usize {
+    markdown
+        .split_inclusive('\n')
+        .take(line - 1)
+        .map(str::len)
+        .sum()
+}
+
+/// Byte range of the section headed by `name`, matched against the TOC
+/// headings by text or by their anchor slug.
+fn section_range(doc: &DocumentQuery, name: &str) -> Result<Range<usize>> {
+    let markdown = doc.markdown;
+    let wanted = name.trim().trim_start_matches('#').trim();
+    let slug = crate::url::heading_slug(wanted);
+    let anchors = heading_anchors(markdown);
+    let anchor_of = |md_line: usize| {
+        anchors
+            .iter()
+            .find(|(_, offset)| byte_offset_to_line(markdown, *offset) == md_line)
+            .map(|(s, _)| s.as_str())
+    };
+    let headings = collect_headings(doc);
+    let Some(idx) = headings.iter().position(|h| {
+        h.text.eq_ignore_ascii_case(wanted) || anchor_of(h.md_line) == Some(slug.as_str())
+    }) else {
+        bail!("no heading matches '{name}'");
+    };
+    let heading = &headings[idx];
+    let end = headings[idx + 1..]
+        .iter()
+        .find(|h| h.level <= heading.level)
+        .map_or(markdown.len(), |h| line_start(markdown, h.md_line));
+    Ok(line_start(markdown, heading.md_line)..end)
+}
+
+/// Byte range covering lines `start..=end`, widened to whole Markdown blocks
+/// of the visual lines it touches.
+fn line_block_range(doc: &DocumentQuery, start: usize, end: usize) -> Result<Range<usize>> {
+    let markdown = doc.markdown;
+    let line_count = markdown.lines().count();
+    if start > line_count {
+        bail!("line {start} is past the end of the document ({line_count} lines)");
+    }
+    let end = end.min(line_count);
+    let mut from = line_start(markdown, start);
+    let mut to = if end == line_count {
+        markdown.len()
+    } else {
+        line_start(markdown, end + 1)
+    };
+    for block in doc
+        .visual_lines
+        .iter()
+        .filter_map(|vl| vl.md_block_range.as_ref())
+    {
+        if block.start < to && from < block.end {
+            from = from.min(block.start);
+            to = to.max(block.end);
+        }
+    }
+    // Block ranges may stop before the final newline; end on a line boundary.
+    let to = if to == 0 || markdown[..to].ends_with('\n') {
+        to
+    } else {
+        markdown[to..]
+            .find('\n')
+            .map_or(markdown.len(), |i| to + i + 1)
+    };
+    Ok(from..to)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::frame::VisualLine;
+    use crate::viewer::query::test_helpers::{empty_ci, make_visual_lines, make_vl};
+
+    const DOC: &str =
+        "# Title\n\nIntro\n\n## Install\n\nStep\n\n### Linux\n\napt\n\n## Usage\n\nRun\n";
+
+    fn excerpt(md: &str, vls: &[VisualLine], excerpt: Excerpt) -> Result<String> {
+        let ci = empty_ci();
+        extract_from(&DocumentQuery::new(md, vls, &ci, 0), &excerpt)
+    }
+
+    fn section(md: &str, name: &str) -> Result<String> {
+        excerpt(md, &make_visual_lines(md), Excerpt::Section(name.into()))
+    }
+
+    #[test]
+    fn section_stops_at_same_level_heading() {
+        let out = section(DOC, "Install").unwrap();
+        assert_eq!(out, "\n\n\n\n## Install\n\nStep\n\n### Linux\n\napt\n\n");
+    }
+
+    #[test]
+    fn section_matches_slug_case_insensitively() {
+        let out = section(DOC, "#usage").unwrap();
+        assert!(out.ends_with("## Usage\n\nRun\n"));
+        let md = "# A\n\n## The `?` operator\n\nx\n";
+        let out = section(md, "the ? operator").unwrap();
+        assert!(out.ends_with("## The `?` operator\n\nx\n"));
+        assert!(section(DOC, "Missing").is_err());
+    }
+
+    #[test]
+    fn section_matches_suffixed_anchor_of_repeated_heading() {
+        let md = "# Notes\n\na\n\n# Notes\n\nb\n";
+        let out = section(md, "notes-1").unwrap();
+        assert_eq!(out, "\n\n\n\n# Notes\n\nb\n");
+    }
+
+    #[test]
+    fn section_ignores_headings_in_code_blocks() {
+        let md = "# A\n\n```\n# B\n```\n\n# C\n";
+        assert!(section(md, "B").is_err());
+        assert_eq!(section(md, "A").unwrap(), "# A\n\n```\n# B\n```\n\n");
+    }
+
+    #[test]
+    fn lines_widen_to_whole_blocks() {
+        let md = "a\n\n```\none\ntwo\n```\n\nb\n";
+        let vls = [
+            make_vl(md, Some((1, 1))),
+            make_vl(md, Some((3, 6))),
+            make_vl(md, Some((8, 8))),
+        ];
+        let out = excerpt(md, &vls, Excerpt::Lines { start: 5, end: 5 }).unwrap();
+        assert_eq!(out, "\n\n```\none\ntwo\n```\n");
+    }
+
+    #[test]
+    fn lines_do_not_spill_into_next_line() {
+        let md = "a\nb\n\nc\n";
+        let vls = [make_vl(md, Some((1, 2))), make_vl(md, Some((4, 4)))];
+        let out = excerpt(md, &vls, Excerpt::Lines { start: 4, end: 4 }).unwrap();
+        assert_eq!(out, "\n\n\nc\n");
+        let md = "a\n\nb\nc\n";
+        let out = excerpt(
+            md,
+            &make_visual_lines(md),
+            Excerpt::Lines { start: 1, end: 1 },
+        )
+        .unwrap();
+        assert_eq!(out, "a\n");
+    }
+
+    #[test]
+    fn lines_end_on_a_line_boundary() {
+        // Paragraph block range without its trailing newline.
+        let md = "a\nb\n\nc\n";
+        let vls = [VisualLine {
+            y_pt: 0.0,
+            y_px: 0,
+            md_block_range: Some(0..3),
+            md_offset: Some(0),
+            diff_status: None,
+        }];
+        let out = excerpt(md, &vls, Excerpt::Lines { start: 1, end: 1 }).unwrap();
+        assert_eq!(out, "a\nb\n");
+    }
+
+    #[test]
+    fn lines_keep_original_numbering() {
+        let vls = make_visual_lines(DOC);
+        let out = excerpt(DOC, &vls, Excerpt::Lines { start: 7, end: 7 }).unwrap();
+        assert_eq!(byte_offset_to_line(&out, out.find("Step").unwrap()), 7);
+    }
+
+    #[test]
+    fn parse_lines_specs() {
+        assert_eq!(
+            Excerpt::parse_lines("40:120").unwrap(),
+            Excerpt::Lines {
+                start: 40,
+                end: 120
+            }
+        );
+        assert_eq!(
+            Excerpt::parse_lines(":3").unwrap(),
+            Excerpt::Lines { start: 1, end: 3 }
+        );
+        assert_eq!(
+            Excerpt::parse_lines("5:").unwrap(),
+            Excerpt::Lines {
+                start: 5,
+                end: usize::MAX
+            }
+        );
+        assert!(Excerpt::parse_lines("5").is_err());
+        assert!(Excerpt::parse_lines("9:3").is_err());
+        assert!(Excerpt::parse_lines("0:3").is_err());
+    }
+}
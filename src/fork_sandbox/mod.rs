@@ -16,6 +16,7 @@ use crate::log::{LogBuffer, LogEntry};
 
 pub use process::ChildProcess;
 pub(crate) use process::{TypedReader, TypedWriter};
+pub(crate) use sandbox::find_git_root;
 
 /// Sandbox policy for a forked child.
 pub(crate) enum SandboxConfig {
@@ -40,18 +41,23 @@ impl SandboxConfig {
     }
 }
 
+/// Stack size of the child's pool threads, matching a typical main thread
+/// (typst's layout recurses deeply).
+const CHILD_STACK_SIZE: usize = 8 * 1024 * 1024;
+
 /// Fork a child with typed channels, applying sandbox before `child_fn` runs.
 ///
 /// This is the general-purpose fork primitive of `fork_sandbox`.
 /// If `sandbox` is `Enforce`, Landlock is applied before `child_fn` runs.
+/// `child_fn` runs inside a rayon pool created in the child.
 pub(crate) fn fork_sandboxed<Req, Resp, F>(
     sandbox: SandboxConfig,
     child_fn: F,
 ) -> Result<(TypedWriter<Req>, TypedReader<Resp>, ChildProcess)>
 where
-    Req: Serialize + DeserializeOwned,
-    Resp: Serialize + DeserializeOwned,
-    F: FnOnce(TypedReader<Req>, TypedWriter<Resp>),
+    Req: Serialize + DeserializeOwned + Send,
+    Resp: Serialize + DeserializeOwned + Send,
+    F: FnOnce(TypedReader<Req>, TypedWriter<Resp>) + Send,
 {
     process::fork_with_channels(move |req_rx, resp_tx| {
         if let SandboxConfig::Enforce {
@@ -62,7 +68,20 @@ where
         {
             log::warn!("child: sandbox failed: {e:#}");
         }
-        child_fn(req_rx, resp_tx);
+        // Rayon's global pool survives fork without its threads: once the
+        // parent has started it, parallel work queued there never runs. Give
+        // the child a pool of its own, built after the sandbox so its threads
+        // inherit the restrictions.
+        match rayon::ThreadPoolBuilder::new()
+            .stack_size(CHILD_STACK_SIZE)
+            .build()
+        {
+            Ok(pool) => pool.install(move || child_fn(req_rx, resp_tx)),
+            Err(e) => {
+                log::warn!("child: thread pool failed: {e}");
+                child_fn(req_rx, resp_tx);
+            }
+        }
     })
 }
 
@@ -80,11 +99,92 @@ enum ComputeResult<T> {
 /// and exits. Panics in `f` are caught; child log entries are forwarded in both cases.
 pub(crate) fn fork_compute<T, F>(sandbox: SandboxConfig, log_buffer: &LogBuffer, f: F) -> Result<T>
 where
-    T: Serialize + DeserializeOwned,
-    F: FnOnce() -> T,
+    T: Serialize + DeserializeOwned + Send,
+    F: FnOnce() -> T + Send,
+{
+    spawn_compute(sandbox, log_buffer, f)?.wait(log_buffer)
+}
+
+/// Run `f` on each item in forked workers, at most `jobs` at a time.
+///
+/// A new worker starts as soon as any running one finishes. Results come
+/// back in input order. Workers are not sandboxed: they are meant to run
+/// the renderer orchestration, which forks its own sandboxed children.
+/// Must be called before the process spawns threads (see
+/// [`fork_sandboxed`]).
+pub fn fork_map<I, T, F>(items: &[I], jobs: usize, log_buffer: &LogBuffer, f: F) -> Vec<Result<T>>
+where
+    I: Sync,
+    T: Serialize + DeserializeOwned + Send,
+    F: Fn(&I) -> T + Sync,
+{
+    let jobs = jobs.max(1);
+    let mut results: Vec<Option<Result<T>>> = items.iter().map(|_| None).collect();
+    // (input index, worker) for every worker still running.
+    let mut running: Vec<(usize, PendingCompute<T>)> = Vec::new();
+    for (idx, item) in items.iter().enumerate() {
+        if running.len() >= jobs {
+            let (done, pending) = running.swap_remove(first_finished(&running));
+            results[done] = Some(pending.wait(log_buffer));
+        }
+        match spawn_compute(SandboxConfig::Disabled, log_buffer, || f(item)) {
+            Ok(pending) => running.push((idx, pending)),
+            Err(e) => results[idx] = Some(Err(e)),
+        }
+    }
+    while !running.is_empty() {
+        let (done, pending) = running.swap_remove(first_finished(&running));
+        results[done] = Some(pending.wait(log_buffer));
+    }
+    results
+        .into_iter()
+        .map(|r| r.expect("every item has a result"))
+        .collect()
+}
+
+/// Position in `running` of a worker whose result pipe is ready, blocking
+/// until one is. A child only exits after its result has been read, so the
+/// pipes are polled rather than the processes.
+fn first_finished<T>(running: &[(usize, PendingCompute<T>)]) -> usize {
+    use nix::poll::{PollFd, PollFlags, PollTimeout, poll};
+    use std::os::fd::AsFd;
+
+    let mut fds: Vec<PollFd> = running
+        .iter()
+        .map(|(_, p)| PollFd::new(p.rx.as_fd(), PollFlags::POLLIN))
+        .collect();
+    loop {
+        match poll(&mut fds, PollTimeout::NONE) {
+            Ok(_) => {
+                return fds
+                    .iter()
+                    .position(|fd| fd.revents().is_some_and(|r| !r.is_empty()))
+                    .unwrap_or(0);
+            }
+            Err(nix::errno::Errno::EINTR) => continue,
+            // Fall back to a blocking wait on the oldest worker.
+            Err(_) => return 0,
+        }
+    }
+}
+
+/// A forked computation whose result has not been collected yet.
+struct PendingCompute<T> {
+    rx: TypedReader<ComputeResult<T>>,
+    child: ChildProcess,
+}
+
+fn spawn_compute<T, F>(
+    sandbox: SandboxConfig,
+    log_buffer: &LogBuffer,
+    f: F,
+) -> Result<PendingCompute<T>>
+where
+    T: Serialize + DeserializeOwned + Send,
+    F: FnOnce() -> T + Send,
 {
     let log_buf = log_buffer.clone();
-    let (_, mut rx, mut child) =
+    let (_, rx, child) =
         fork_sandboxed::<(), ComputeResult<T>, _>(sandbox, move |_req_rx, mut resp_tx| {
             // Discard log entries inherited from the parent via fork COW.
             // Without this, drain() would include pre-fork entries, causing
@@ -102,21 +202,28 @@ where
                 }
             }
         })?;
-    let result = rx.recv().context("fork_compute: child failed")?;
-    match result {
-        ComputeResult::Ok { value, logs } => {
-            for entry in logs {
-                log_buffer.push(entry);
+    Ok(PendingCompute { rx, child })
+}
+
+impl<T: DeserializeOwned> PendingCompute<T> {
+    /// Receive the child's result, forward its logs and reap it.
+    fn wait(mut self, log_buffer: &LogBuffer) -> Result<T> {
+        let result = self.rx.recv().context("fork_compute: child failed")?;
+        match result {
+            ComputeResult::Ok { value, logs } => {
+                for entry in logs {
+                    log_buffer.push(entry);
+                }
+                self.child.wait()?;
+                Ok(value)
             }
-            child.wait()?;
-            Ok(value)
-        }
-        ComputeResult::Panicked { logs } => {
-            for entry in logs {
-                log_buffer.push(entry);
+            ComputeResult::Panicked { logs } => {
+                for entry in logs {
+                    log_buffer.push(entry);
+                }
+                self.child.wait()?;
+                anyhow::bail!("fork_compute: child panicked")
             }
-            child.wait()?;
-            anyhow::bail!("fork_compute: child panicked")
         }
     }
 }
@@ -128,8 +235,8 @@ mod tests {
     /// Convenience wrapper for tests: `fork_compute` with no sandbox.
     fn fork_compute_nosandbox<T, F>(log_buffer: &LogBuffer, f: F) -> Result<T>
     where
-        T: Serialize + DeserializeOwned,
-        F: FnOnce() -> T,
+        T: Serialize + DeserializeOwned + Send,
+        F: FnOnce() -> T + Send,
     {
         fork_compute(SandboxConfig::Disabled, log_buffer, f)
     }
@@ -153,4 +260,47 @@ mod tests {
             "expected panic error, got: {err:#}"
         );
     }
+
+    #[test]
+    fn fork_map_keeps_input_order() {
+        let log_buf = LogBuffer::new(16);
+        let items: Vec<u64> = (1..=5).collect();
+        let results = fork_map(&items, 2, &log_buf, |n| n * 10);
+        let values: Vec<u64> = results.into_iter().map(Result::unwrap).collect();
+        assert_eq!(values, vec![10, 20, 30, 40, 50]);
+    }
+
+    #[test]
+    fn fork_map_refills_slots_while_a_slow_item_runs() {
+        let log_buf = LogBuffer::new(16);
+        let now_ms = || {
+            std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap()
+                .as_millis() as u64
+        };
+        // Item 0 reports when it finished, the others when they started.
+        let results = fork_map(&[1000u64, 0, 0, 0], 2, &log_buf, |&sleep_ms| {
+            let start = now_ms();
+            std::thread::sleep(std::time::Duration::from_millis(sleep_ms));
+            if sleep_ms > 0 { now_ms() } else { start }
+        });
+        let times: Vec<u64> = results.into_iter().map(Result::unwrap).collect();
+        assert!(
+            times[3] < times[0],
+            "last item waited for the slow one: {times:?}"
+        );
+    }
+
+    #[test]
+    fn fork_map_isolates_panics() {
+        let log_buf = LogBuffer::new(16);
+        let results = fork_map(&[1u8, 0, 2], 3, &log_buf, |&n| {
+            assert!(n != 0, "deliberate test panic");
+            n
+        });
+        assert_eq!(results[0].as_ref().unwrap(), &1);
+        assert!(results[1].is_err());
+        assert_eq!(results[2].as_ref().unwrap(), &2);
+    }
 }
@@ -3,7 +3,7 @@ use std::path::{Path, PathBuf};
 use anyhow::Result;
 
 /// Find git repository root by walking up from `start` looking for `.git`.
-fn find_git_root(start: &Path) -> Option<PathBuf> {
+pub(crate) fn find_git_root(start: &Path) -> Option<PathBuf> {
     let mut dir = start;
     loop {
         if dir.join(".git").exists() {
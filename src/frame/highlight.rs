@@ -250,7 +250,13 @@ impl GlyphRun {
     }
 }
 
-/// 2048×24 semi-transparent yellow PNG (RGBA 255, 220, 0, 80).
+/// Highlight color of non-active matches (straight alpha).
+pub const HIGHLIGHT_RGBA: [u8; 4] = [255, 220, 0, 80];
+
+/// Highlight color of the active match (straight alpha).
+pub const HIGHLIGHT_ACTIVE_RGBA: [u8; 4] = [255, 140, 0, 120];
+
+/// 2048×24 semi-transparent yellow PNG ([`HIGHLIGHT_RGBA`]).
 ///
 /// Dimensions are chosen to match 12pt text at 144 PPI (= 24 px height).
 /// Width is large enough for any highlight span. KGP source-rectangle `w`
@@ -258,7 +264,7 @@ impl GlyphRun {
 /// text line height, so KGP placement with `r=1` requires minimal scaling.
 pub const HIGHLIGHT_PNG: &[u8] = include_bytes!("../../assets/highlight.png");
 
-/// 2048×24 semi-transparent orange PNG (RGBA 255, 140, 0, 120) for the active match.
+/// 2048×24 semi-transparent orange PNG ([`HIGHLIGHT_ACTIVE_RGBA`]) for the active match.
 pub const HIGHLIGHT_ACTIVE_PNG: &[u8] = include_bytes!("../../assets/highlight_active.png");
 
 /// Native width of [`HIGHLIGHT_PNG`] in pixels.
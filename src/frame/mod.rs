@@ -1,16 +1,23 @@
 mod highlight;
 mod render_png;
+mod render_svg;
+mod stitch;
 mod tile;
 mod tile_cache;
 mod visual_line;
 
 pub use highlight::{
-    HIGHLIGHT_ACTIVE_PNG, HIGHLIGHT_PNG, HIGHLIGHT_PNG_HEIGHT, HIGHLIGHT_PNG_WIDTH, HighlightRect,
-    HighlightSpec, PATTERN_ACTIVE_P25, PATTERN_ACTIVE_P50, PATTERN_ACTIVE_P75, PATTERN_HEIGHT,
-    PATTERN_P25, PATTERN_P50, PATTERN_P75, PATTERN_WIDTH, PartialPattern, find_highlight_rects,
-    select_overflow_pattern,
+    HIGHLIGHT_ACTIVE_PNG, HIGHLIGHT_ACTIVE_RGBA, HIGHLIGHT_PNG, HIGHLIGHT_PNG_HEIGHT,
+    HIGHLIGHT_PNG_WIDTH, HIGHLIGHT_RGBA, HighlightRect, HighlightSpec, PATTERN_ACTIVE_P25,
+    PATTERN_ACTIVE_P50, PATTERN_ACTIVE_P75, PATTERN_HEIGHT, PATTERN_P25, PATTERN_P50, PATTERN_P75,
+    PATTERN_WIDTH, PartialPattern, find_highlight_rects, select_overflow_pattern,
 };
 pub use render_png::render_frame_to_png;
+pub(crate) use render_svg::escape_xml;
+pub use render_svg::render_frame_to_svg;
+#[cfg(test)]
+pub(crate) use stitch::solid_png;
+pub use stitch::{Rgba, decode_rgba, encode_png, group_by_height, stitch_tiles};
 pub use tile::{
     ContentMapping, DocumentMeta, TileHash, TilePngs, TiledDocument, VisibleTiles,
     compute_tile_pair_hash, split_frame,
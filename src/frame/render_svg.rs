@@ -0,0 +1,149 @@
+//! SVG export of rendered frames.
+//!
+//! typst-svg draws text as glyph outlines, so fonts are self-contained but the
+//! text is not selectable. An invisible `<text>` layer is added on top, one
+//! element per `TextItem` placed on its baseline and stretched to its width,
+//! so browsers can select, copy and search the text.
+
+use std::fmt::Write;
+
+use typst::foundations::Smart;
+use typst::layout::{Frame, FrameItem, Page, Transform};
+use typst::text::TextItem;
+use typst::visualize::Paint;
+
+/// Render a single Frame to a standalone SVG document.
+///
+/// Images and diagrams are embedded as data URLs by typst-svg.
+pub fn render_frame_to_svg(frame: &Frame, fill: &Smart<Option<Paint>>) -> String {
+    let page = Page {
+        frame: frame.clone(),
+        fill: fill.clone(),
+        numbering: None,
+        supplement: typst::foundations::Content::empty(),
+        number: 0,
+    };
+    let svg = typst_svg::svg(&page);
+
+    let mut layer = String::new();
+    collect_text(frame, Transform::identity(), &mut layer);
+    if layer.is_empty() {
+        return svg;
+    }
+    let Some(end) = svg.rfind("</svg>") else {
+        return svg;
+    };
+    format!(
+        "{}<g class=\"mlux-text\" fill-opacity=\"0\" style=\"white-space: pre\">{layer}</g>{}",
+        &svg[..end],
+        &svg[end..]
+    )
+}
+
+/// Recursively walk the frame tree, emitting a `<text>` element per TextItem.
+///
+/// `ts` accumulates item positions and group transforms the way typst-svg
+/// does, so the layer stays on the glyphs of scaled or rotated groups.
+fn collect_text(frame: &Frame, ts: Transform, out: &mut String) {
+    for (pos, item) in frame.items() {
+        let ts = ts.pre_concat(Transform::translate(pos.x, pos.y));
+        match item {
+            FrameItem::Text(text) => push_text(text, ts, out),
+            FrameItem::Group(group) => {
+                collect_text(&group.frame, ts.pre_concat(group.transform), out)
+            }
+            _ => {}
+        }
+    }
+}
+
+fn push_text(text: &TextItem, ts: Transform, out: &mut String) {
+    if text.text.trim().is_empty() {
+        return;
+    }
+    let width: f64 = text
+        .glyphs
+        .iter()
+        .map(|g| g.x_advance.at(text.size).to_pt())
+        .sum();
+    // Plain translations keep the short `x`/`y` form.
+    if Transform::translate(ts.tx, ts.ty) == ts {
+        let _ = write!(
+            out,
+            "<text x=\"{:.2}\" y=\"{:.2}\"",
+            ts.tx.to_pt(),
+            ts.ty.to_pt()
+        );
+    } else {
+        let _ = write!(
+            out,
+            "<text transform=\"matrix({} {} {} {} {:.2} {:.2})\"",
+            ts.sx.get(),
+            ts.ky.get(),
+            ts.kx.get(),
+            ts.sy.get(),
+            ts.tx.to_pt(),
+            ts.ty.to_pt(),
+        );
+    }
+    let _ = write!(
+        out,
+        " font-size=\"{:.2}\" textLength=\"{width:.2}\" \
+         lengthAdjust=\"spacingAndGlyphs\">{}</text>",
+        text.size.to_pt(),
+        escape_xml(&text.text),
+    );
+}
+
+/// Escape `s` for use in XML/HTML text and double-quoted attribute values.
+pub(crate) fn escape_xml(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn escape_xml_special_chars() {
+        assert_eq!(escape_xml("a<b & \"c\">"), "a&lt;b &amp; &quot;c&quot;&gt;");
+    }
+
+    #[test]
+    fn empty_frame_has_no_text_layer() {
+        let frame = Frame::soft(typst::layout::Size::new(
+            typst::layout::Abs::pt(100.0),
+            typst::layout::Abs::pt(50.0),
+        ));
+        let svg = render_frame_to_svg(&frame, &Smart::Auto);
+        assert!(svg.starts_with("<svg"));
+        assert!(!svg.contains("mlux-text"));
+    }
+
+    #[test]
+    fn text_layer_follows_group_transforms() {
+        let fonts: &'static crate::compile::FontCache =
+            Box::leak(Box::new(crate::compile::FontCache::new()));
+        let source = "#set page(width: 200pt, height: auto, margin: 10pt)\n\
+                      Plain\n\n#scale(x: 200%, y: 200%, reflow: true)[Big]\n";
+        let world = crate::compile::MluxWorld::new_raw(source, fonts);
+        let document = crate::compile::compile_document(&world).unwrap();
+        let svg = render_frame_to_svg(&document.pages[0].frame, &Smart::Auto);
+        let layer = &svg[svg.find("mlux-text").unwrap()..];
+        assert!(layer.contains("<text x=\""), "{layer}");
+        assert!(
+            layer.contains("<text transform=\"matrix(2 0 0 2 "),
+            "{layer}"
+        );
+    }
+}
@@ -0,0 +1,178 @@
+//! Stitching rendered tiles into one tall PNG (`mlux render --single`).
+
+use std::io::Cursor;
+use std::ops::Range;
+
+use anyhow::{Context, Result, bail};
+
+use super::tile::TilePngs;
+
+/// A decoded RGBA8 image.
+pub struct Rgba {
+    pub width: u32,
+    pub height: u32,
+    pub data: Vec<u8>,
+}
+
+/// Decode a tile PNG (always RGBA8) into raw pixels.
+pub fn decode_rgba(png_bytes: &[u8]) -> Result<Rgba> {
+    let decoder = png::Decoder::new(Cursor::new(png_bytes));
+    let mut reader = decoder
+        .read_info()
+        .context("failed to read tile PNG header")?;
+    let Some(size) = reader.output_buffer_size() else {
+        bail!("tile PNG is too large to decode");
+    };
+    let mut data = vec![0; size];
+    let info = reader
+        .next_frame(&mut data)
+        .context("failed to decode tile PNG")?;
+    if info.color_type != png::ColorType::Rgba || info.bit_depth != png::BitDepth::Eight {
+        bail!(
+            "[BUG] unexpected tile PNG format: {:?} {:?}",
+            info.color_type,
+            info.bit_depth
+        );
+    }
+    data.truncate(info.buffer_size());
+    Ok(Rgba {
+        width: info.width,
+        height: info.height,
+        data,
+    })
+}
+
+/// Stack tiles vertically into one PNG, with each sidebar tile to the left of
+/// its content tile when `with_sidebar` is set.
+pub fn stitch_tiles(tiles: &[TilePngs], with_sidebar: bool) -> Result<Vec<u8>> {
+    let mut rows = Vec::with_capacity(tiles.len());
+    for tile in tiles {
+        let content = decode_rgba(&tile.content)?;
+        let sidebar = if with_sidebar {
+            Some(decode_rgba(&tile.sidebar)?)
+        } else {
+            None
+        };
+        rows.push((sidebar, content));
+    }
+
+    let sidebar_width = rows
+        .iter()
+        .filter_map(|(s, _)| s.as_ref().map(|s| s.width))
+        .max()
+        .unwrap_or(0);
+    let content_width = rows.iter().map(|(_, c)| c.width).max().unwrap_or(0);
+    let width = sidebar_width + content_width;
+    let height: u32 = rows.iter().map(|(_, c)| c.height).sum();
+    if width == 0 || height == 0 {
+        bail!("no tiles to stitch");
+    }
+
+    let stride = width as usize * 4;
+    let mut canvas = vec![0u8; stride * height as usize];
+    let mut y0 = 0usize;
+    for (sidebar, content) in &rows {
+        if let Some(sidebar) = sidebar {
+            blit(&mut canvas, stride, sidebar, 0, y0);
+        }
+        blit(&mut canvas, stride, content, sidebar_width as usize, y0);
+        y0 += content.height as usize;
+    }
+
+    encode_png(width, height, &canvas)
+}
+
+/// Encode an RGBA8 buffer as PNG (fast compression).
+pub fn encode_png(width: u32, height: u32, rgba: &[u8]) -> Result<Vec<u8>> {
+    let mut out = Vec::new();
+    {
+        let mut encoder = png::Encoder::new(&mut out, width, height);
+        encoder.set_color(png::ColorType::Rgba);
+        encoder.set_depth(png::BitDepth::Eight);
+        encoder.set_compression(png::Compression::Fast);
+        let mut writer = encoder
+            .write_header()
+            .map_err(|e| anyhow::anyhow!("[BUG] PNG header write failed: {e}"))?;
+        writer
+            .write_image_data(rgba)
+            .map_err(|e| anyhow::anyhow!("[BUG] PNG encoding failed: {e}"))?;
+    }
+    Ok(out)
+}
+
+/// Encode a `width`x`height` PNG filled with a single `rgba` color (test-only).
+#[cfg(test)]
+pub(crate) fn solid_png(width: u32, height: u32, rgba: [u8; 4]) -> Vec<u8> {
+    let data: Vec<u8> = rgba
+        .iter()
+        .copied()
+        .cycle()
+        .take((width * height * 4) as usize)
+        .collect();
+    encode_png(width, height, &data).unwrap()
+}
+
+/// Copy `img` into `canvas` with its top-left corner at `(x0, y0)`.
+fn blit(canvas: &mut [u8], stride: usize, img: &Rgba, x0: usize, y0: usize) {
+    let row_bytes = img.width as usize * 4;
+    for (y, row) in img.data.chunks_exact(row_bytes).enumerate() {
+        let start = (y0 + y) * stride + x0 * 4;
+        canvas[start..start + row_bytes].copy_from_slice(row);
+    }
+}
+
+/// Split consecutive tiles into groups whose total height stays within
+/// `max_height`. A tile taller than the limit gets a group of its own.
+pub fn group_by_height(heights: &[u32], max_height: u32) -> Vec<Range<usize>> {
+    let mut groups = Vec::new();
+    let mut start = 0;
+    let mut acc = 0u32;
+    for (i, &h) in heights.iter().enumerate() {
+        if i > start && acc + h > max_height {
+            groups.push(start..i);
+            start = i;
+            acc = 0;
+        }
+        acc += h;
+    }
+    if start < heights.len() {
+        groups.push(start..heights.len());
+    }
+    groups
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tile(height: u32) -> TilePngs {
+        TilePngs {
+            content: solid_png(4, height, [255, 0, 0, 255]),
+            sidebar: solid_png(2, height, [0, 0, 255, 255]),
+        }
+    }
+
+    #[test]
+    fn stitch_stacks_tiles_vertically() {
+        let png = stitch_tiles(&[tile(3), tile(2)], false).unwrap();
+        let img = decode_rgba(&png).unwrap();
+        assert_eq!((img.width, img.height), (4, 5));
+    }
+
+    #[test]
+    fn stitch_puts_sidebar_on_the_left() {
+        let png = stitch_tiles(&[tile(3)], true).unwrap();
+        let img = decode_rgba(&png).unwrap();
+        assert_eq!((img.width, img.height), (6, 3));
+        assert_eq!(&img.data[0..4], &[0, 0, 255, 255]);
+        assert_eq!(&img.data[8..12], &[255, 0, 0, 255]);
+    }
+
+    #[test]
+    fn group_by_height_respects_limit() {
+        assert_eq!(group_by_height(&[10, 10, 10], 30), vec![0..3]);
+        assert_eq!(group_by_height(&[10, 10, 10], 25), vec![0..2, 2..3]);
+        assert_eq!(group_by_height(&[40, 10], 25), vec![0..1, 1..2]);
+        assert!(group_by_height(&[], 25).is_empty());
+    }
+}
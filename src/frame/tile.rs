@@ -331,7 +331,7 @@ impl DocumentMeta {
     }
 
     /// Actual pixel height of a specific tile (last tile may be shorter).
-    fn tile_actual_height_px(&self, idx: usize) -> u32 {
+    pub fn tile_actual_height_px(&self, idx: usize) -> u32 {
         if idx + 1 < self.tile_count {
             self.tile_height_px
         } else {
@@ -0,0 +1,369 @@
+//! Standalone HTML export (`mlux render --format html`).
+//!
+//! Content tiles are inlined as PNG data URLs and stacked into one page. On
+//! top sits an invisible text layer: one absolutely positioned line per
+//! [`VisualLine`], whose text is rebuilt from the searchable spans of the
+//! [`ContentIndex`]. Browsers can then find, select and copy the text, and
+//! links found by [`DocumentQuery::extract_urls`] are wrapped in `<a>` so
+//! they stay clickable.
+
+use std::fmt::Write;
+use std::ops::Range;
+
+use base64::{Engine as _, engine::general_purpose::STANDARD as BASE64};
+
+use crate::compile::{ContentIndex, SpanKind};
+use crate::frame::{DocumentMeta, VisualLine, escape_xml};
+use crate::url::LinkTarget;
+use crate::viewer::query::{DocumentQuery, heading_anchors};
+
+/// CSS pixels per typst point.
+const CSS_PX_PER_PT: f64 = 96.0 / 72.0;
+
+/// Build a self-contained HTML document.
+///
+/// `tiles` are the content tile PNGs in order; `ppi` is the resolution they
+/// were rendered at, used to convert pixel sizes back to points.
+pub fn render_html(
+    title: &str,
+    markdown: &str,
+    meta: &DocumentMeta,
+    tiles: &[Vec<u8>],
+    ppi: f32,
+) -> String {
+    let px_to_css = |px: u32| px as f64 * 72.0 / ppi as f64 * CSS_PX_PER_PT;
+    let query = DocumentQuery::new(
+        markdown,
+        &meta.visual_lines,
+        &meta.content_index,
+        meta.content_offset,
+    );
+
+    // Heading lines get their slug as id so `#anchor` links resolve.
+    let mut ids: Vec<Option<String>> = vec![None; meta.visual_lines.len()];
+    for (slug, offset) in heading_anchors(markdown) {
+        if let Some(idx) = query.find_visual_line_by_offset(offset)
+            && ids[idx].is_none()
+        {
+            ids[idx] = Some(slug);
+        }
+    }
+
+    let texts = line_texts(markdown, &meta.visual_lines, &meta.content_index);
+
+    let mut html = String::new();
+    let _ = write!(
+        html,
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{}</title>\n\
+         <style>\n{STYLE}</style>\n</head>\n<body>\n<div class=\"page\" style=\"width: {:.1}px\">\n",
+        escape_xml(title),
+        px_to_css(meta.width_px),
+    );
+    for tile in tiles {
+        let _ = writeln!(
+            html,
+            "<img alt=\"\" src=\"data:image/png;base64,{}\">",
+            BASE64.encode(tile)
+        );
+    }
+    html.push_str("<div class=\"text\">\n");
+    for (idx, vl) in meta.visual_lines.iter().enumerate() {
+        let text = &texts[idx];
+        if text.is_empty() && ids[idx].is_none() {
+            continue;
+        }
+        let size_pt = line_font_size_pt(&meta.visual_lines, idx);
+        let top_pt = vl.y_pt - size_pt * 0.8;
+        html.push_str("<div");
+        if let Some(id) = &ids[idx] {
+            let _ = write!(html, " id=\"{}\"", escape_xml(id));
+        }
+        let _ = write!(
+            html,
+            " style=\"top: {:.1}px; font-size: {:.1}px\">",
+            top_pt * CSS_PX_PER_PT,
+            size_pt * CSS_PX_PER_PT,
+        );
+        let urls = query.extract_urls(idx);
+        let links: Vec<(&str, String)> = urls
+            .iter()
+            .filter_map(|u| Some((u.text.as_str(), link_href(&u.target)?)))
+            .collect();
+        push_linked_text(&mut html, text, &links);
+        html.push_str("</div>\n");
+    }
+    html.push_str("</div>\n</div>\n</body>\n</html>\n");
+    html
+}
+
+const STYLE: &str = "\
+body { margin: 0; background: #808080; }
+.page { position: relative; margin: 0 auto; }
+.page img { display: block; width: 100%; }
+.text { position: absolute; inset: 0; }
+.text div { position: absolute; left: 0; right: 0; line-height: 1; white-space: pre; \
+color: transparent; overflow: hidden; }
+.text a { color: transparent; }
+.text ::selection { background: rgba(64, 128, 255, 0.35); }
+";
+
+/// `href` for a link target. Footnotes have no visible link text to anchor
+/// to, so they are left out, as is anything [`is_safe_href`] rejects.
+fn link_href(target: &LinkTarget) -> Option<String> {
+    match target {
+        LinkTarget::ExternalUrl(url) | LinkTarget::LocalMarkdown(url) | LinkTarget::Anchor(url) => {
+            is_safe_href(url).then(|| url.clone())
+        }
+        LinkTarget::Footnote(_) => None,
+    }
+}
+
+/// Whether `url` may be emitted as an `href`: `http`, `https` and `mailto`
+/// URLs, relative paths and `#` fragments. Other schemes (`javascript:`,
+/// `data:`, …) could run script in the page embedding the export.
+fn is_safe_href(url: &str) -> bool {
+    // Browsers drop control characters and leading spaces before reading
+    // the scheme.
+    if url.chars().any(|c| c.is_control()) {
+        return false;
+    }
+    let url = url.trim_start();
+    let scheme_end =
+        url.find(|c: char| !(c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '.')));
+    match scheme_end.map(|i| (&url[..i], &url[i..])) {
+        Some((scheme, rest)) if rest.starts_with(':') && !scheme.is_empty() => {
+            ["http", "https", "mailto"]
+                .iter()
+                .any(|s| scheme.eq_ignore_ascii_case(s))
+        }
+        _ => true,
+    }
+}
+
+/// Approximate font size of a visual line from the tighter of the baseline
+/// gaps to its neighbours (block spacing only widens one side), clamped to a
+/// plausible range.
+fn line_font_size_pt(lines: &[VisualLine], idx: usize) -> f64 {
+    let y = lines[idx].y_pt;
+    let next = lines.get(idx + 1).map(|l| l.y_pt - y);
+    let prev = idx.checked_sub(1).map(|i| y - lines[i].y_pt);
+    let gap = match (prev, next) {
+        (Some(p), Some(n)) => p.min(n),
+        (Some(g), None) | (None, Some(g)) => g,
+        (None, None) => 16.0,
+    };
+    (gap * 0.75).clamp(8.0, 24.0)
+}
+
+/// Rebuild the displayed text of every visual line.
+///
+/// A line covers the Markdown bytes from its `md_offset` up to the next
+/// line's offset (nested blocks start a new line), capped at its block end.
+/// Of those, only bytes inside searchable text spans are kept, which drops
+/// Markdown syntax.
+fn line_texts(markdown: &str, lines: &[VisualLine], index: &ContentIndex) -> Vec<String> {
+    lines
+        .iter()
+        .enumerate()
+        .map(|(idx, vl)| {
+            let (Some(start), Some(block)) = (vl.md_offset, &vl.md_block_range) else {
+                return String::new();
+            };
+            let end = lines[idx + 1..]
+                .iter()
+                .filter_map(|next| next.md_offset)
+                .find(|&o| o > start)
+                .map_or(block.end, |o| o.min(block.end));
+            span_text(markdown, index, start..end)
+        })
+        .collect()
+}
+
+/// Concatenate the searchable text of all spans overlapping `range`.
+fn span_text(markdown: &str, index: &ContentIndex, range: Range<usize>) -> String {
+    let mut pieces: Vec<(usize, &str)> = Vec::new();
+    for span in index.text_spans() {
+        let start = span.md_range.start.max(range.start);
+        let end = span.md_range.end.min(range.end);
+        if start >= end {
+            continue;
+        }
+        match span.kind {
+            SpanKind::Plain | SpanKind::Math => {
+                if let Some(s) = markdown.get(start..end) {
+                    pieces.push((start, s));
+                }
+            }
+            // Inline code spans include their backtick delimiters.
+            SpanKind::Code => {
+                if let Some(s) = markdown.get(start..end) {
+                    pieces.push((start, s.trim_matches('`')));
+                }
+            }
+            SpanKind::Break => pieces.push((start, " ")),
+            SpanKind::Opaque => {}
+        }
+    }
+    pieces.sort_by_key(|&(start, _)| start);
+    let mut text = String::new();
+    for (_, s) in pieces {
+        text.push_str(&s.replace('\n', " "));
+    }
+    text.trim().to_string()
+}
+
+/// Write `text` as HTML, wrapping the first occurrence of each link text.
+fn push_linked_text(html: &mut String, text: &str, links: &[(&str, String)]) {
+    let mut found: Vec<(Range<usize>, &str)> = Vec::new();
+    for (label, href) in links {
+        if label.is_empty() {
+            continue;
+        }
+        let mut from = 0;
+        while let Some(i) = text[from..].find(label) {
+            let r = from + i..from + i + label.len();
+            if found
+                .iter()
+                .all(|(f, _)| f.end <= r.start || r.end <= f.start)
+            {
+                found.push((r, href));
+                break;
+            }
+            from = r.end;
+        }
+    }
+    found.sort_by_key(|(r, _)| r.start);
+
+    let mut pos = 0;
+    for (r, href) in found {
+        html.push_str(&escape_xml(&text[pos..r.start]));
+        let _ = write!(
+            html,
+            "<a href=\"{}\">{}</a>",
+            escape_xml(href),
+            escape_xml(&text[r.clone()])
+        );
+        pos = r.end;
+    }
+    html.push_str(&escape_xml(&text[pos..]));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::compile::TextSpan;
+
+    fn vl(y_pt: f64, block: Range<usize>, offset: usize) -> VisualLine {
+        VisualLine {
+            y_pt,
+            y_px: 0,
+            md_block_range: Some(block),
+            md_offset: Some(offset),
+            diff_status: None,
+        }
+    }
+
+    fn plain(md_range: Range<usize>) -> TextSpan {
+        TextSpan {
+            typst_range: 0..0,
+            md_range,
+            kind: SpanKind::Plain,
+        }
+    }
+
+    #[test]
+    fn line_texts_split_block_at_next_offset() {
+        let md = "Hello **big** world\n";
+        let index = ContentIndex::new(vec![plain(0..6), plain(8..11), plain(13..19)], vec![]);
+        let lines = vec![vl(10.0, 0..20, 0), vl(24.0, 0..20, 13)];
+        assert_eq!(line_texts(md, &lines, &index), vec!["Hello big", "world"]);
+    }
+
+    #[test]
+    fn line_texts_nested_block_and_inline_code() {
+        let md = "- a `b`\n  - c\n";
+        let code = TextSpan {
+            typst_range: 0..0,
+            md_range: 4..7,
+            kind: SpanKind::Code,
+        };
+        let index = ContentIndex::new(vec![plain(2..4), code, plain(12..13)], vec![]);
+        let lines = vec![vl(10.0, 0..14, 2), vl(24.0, 10..14, 12)];
+        assert_eq!(line_texts(md, &lines, &index), vec!["a b", "c"]);
+    }
+
+    #[test]
+    fn line_texts_skip_unmapped_lines() {
+        let lines = vec![VisualLine {
+            y_pt: 10.0,
+            y_px: 0,
+            md_block_range: None,
+            md_offset: None,
+            diff_status: None,
+        }];
+        let index = ContentIndex::new(vec![], vec![]);
+        assert_eq!(line_texts("- x\n", &lines, &index), vec![String::new()]);
+    }
+
+    #[test]
+    fn linked_text_wraps_first_occurrence_and_escapes() {
+        let mut html = String::new();
+        push_linked_text(
+            &mut html,
+            "see docs & docs",
+            &[("docs", "https://x.test/?a=1&b=2".to_string())],
+        );
+        assert_eq!(
+            html,
+            "see <a href=\"https://x.test/?a=1&amp;b=2\">docs</a> &amp; docs"
+        );
+    }
+
+    #[test]
+    fn script_links_stay_plain_text() {
+        for url in [
+            "javascript:alert(document.cookie)",
+            "JavaScript:alert(1)",
+            " javascript:alert(1)",
+            "java\tscript:alert(1)",
+            "data:text/html,x",
+            "vbscript:x.md",
+        ] {
+            assert_eq!(link_href(&LinkTarget::classify(url)), None, "{url}");
+        }
+        let mut html = String::new();
+        let links: Vec<(&str, String)> = [("here", "javascript:alert(document.cookie)")]
+            .iter()
+            .filter_map(|&(text, url)| Some((text, link_href(&LinkTarget::classify(url))?)))
+            .collect();
+        push_linked_text(&mut html, "click here", &links);
+        assert_eq!(html, "click here");
+    }
+
+    #[test]
+    fn safe_links_keep_their_href() {
+        for url in [
+            "https://x.test/",
+            "HTTP://x.test/",
+            "mailto:a@x.test",
+            "#usage",
+            "guide.md#install",
+            "docs/a:b.png",
+            "//x.test/a",
+        ] {
+            assert_eq!(
+                link_href(&LinkTarget::classify(url)).as_deref(),
+                Some(url),
+                "{url}"
+            );
+        }
+    }
+
+    #[test]
+    fn font_size_follows_line_gap() {
+        let lines = vec![vl(10.0, 0..1, 0), vl(26.0, 0..1, 0), vl(60.0, 0..1, 0)];
+        assert_eq!(line_font_size_pt(&lines, 0), 12.0);
+        assert_eq!(line_font_size_pt(&lines, 1), 12.0);
+        assert_eq!(line_font_size_pt(&lines, 2), 24.0);
+    }
+}
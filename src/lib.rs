@@ -1,9 +1,12 @@
 pub mod app_context;
+pub mod check;
 pub mod compile;
 pub mod config;
 pub mod diff;
+pub mod excerpt;
 pub mod fork_sandbox;
 pub mod frame;
+pub mod html_export;
 pub mod input_source;
 pub mod log;
 pub mod pipeline;
@@ -1,6 +1,6 @@
 use std::fs;
 use std::path::PathBuf;
-use std::time::Instant;
+use std::time::{Duration, Instant};
 
 use anyhow::{Context, Result};
 use clap::{Parser, Subcommand};
@@ -45,7 +45,6 @@ struct Cli {
     command: Option<Command>,
 
     /// Input Markdown file (for view mode; use `-` for stdin)
-    #[arg(global = true)]
     input: Option<PathBuf>,
 
     /// Theme name (loaded from themes/{name}.typ)
@@ -91,9 +90,17 @@ struct Cli {
     #[arg(long, value_enum, global = true)]
     exp_preset: Option<ExpPresetArg>,
 
+    /// Terminal image protocol for the viewer (`auto` probes the terminal)
+    #[arg(long, value_enum, global = true)]
+    graphics: Option<GraphicsArg>,
+
     /// Enable mouse wheel input in the viewer (scroll: wheel; zoom: Ctrl+wheel).
     #[arg(long, global = true)]
     mouse: bool,
+
+    /// Config file path (default: $XDG_CONFIG_HOME/mlux/config.toml)
+    #[arg(long, global = true)]
+    config: Option<PathBuf>,
 }
 
 /// CLI-local mirror of [`mlux::config::ScrollMode`] — carries the clap
@@ -132,6 +139,30 @@ impl From<ScrollAnimationArg> for mlux::config::ScrollAnimation {
     }
 }
 
+/// CLI-local mirror of [`mlux::config::GraphicsMode`].
+#[derive(clap::ValueEnum, Clone, Copy, Debug)]
+enum GraphicsArg {
+    Auto,
+    Kitty,
+    KittyPlaceholder,
+    Sixel,
+    Iterm2,
+    Text,
+}
+
+impl From<GraphicsArg> for mlux::config::GraphicsMode {
+    fn from(v: GraphicsArg) -> Self {
+        match v {
+            GraphicsArg::Auto => Self::Auto,
+            GraphicsArg::Kitty => Self::Kitty,
+            GraphicsArg::KittyPlaceholder => Self::KittyPlaceholder,
+            GraphicsArg::Sixel => Self::Sixel,
+            GraphicsArg::Iterm2 => Self::Iterm2,
+            GraphicsArg::Text => Self::Text,
+        }
+    }
+}
+
 /// CLI-local mirror of [`mlux::config::ExpPreset`].
 #[derive(clap::ValueEnum, Clone, Copy, Debug)]
 enum ExpPresetArg {
@@ -146,17 +177,83 @@ impl From<ExpPresetArg> for mlux::config::ExpPreset {
     }
 }
 
+/// Output format of `mlux render`.
+#[derive(clap::ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+enum RenderFormat {
+    /// One PNG per tile (`output-000.png`, ...)
+    Png,
+    /// Paginated PDF
+    Pdf,
+    /// One SVG per tile, or per page with `--paper` (`output-000.svg`, ...)
+    Svg,
+    /// Self-contained HTML page with a selectable text layer
+    Html,
+}
+
+/// Output format of `mlux check`.
+#[derive(clap::ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+enum CheckFormat {
+    /// `file:line: severity[kind]: message`, one issue per line
+    Human,
+    /// A JSON array of issues
+    Json,
+}
+
+/// CLI-local mirror of [`mlux::pipeline::Paper`].
+#[derive(clap::ValueEnum, Clone, Copy, Debug)]
+enum PaperArg {
+    A4,
+    Letter,
+}
+
+impl From<PaperArg> for mlux::pipeline::Paper {
+    fn from(v: PaperArg) -> Self {
+        match v {
+            PaperArg::A4 => Self::A4,
+            PaperArg::Letter => Self::Letter,
+        }
+    }
+}
+
+// Parsed once at startup; boxing the render flags buys nothing.
+#[allow(clippy::large_enum_variant)]
 #[derive(Subcommand)]
 enum Command {
-    /// Render Markdown to PNG
+    /// Render Markdown to PNG, PDF, SVG or HTML
     Render {
-        /// Input Markdown file (use `-` for stdin)
-        input: PathBuf,
+        /// Input Markdown files, directories or glob patterns (use `-` for stdin)
+        #[arg(required = true)]
+        inputs: Vec<PathBuf>,
 
-        /// Output PNG file
-        #[arg(short, long, default_value = "output.png")]
+        /// Output file (the extension follows `--format`)
+        #[arg(short, long, default_value = "output.png", conflicts_with = "out_dir")]
         output: PathBuf,
 
+        /// Render every input into this directory, mirroring their layout
+        #[arg(long)]
+        out_dir: Option<PathBuf>,
+
+        /// Files rendered in parallel with `--out-dir` [default: CPU count]
+        #[arg(short, long)]
+        jobs: Option<usize>,
+
+        /// Output format
+        #[arg(long, value_enum, default_value_t = RenderFormat::Png)]
+        format: RenderFormat,
+
+        /// Paper size for PDF output [default: a4]; with SVG, split into
+        /// pages of this size instead of tiles
+        #[arg(long, value_enum)]
+        paper: Option<PaperArg>,
+
+        /// Page margin in pt for paginated output
+        #[arg(long, default_value_t = 50.0)]
+        margin: f64,
+
+        /// Use the light theme on white pages (paginated output)
+        #[arg(long)]
+        print: bool,
+
         /// Page width in pt
         #[arg(long)]
         width: Option<f64>,
@@ -176,6 +273,50 @@ enum Command {
         /// Dump frame tree to stderr
         #[arg(long)]
         dump: bool,
+
+        /// Render only the section under this heading (text or anchor slug)
+        #[arg(long, value_name = "HEADING")]
+        section: Option<String>,
+
+        /// Render only these Markdown lines, widened to whole blocks
+        /// (e.g. `40:120`, `40:`, `:120`)
+        #[arg(long, value_name = "START:END", conflicts_with = "section")]
+        lines: Option<String>,
+
+        /// Stitch all PNG tiles into one tall image
+        #[arg(long)]
+        single: bool,
+
+        /// Include the line-number sidebar in `--single` output
+        #[arg(long, requires = "single")]
+        sidebar: bool,
+
+        /// Height limit in px for `--single`; taller output is split into
+        /// several stitched images
+        #[arg(long, default_value_t = 16384, requires = "single")]
+        max_height: u32,
+    },
+    /// Print the rendered document inline (Kitty graphics) and exit
+    Cat {
+        /// Input Markdown file (use `-` or a pipe for stdin)
+        input: Option<PathBuf>,
+    },
+    /// Check Markdown for broken links, images, diagrams, math and Typst
+    /// errors; exits non-zero if any error is found
+    Check {
+        /// Input Markdown files, directories or glob patterns (use `-` for stdin)
+        #[arg(required = true)]
+        inputs: Vec<PathBuf>,
+
+        /// Output format
+        #[arg(long, value_enum, default_value_t = CheckFormat::Human)]
+        format: CheckFormat,
+    },
+    /// Inspect configuration
+    Config {
+        /// Print the resolved effective config (defaults + config file + CLI flags)
+        #[arg(long)]
+        print: bool,
     },
 }
 
@@ -199,7 +340,9 @@ fn main() {
             scale,
             ..
         }) => (*width, *ppi, *tile_height, *scale),
-        None => (None, None, None, None),
+        Some(Command::Cat { .. } | Command::Check { .. } | Command::Config { .. }) | None => {
+            (None, None, None, None)
+        }
     };
 
     // Build CliOverrides
@@ -214,13 +357,56 @@ fn main() {
         scroll_animation: cli.scroll_animation.map(Into::into),
         exp_preset: cli.exp_preset.map(Into::into),
         mouse: cli.mouse,
+        graphics: cli.graphics.map(Into::into),
+    };
+
+    // Batch renders resolve project config from the working directory.
+    let command_input_path = match &cli.command {
+        Some(Command::Render {
+            inputs, out_dir, ..
+        }) if inputs.len() == 1 && out_dir.is_none() => Some(inputs[0].clone()),
+        Some(Command::Cat { input }) => input.clone(),
+        Some(Command::Check { inputs, .. }) if inputs.len() == 1 => Some(inputs[0].clone()),
+        _ => None,
+    };
+    let input_path = cli.input.clone().or(command_input_path);
+
+    // Project config (.mlux.toml) is searched from the document's directory;
+    // stdin input (or no input, e.g. `mlux config`) uses the working directory.
+    let project_dir = match input_path.as_deref() {
+        Some(p) if p.as_os_str() != "-" => p
+            .canonicalize()
+            .ok()
+            .and_then(|p| p.parent().map(|d| d.to_path_buf())),
+        _ => std::env::current_dir().ok(),
+    };
+    let (config, config_files) = match config::resolve(
+        cli.config.as_deref(),
+        project_dir.as_deref(),
+        &cli_overrides,
+    ) {
+        Ok(v) => v,
+        Err(e) => {
+            eprintln!("Error: {e:#}");
+            std::process::exit(1);
+        }
     };
 
-    let mut config = config::Config::default();
-    config.apply_cli(&cli_overrides);
+    if let Some(Command::Config { print }) = &cli.command {
+        if *print {
+            print!("{}", config.to_toml());
+        } else {
+            match config_files.as_slice() {
+                [] => println!("no config file loaded"),
+                files => files.iter().for_each(|p| println!("{}", p.display())),
+            }
+        }
+        return;
+    }
 
     // Theme detection: only when theme is "auto" and stdout is a TTY
-    let detected_light = if config.theme == "auto" {
+    let checking = matches!(cli.command, Some(Command::Check { .. }));
+    let detected_light = if config.theme == "auto" && !checking {
         use std::io::IsTerminal;
         if std::io::stdout().is_terminal() {
             let _raw = crossterm::terminal::enable_raw_mode();
@@ -235,24 +421,9 @@ fn main() {
         false
     };
 
-    // Build InputSource and read markdown
-    let render_input_path = cli
-        .command
-        .as_ref()
-        .map(|Command::Render { input, .. }| input.clone());
-    let mut input_source = build_input_source(cli.input.or(render_input_path));
-
-    let markdown = match input_source.read_all() {
-        Ok(md) => md,
-        Err(e) => {
-            eprintln!("Error: {e:#}");
-            std::process::exit(1);
-        }
-    };
-
     // Build AppContext: shared initialization for both modes.
     // Theme resolution is deferred to the build pipeline (prescan → CJK → theme).
-    let app = match AppContextBuilder::new(config, cli_overrides)
+    let build_app = move || match AppContextBuilder::new(config, cli_overrides)
         .load_fonts()
         .set_detected_light(detected_light)
         .build()
@@ -264,35 +435,84 @@ fn main() {
         }
     };
 
-    let base_dir = match &input_source {
-        InputSource::File(path) => path.parent().map(|d| d.to_path_buf()),
-        InputSource::Stdin(_) => None,
-    };
-    let file_path = match &input_source {
-        InputSource::File(path) => Some(path.clone()),
-        InputSource::Stdin(_) => None,
-    };
-
-    let result = match cli.command {
-        Some(Command::Render { output, dump, .. }) => cmd_render(
-            app,
-            &input_source,
-            markdown,
-            base_dir,
-            file_path,
+    let result = if let Some(
+        command @ Command::Render {
+            inputs,
             output,
-            dump,
-            cli.no_sandbox,
-            &log_buffer,
-        ),
-        None => mlux::viewer::run(
-            app,
+            out_dir,
+            jobs,
+            ..
+        },
+    ) = &cli.command
+    {
+        let opts = match RenderOptions::from_command(command, cli.no_sandbox) {
+            Ok(opts) => opts,
+            Err(e) => {
+                eprintln!("Error: {e:#}");
+                std::process::exit(1);
+            }
+        };
+        if out_dir.is_some() || inputs.len() > 1 {
+            let Some(out_dir) = out_dir else {
+                eprintln!("Error: rendering several files requires --out-dir");
+                std::process::exit(1);
+            };
+            if cli.watch {
+                eprintln!("Error: --watch renders a single file");
+                std::process::exit(1);
+            }
+            cmd_render_batch(&build_app(), &opts, inputs, out_dir, *jobs, &log_buffer)
+        } else {
+            let mut input_source = build_input_source(input_path);
+            let markdown = match input_source.read_all() {
+                Ok(md) => md,
+                Err(e) => {
+                    eprintln!("Error: {e:#}");
+                    std::process::exit(1);
+                }
+            };
+            let doc = RenderDoc::new(&input_source, markdown);
+            if cli.watch {
+                cmd_render_watch(&build_app(), &opts, doc, output, &log_buffer)
+            } else {
+                render_one(&build_app(), &opts, &doc, output, &log_buffer)
+                    .map(|rendered| report_rendered(&doc, &rendered))
+            }
+        }
+    } else if let Some(Command::Check { inputs, format }) = &cli.command {
+        cmd_check(&build_app(), inputs, *format, cli.no_sandbox, &log_buffer)
+    } else if let Some(Command::Cat { .. }) = &cli.command {
+        let mut input_source = build_input_source(input_path);
+        match input_source.read_all() {
+            Ok(markdown) => mlux::viewer::cat(
+                &build_app(),
+                &input_source,
+                markdown,
+                cli.no_sandbox,
+                &log_buffer,
+            ),
+            Err(e) => Err(e),
+        }
+    } else {
+        // Build InputSource and read markdown
+        let mut input_source = build_input_source(input_path);
+
+        let markdown = match input_source.read_all() {
+            Ok(md) => md,
+            Err(e) => {
+                eprintln!("Error: {e:#}");
+                std::process::exit(1);
+            }
+        };
+
+        mlux::viewer::run(
+            build_app(),
             input_source,
             markdown,
             cli.watch,
             cli.no_sandbox,
             log_buffer,
-        ),
+        )
     };
 
     if let Err(e) = result {
@@ -326,81 +546,669 @@ fn build_input_source(input: Option<PathBuf>) -> InputSource {
     }
 }
 
-#[allow(clippy::too_many_arguments)]
-fn cmd_render(
-    app: AppContext,
-    input: &InputSource,
+/// `--single` options for PNG output.
+#[derive(Clone, Copy)]
+struct Stitch {
+    sidebar: bool,
+    max_height: u32,
+}
+
+/// `mlux render` settings shared by every input file.
+struct RenderOptions {
+    format: RenderFormat,
+    paper: Option<mlux::pipeline::Paper>,
+    margin_pt: f64,
+    print: bool,
+    stitch: Option<Stitch>,
+    excerpt: Option<mlux::excerpt::Excerpt>,
+    dump: bool,
+    no_sandbox: bool,
+}
+
+impl RenderOptions {
+    fn from_command(command: &Command, no_sandbox: bool) -> Result<Self> {
+        let Command::Render {
+            format,
+            paper,
+            margin,
+            print,
+            dump,
+            section,
+            lines,
+            single,
+            sidebar,
+            max_height,
+            ..
+        } = command
+        else {
+            unreachable!("render options from a non-render command");
+        };
+        let excerpt = match (section, lines) {
+            (Some(name), _) => Some(mlux::excerpt::Excerpt::Section(name.clone())),
+            (None, Some(spec)) => Some(mlux::excerpt::Excerpt::parse_lines(spec)?),
+            (None, None) => None,
+        };
+        Ok(Self {
+            format: *format,
+            paper: paper.map(Into::into),
+            margin_pt: *margin,
+            print: *print,
+            stitch: single.then_some(Stitch {
+                sidebar: *sidebar,
+                max_height: *max_height,
+            }),
+            excerpt,
+            dump: *dump,
+            no_sandbox,
+        })
+    }
+
+    /// Page layout: PDF is always paginated (A4 by default), SVG only with
+    /// an explicit `--paper`.
+    fn page_setup(&self) -> Option<mlux::pipeline::PageSetup> {
+        let paper = match self.format {
+            RenderFormat::Pdf => Some(self.paper.unwrap_or(mlux::pipeline::Paper::A4)),
+            _ => self.paper,
+        };
+        paper.map(|paper| mlux::pipeline::PageSetup {
+            paper,
+            margin_pt: self.margin_pt,
+            print: self.print,
+        })
+    }
+
+    fn extension(&self) -> &'static str {
+        match self.format {
+            RenderFormat::Png => "png",
+            RenderFormat::Pdf => "pdf",
+            RenderFormat::Svg => "svg",
+            RenderFormat::Html => "html",
+        }
+    }
+}
+
+/// One input document of `mlux render`.
+struct RenderDoc {
+    name: String,
     markdown: String,
     base_dir: Option<PathBuf>,
     file_path: Option<PathBuf>,
-    output: PathBuf,
-    dump: bool,
-    no_sandbox: bool,
+}
+
+impl RenderDoc {
+    fn new(input: &InputSource, markdown: String) -> Self {
+        let file_path = match input {
+            InputSource::File(path) => Some(path.clone()),
+            InputSource::Stdin(_) => None,
+        };
+        Self {
+            name: input.display_name().to_string(),
+            markdown,
+            base_dir: file_path
+                .as_ref()
+                .and_then(|p| p.parent().map(|d| d.to_path_buf())),
+            file_path,
+        }
+    }
+}
+
+/// What a render wrote: a short description and each file with its size.
+struct Rendered {
+    summary: String,
+    files: Vec<(PathBuf, usize)>,
+}
+
+/// Render one document to `output` in the requested format.
+fn render_one(
+    app: &AppContext,
+    opts: &RenderOptions,
+    doc: &RenderDoc,
+    output: &std::path::Path,
     log_buffer: &mlux::log::LogBuffer,
-) -> Result<()> {
+) -> Result<Rendered> {
     let pipeline_start = Instant::now();
 
-    if markdown.trim().is_empty() {
+    if doc.markdown.trim().is_empty() {
         anyhow::bail!("input file is empty or contains only whitespace");
     }
 
-    let params = app.build_params(
-        markdown.clone(),
-        base_dir,
-        file_path,
+    let mut params = app.build_params(
+        doc.markdown.clone(),
+        doc.base_dir.clone(),
+        doc.file_path.clone(),
         app.config.width,
         DEFAULT_SIDEBAR_WIDTH_PT,
         app.config.viewer.tile_height,
         false,
     );
+    if opts.print {
+        params.theme_spec = "light".to_string();
+    }
+    if let Some(excerpt) = &opts.excerpt {
+        // Headings and block boundaries come from a build of the whole file.
+        let (meta, renderer, _child) =
+            mlux::renderer::build_renderer_blocking(&params, opts.no_sandbox, log_buffer)?;
+        renderer.shutdown();
+        params.markdown = mlux::excerpt::extract(&doc.markdown, excerpt, &meta)?;
+    }
 
-    if dump {
-        let mut child = mlux::renderer::build_dump(&params, no_sandbox, log_buffer)?;
+    if opts.dump {
+        let mut child = mlux::renderer::build_dump(&params, opts.no_sandbox, log_buffer)?;
         let code = child.wait()?;
         if code != 0 {
             anyhow::bail!("dump failed (child exited with code {code})");
         }
-        return Ok(());
+        return Ok(Rendered {
+            summary: "frame dump".to_string(),
+            files: Vec::new(),
+        });
     }
 
+    let output = output.with_extension(opts.extension());
     let output_parent = output.parent().unwrap_or_else(|| std::path::Path::new("."));
     fs::create_dir_all(output_parent).ok();
 
-    let stem = output
-        .file_stem()
-        .unwrap_or_default()
-        .to_string_lossy()
-        .to_string();
-    let ext = output
-        .extension()
-        .unwrap_or_default()
-        .to_string_lossy()
-        .to_string();
+    let rendered = match opts.format {
+        RenderFormat::Png => render_png(&params, opts, &output, log_buffer)?,
+        RenderFormat::Pdf => {
+            let page = opts.page_setup().expect("PDF output is always paginated");
+            let pdf = mlux::renderer::build_pdf(&params, &page, opts.no_sandbox, log_buffer)?;
+            write_output(&output, &pdf.bytes)?;
+            Rendered {
+                summary: format!("{} page(s)", pdf.page_count),
+                files: vec![(output.clone(), pdf.bytes.len())],
+            }
+        }
+        RenderFormat::Svg => {
+            let page = opts.page_setup();
+            let svgs =
+                mlux::renderer::build_svg(&params, page.as_ref(), opts.no_sandbox, log_buffer)?;
+            let mut files = Vec::new();
+            for (i, svg) in svgs.iter().enumerate() {
+                let path = numbered_path(&output, i);
+                write_output(&path, svg.as_bytes())?;
+                files.push((path, svg.len()));
+            }
+            let unit = if page.is_some() { "page" } else { "tile" };
+            Rendered {
+                summary: format!("{} {unit}(s)", svgs.len()),
+                files,
+            }
+        }
+        RenderFormat::Html => {
+            let (meta, mut renderer, mut _child) =
+                mlux::renderer::build_renderer_blocking(&params, opts.no_sandbox, log_buffer)?;
+            let mut tiles = Vec::with_capacity(meta.tile_count);
+            for i in 0..meta.tile_count {
+                tiles.push(renderer.render_tile_pair(i)?.content);
+            }
+            renderer.shutdown();
 
+            let title = mlux::compile::front_matter(&doc.markdown)
+                .and_then(|f| f.title)
+                .unwrap_or_else(|| doc.name.clone());
+            let html =
+                mlux::html_export::render_html(&title, &params.markdown, &meta, &tiles, params.ppi);
+            write_output(&output, html.as_bytes())?;
+            Rendered {
+                summary: format!("{} tile(s)", meta.tile_count),
+                files: vec![(output.clone(), html.len())],
+            }
+        }
+    };
+
+    info!(
+        "render_one: total pipeline completed in {:.1}ms",
+        pipeline_start.elapsed().as_secs_f64() * 1000.0
+    );
+    Ok(rendered)
+}
+
+/// PNG output: one file per tile, or stitched with `--single`.
+fn render_png(
+    params: &mlux::pipeline::BuildParams,
+    opts: &RenderOptions,
+    output: &std::path::Path,
+    log_buffer: &mlux::log::LogBuffer,
+) -> Result<Rendered> {
     let (meta, mut renderer, mut _child) =
-        mlux::renderer::build_renderer_blocking(&params, no_sandbox, log_buffer)?;
+        mlux::renderer::build_renderer_blocking(params, opts.no_sandbox, log_buffer)?;
 
     let mut files = Vec::new();
-    for i in 0..meta.tile_count {
-        let pngs = renderer.render_tile_pair(i)?;
-        let filename = format!("{}-{:03}.{}", stem, i, ext);
-        let path = output_parent.join(&filename);
-        fs::write(&path, &pngs.content)
-            .with_context(|| format!("failed to write {}", path.display()))?;
-        files.push((filename, pngs.content.len()));
+    if let Some(stitch) = opts.stitch {
+        let mut tiles = Vec::with_capacity(meta.tile_count);
+        for i in 0..meta.tile_count {
+            tiles.push(renderer.render_tile_pair(i)?);
+        }
+        renderer.shutdown();
+
+        let heights: Vec<u32> = (0..meta.tile_count)
+            .map(|i| meta.tile_actual_height_px(i))
+            .collect();
+        let groups = mlux::frame::group_by_height(&heights, stitch.max_height);
+        if groups.len() > 1 {
+            eprintln!(
+                "note: {}px exceeds --max-height {}px, splitting into {} images",
+                meta.total_height_px,
+                stitch.max_height,
+                groups.len()
+            );
+        }
+        for (n, group) in groups.iter().enumerate() {
+            let png = mlux::frame::stitch_tiles(&tiles[group.clone()], stitch.sidebar)?;
+            let path = if groups.len() == 1 {
+                output.to_path_buf()
+            } else {
+                numbered_path(output, n)
+            };
+            write_output(&path, &png)?;
+            files.push((path, png.len()));
+        }
+    } else {
+        for i in 0..meta.tile_count {
+            let pngs = renderer.render_tile_pair(i)?;
+            let path = numbered_path(output, i);
+            write_output(&path, &pngs.content)?;
+            files.push((path, pngs.content.len()));
+        }
+        renderer.shutdown();
     }
-    renderer.shutdown();
 
-    info!(
-        "cmd_render: total pipeline completed in {:.1}ms",
-        pipeline_start.elapsed().as_secs_f64() * 1000.0
+    Ok(Rendered {
+        summary: format!("{} tile(s) in {} file(s)", meta.tile_count, files.len()),
+        files,
+    })
+}
+
+/// `dir/name.ext` → `dir/name-007.ext`.
+fn numbered_path(output: &std::path::Path, n: usize) -> PathBuf {
+    let stem = output.file_stem().unwrap_or_default().to_string_lossy();
+    let ext = output.extension().unwrap_or_default().to_string_lossy();
+    output.with_file_name(format!("{stem}-{n:03}.{ext}"))
+}
+
+/// Write via a temporary file in the same directory and rename it into
+/// place, so a viewer reloading `path` never sees a partial file.
+fn write_output(path: &std::path::Path, bytes: &[u8]) -> Result<()> {
+    let name = path.file_name().unwrap_or_default().to_string_lossy();
+    let tmp = path.with_file_name(format!(".{name}.{}.tmp", std::process::id()));
+    fs::write(&tmp, bytes)
+        .and_then(|()| fs::rename(&tmp, path))
+        .with_context(|| format!("failed to write {}", path.display()))
+        .inspect_err(|_| {
+            let _ = fs::remove_file(&tmp);
+        })
+}
+
+fn report_rendered(doc: &RenderDoc, rendered: &Rendered) {
+    eprintln!("rendered {} -> {}:", doc.name, rendered.summary);
+    for (path, size) in &rendered.files {
+        eprintln!("  {} ({} bytes)", path.display(), size);
+    }
+}
+
+/// `mlux render --watch`: render, then re-render whenever the input or one
+/// of its local images changes. Render errors are reported and the previous
+/// output is left in place until the next successful render.
+fn cmd_render_watch(
+    app: &AppContext,
+    opts: &RenderOptions,
+    mut doc: RenderDoc,
+    output: &std::path::Path,
+    log_buffer: &mlux::log::LogBuffer,
+) -> Result<()> {
+    let Some(file_path) = doc.file_path.clone() else {
+        anyhow::bail!("--watch needs a file input, not stdin");
+    };
+    let mut written: Vec<PathBuf> = Vec::new();
+    // Watch from before the first render so saves made while rendering
+    // trigger another pass.
+    let mut watched = vec![file_path.clone()];
+    let mut watcher = mlux::watch::FileWatcher::new_multi(&watched)?;
+    loop {
+        let start = Instant::now();
+        match render_one(app, opts, &doc, output, log_buffer) {
+            Ok(rendered) => {
+                // Numbered outputs beyond the new count would otherwise linger.
+                for stale in written
+                    .iter()
+                    .filter(|p| !rendered.files.iter().any(|(f, _)| f == *p))
+                {
+                    let _ = fs::remove_file(stale);
+                }
+                written = rendered.files.iter().map(|(p, _)| p.clone()).collect();
+                eprintln!(
+                    "[{:.1}s] rendered {} -> {}",
+                    start.elapsed().as_secs_f64(),
+                    doc.name,
+                    rendered.summary
+                );
+            }
+            Err(e) => eprintln!("Error: {e:#}"),
+        }
+
+        // Follow the images this version references.
+        let mut paths = vec![file_path.clone()];
+        paths.extend(local_image_paths(&doc.markdown, doc.base_dir.as_deref()));
+        let mut changed = false;
+        if paths != watched {
+            let refreshed = mlux::watch::FileWatcher::new_multi(&paths)?;
+            // Changes the old watcher saw during the render still count.
+            changed = watcher.has_changed();
+            watcher = refreshed;
+            watched = paths;
+        }
+        eprintln!(
+            "watching {} file(s) for changes (Ctrl-C to stop)",
+            watched.len()
+        );
+        doc.markdown = loop {
+            if !std::mem::take(&mut changed) {
+                while !watcher.wait(Duration::from_secs(3600)) {}
+            }
+            // Editors often save in several steps; wait for them to settle.
+            while watcher.wait(Duration::from_millis(100)) {}
+            match fs::read_to_string(&file_path) {
+                Ok(md) => break md,
+                Err(e) => eprintln!("Error: failed to read {}: {e}", file_path.display()),
+            }
+        };
+    }
+}
+
+/// One `mlux check` issue as printed with `--format json`.
+#[derive(serde::Serialize)]
+struct CheckReport<'a> {
+    file: &'a str,
+    #[serde(flatten)]
+    issue: &'a mlux::compile::Issue,
+}
+
+/// Check every input and print the issues found. Fails if any is an error.
+fn cmd_check(
+    app: &AppContext,
+    inputs: &[PathBuf],
+    format: CheckFormat,
+    no_sandbox: bool,
+    log_buffer: &mlux::log::LogBuffer,
+) -> Result<()> {
+    use mlux::compile::Severity;
+
+    let docs: Vec<(String, RenderDoc)> = if inputs.len() == 1 && inputs[0].as_os_str() == "-" {
+        let mut input = build_input_source(Some(inputs[0].clone()));
+        let markdown = input.read_all()?;
+        vec![("<stdin>".to_string(), RenderDoc::new(&input, markdown))]
+    } else {
+        let cwd = std::env::current_dir().unwrap_or_default();
+        expand_inputs(inputs)?
+            .into_iter()
+            .map(|path| {
+                let markdown = fs::read_to_string(&path)
+                    .with_context(|| format!("failed to read {}", path.display()))?;
+                let name = path
+                    .strip_prefix(&cwd)
+                    .unwrap_or(&path)
+                    .display()
+                    .to_string();
+                Ok((name, RenderDoc::new(&InputSource::File(path), markdown)))
+            })
+            .collect::<Result<_>>()?
+    };
+
+    let mut results = Vec::with_capacity(docs.len());
+    for (name, doc) in docs {
+        let params = app.build_params(
+            doc.markdown,
+            doc.base_dir,
+            doc.file_path,
+            app.config.width,
+            DEFAULT_SIDEBAR_WIDTH_PT,
+            app.config.viewer.tile_height,
+            false,
+        );
+        let issues = mlux::check::check(&params, no_sandbox, log_buffer)
+            .with_context(|| format!("failed to check {name}"))?;
+        results.push((name, issues));
+    }
+
+    match format {
+        CheckFormat::Human => {
+            for (name, issues) in &results {
+                for issue in issues {
+                    let location = match issue.line {
+                        Some(line) => format!("{name}:{line}"),
+                        None => name.clone(),
+                    };
+                    println!(
+                        "{location}: {}[{}]: {}",
+                        issue.severity, issue.kind, issue.message
+                    );
+                    for note in &issue.notes {
+                        println!("    {note}");
+                    }
+                }
+            }
+        }
+        CheckFormat::Json => {
+            let reports: Vec<CheckReport> = results
+                .iter()
+                .flat_map(|(name, issues)| {
+                    issues.iter().map(|issue| CheckReport { file: name, issue })
+                })
+                .collect();
+            println!("{}", serde_json::to_string_pretty(&reports)?);
+        }
+    }
+
+    let count = |severity| {
+        results
+            .iter()
+            .flat_map(|(_, issues)| issues)
+            .filter(|i| i.severity == severity)
+            .count()
+    };
+    let (errors, warnings) = (count(Severity::Error), count(Severity::Warning));
+    if errors > 0 {
+        anyhow::bail!(
+            "{errors} error(s), {warnings} warning(s) in {} file(s)",
+            results.len()
+        );
+    }
+    eprintln!(
+        "checked {} file(s): no errors, {warnings} warning(s)",
+        results.len()
     );
+    Ok(())
+}
+
+/// Existing local image files referenced by `markdown`.
+fn local_image_paths(markdown: &str, base_dir: Option<&std::path::Path>) -> Vec<PathBuf> {
+    let Some(base_dir) = base_dir else {
+        return Vec::new();
+    };
+    mlux::compile::prescan(markdown)
+        .image_paths
+        .iter()
+        .filter(|p| !p.contains("://") && !p.starts_with("data:"))
+        .map(|p| base_dir.join(p))
+        .filter(|p| p.is_file())
+        .collect()
+}
+
+/// Outcome of one file in a batch render, sent back from its worker.
+#[derive(serde::Serialize, serde::Deserialize)]
+struct BatchOutcome {
+    elapsed_ms: f64,
+    result: std::result::Result<String, String>,
+}
 
-    let input_name = input.display_name();
-    eprintln!("rendered {} -> {} tile(s):", input_name, meta.tile_count);
-    for (filename, size) in &files {
-        eprintln!("  {} ({} bytes)", filename, size);
+/// Render many files into `out_dir` in parallel forked workers, then print a
+/// summary table. Fails if any file failed.
+fn cmd_render_batch(
+    app: &AppContext,
+    opts: &RenderOptions,
+    inputs: &[PathBuf],
+    out_dir: &std::path::Path,
+    jobs: Option<usize>,
+    log_buffer: &mlux::log::LogBuffer,
+) -> Result<()> {
+    let batch_start = Instant::now();
+    let files = expand_inputs(inputs)?;
+    if files.is_empty() {
+        anyhow::bail!("no Markdown files to render");
     }
+    let base = common_dir(&files);
+    let jobs = jobs.unwrap_or_else(|| std::thread::available_parallelism().map_or(1, |n| n.get()));
 
+    let outcomes = mlux::fork_sandbox::fork_map(&files, jobs, log_buffer, |path| {
+        let start = Instant::now();
+        let result = render_batch_file(app, opts, path, &base, out_dir, log_buffer)
+            .map_err(|e| format!("{e:#}"));
+        BatchOutcome {
+            elapsed_ms: start.elapsed().as_secs_f64() * 1000.0,
+            result,
+        }
+    });
+
+    let names: Vec<String> = files
+        .iter()
+        .map(|f| f.strip_prefix(&base).unwrap_or(f).display().to_string())
+        .collect();
+    let name_width = names
+        .iter()
+        .map(|n| n.chars().count())
+        .max()
+        .unwrap_or(4)
+        .max(4);
+    eprintln!("{:<name_width$}  {:>9}  RESULT", "FILE", "TIME");
+    let mut failed = 0;
+    for (name, outcome) in names.iter().zip(outcomes) {
+        let (time, result) = match outcome {
+            Ok(BatchOutcome {
+                elapsed_ms,
+                result: Ok(summary),
+            }) => (format!("{elapsed_ms:.0}ms"), summary),
+            Ok(BatchOutcome {
+                elapsed_ms,
+                result: Err(e),
+            }) => {
+                failed += 1;
+                (format!("{elapsed_ms:.0}ms"), format!("error: {e}"))
+            }
+            Err(e) => {
+                failed += 1;
+                ("-".to_string(), format!("error: {e:#}"))
+            }
+        };
+        eprintln!("{name:<name_width$}  {time:>9}  {result}");
+    }
+    eprintln!(
+        "rendered {} of {} file(s) into {} in {:.1}s",
+        files.len() - failed,
+        files.len(),
+        out_dir.display(),
+        batch_start.elapsed().as_secs_f64()
+    );
+
+    if failed > 0 {
+        anyhow::bail!("{failed} file(s) failed to render");
+    }
+    Ok(())
+}
+
+/// Render one batch file to its mirrored location under `out_dir`.
+fn render_batch_file(
+    app: &AppContext,
+    opts: &RenderOptions,
+    path: &std::path::Path,
+    base: &std::path::Path,
+    out_dir: &std::path::Path,
+    log_buffer: &mlux::log::LogBuffer,
+) -> Result<String> {
+    let markdown =
+        fs::read_to_string(path).with_context(|| format!("failed to read {}", path.display()))?;
+    let doc = RenderDoc::new(&InputSource::File(path.to_path_buf()), markdown);
+    let output = out_dir.join(path.strip_prefix(base).unwrap_or(path));
+    Ok(render_one(app, opts, &doc, &output, log_buffer)?.summary)
+}
+
+/// Expand files, directories (recursively, `.md`/`.markdown` only) and glob
+/// patterns into canonical file paths, in order and without duplicates.
+fn expand_inputs(inputs: &[PathBuf]) -> Result<Vec<PathBuf>> {
+    let mut files = Vec::new();
+    for input in inputs {
+        let pattern = input.to_string_lossy();
+        if pattern == "-" {
+            anyhow::bail!("stdin input (`-`) must be the only input");
+        } else if input.is_dir() {
+            collect_markdown(input, &mut files)?;
+        } else if input.exists() {
+            files.push(input.clone());
+        } else if pattern.contains(['*', '?', '[']) {
+            let before = files.len();
+            for entry in
+                glob::glob(&pattern).with_context(|| format!("invalid glob pattern '{pattern}'"))?
+            {
+                let path = entry?;
+                if path.is_file() {
+                    files.push(path);
+                }
+            }
+            if files.len() == before {
+                anyhow::bail!("no files match '{pattern}'");
+            }
+        } else {
+            anyhow::bail!("{}: no such file or directory", input.display());
+        }
+    }
+    let mut seen = std::collections::HashSet::new();
+    let mut unique = Vec::with_capacity(files.len());
+    for file in files {
+        let canonical = file
+            .canonicalize()
+            .with_context(|| format!("failed to resolve {}", file.display()))?;
+        if seen.insert(canonical.clone()) {
+            unique.push(canonical);
+        }
+    }
+    Ok(unique)
+}
+
+fn collect_markdown(dir: &std::path::Path, files: &mut Vec<PathBuf>) -> Result<()> {
+    let mut entries: Vec<PathBuf> = fs::read_dir(dir)
+        .with_context(|| format!("failed to read {}", dir.display()))?
+        .filter_map(|e| e.ok().map(|e| e.path()))
+        .filter(|p| {
+            !p.file_name()
+                .is_some_and(|n| n.to_string_lossy().starts_with('.'))
+        })
+        .collect();
+    entries.sort();
+    for path in entries {
+        if path.is_dir() {
+            collect_markdown(&path, files)?;
+        } else if path
+            .extension()
+            .is_some_and(|e| e == "md" || e == "markdown")
+        {
+            files.push(path);
+        }
+    }
     Ok(())
 }
+
+/// Deepest directory containing every file.
+fn common_dir(files: &[PathBuf]) -> PathBuf {
+    let mut common = files[0]
+        .parent()
+        .map(|p| p.to_path_buf())
+        .unwrap_or_default();
+    for file in &files[1..] {
+        while !file.starts_with(&common) {
+            if !common.pop() {
+                break;
+            }
+        }
+    }
+    common
+}
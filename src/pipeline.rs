@@ -1,16 +1,21 @@
-use std::fmt::Write as _;
+use std::fmt::{self, Write as _};
 use std::path::PathBuf;
 use std::time::Instant;
 
 use anyhow::{Result, bail};
 use log::info;
+use serde::{Deserialize, Serialize};
 use typst::layout::PagedDocument;
 
 use crate::compile::{
-    BoundIndex, ContentIndex, FontCache, LoadedImages, MluxWorld, Prescan, compile_document,
-    dump_document, extract_diagrams, load_images, markdown_to_typst, prescan, render_diagrams,
+    BoundIndex, ContentIndex, DiagramError, FontCache, Issue, LoadedImages, MluxWorld, Prescan,
+    compile_document, dump_document, export_pdf, extract_diagrams, load_images, markdown_to_typst,
+    prescan, render_diagrams, try_compile_document, typst_issues,
+};
+use crate::frame::{
+    ContentMapping, TiledDocument, VisualLine, extract_visual_lines_with_map, render_frame_to_svg,
+    split_frame,
 };
-use crate::frame::{ContentMapping, TiledDocument, VisualLine, extract_visual_lines_with_map};
 
 /// Parameters for [`build_tiled_document`].
 #[derive(Clone)]
@@ -30,6 +35,88 @@ pub struct BuildParams {
     pub fast_png: bool,
 }
 
+/// Paper size for paginated output.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Paper {
+    A4,
+    Letter,
+}
+
+impl Paper {
+    /// `(width, height)` in pt.
+    pub fn size_pt(self) -> (f64, f64) {
+        match self {
+            Paper::A4 => (595.28, 841.89),
+            Paper::Letter => (612.0, 792.0),
+        }
+    }
+}
+
+/// Page layout for paginated output (PDF), replacing the theme's single
+/// auto-height page.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct PageSetup {
+    pub paper: Paper,
+    pub margin_pt: f64,
+    /// Print on a white page instead of the theme background.
+    pub print: bool,
+}
+
+impl PageSetup {
+    /// Typst `#set page(...)` rule appended after the theme.
+    ///
+    /// The width comes from the world's own width override (see
+    /// [`compile_content`]); only height, margin and fill are set here.
+    fn page_rule(&self) -> String {
+        let (_, height) = self.paper.size_pt();
+        let fill = if self.print { ", fill: white" } else { "" };
+        format!(
+            "#set page(height: {height}pt, margin: {}pt, numbering: \"1\"{fill})",
+            self.margin_pt
+        )
+    }
+}
+
+/// A finished PDF, as returned from the sandboxed child.
+#[derive(Serialize, Deserialize)]
+pub struct PdfOutput {
+    pub bytes: Vec<u8>,
+    pub page_count: usize,
+}
+
+/// The content document failed to compile. Each issue is located on its
+/// Markdown line where possible.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CompileError {
+    pub issues: Vec<Issue>,
+}
+
+impl fmt::Display for CompileError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // Errors we cannot trace to the input come from mlux's own markup.
+        if self.issues.iter().all(|i| i.line.is_none()) {
+            write!(f, "[BUG] ")?;
+        }
+        write!(
+            f,
+            "typst compilation failed — {} error(s):",
+            self.issues.len()
+        )?;
+        for issue in &self.issues {
+            match issue.line {
+                Some(line) => write!(f, "\nline {line}: {}", issue.message)?,
+                None => write!(f, "\n{}", issue.message)?,
+            }
+            for note in &issue.notes {
+                write!(f, "\n  {note}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for CompileError {}
+
 /// Result of the shared compilation pipeline (steps 1-4).
 struct CompiledContent {
     theme_name: String,
@@ -38,15 +125,51 @@ struct CompiledContent {
     content_index: ContentIndex,
 }
 
+/// A content world ready to compile (steps 1-3).
+pub(crate) struct PreparedContent {
+    pub(crate) theme_name: String,
+    pub(crate) world: MluxWorld,
+    pub(crate) content_index: ContentIndex,
+    /// Mermaid diagrams that failed to render.
+    pub(crate) diagram_errors: Vec<DiagramError>,
+}
+
 /// Shared build steps: theme resolution, diagram rendering,
 /// markdown→typst, world construction, and compilation.
 ///
-/// Prescan and image loading are the caller's responsibility.
+/// Prescan and image loading are the caller's responsibility. With `page`,
+/// the document is laid out on fixed-size pages instead of one tall page.
 fn compile_content(
     params: &BuildParams,
     prescan: &Prescan,
-    mut image_files: LoadedImages,
+    image_files: LoadedImages,
+    page: Option<&PageSetup>,
 ) -> Result<CompiledContent> {
+    let PreparedContent {
+        theme_name,
+        world,
+        content_index,
+        ..
+    } = prepare_content(params, prescan, image_files, page)?;
+    let document = try_compile_document(&world).map_err(|errors| CompileError {
+        issues: typst_issues(&errors, &world, &content_index, &params.markdown),
+    })?;
+
+    Ok(CompiledContent {
+        theme_name,
+        world,
+        document,
+        content_index,
+    })
+}
+
+/// [`compile_content`] up to (not including) the Typst compile.
+pub(crate) fn prepare_content(
+    params: &BuildParams,
+    prescan: &Prescan,
+    mut image_files: LoadedImages,
+    page: Option<&PageSetup>,
+) -> Result<PreparedContent> {
     // 0. Theme resolution (from prescan CJK detection)
     info!(
         "prescan: has_cjk={}, image_paths={}",
@@ -69,7 +192,8 @@ fn compile_content(
     // 1. Diagram pipeline
     let diagrams = extract_diagrams(&params.markdown);
     let mermaid_colors = crate::theme::mermaid_colors(theme_name);
-    for (key, svg) in render_diagrams(&diagrams, mermaid_colors) {
+    let (rendered, diagram_errors) = render_diagrams(&diagrams, mermaid_colors);
+    for (key, svg) in rendered {
         image_files.insert(key, svg);
     }
 
@@ -77,23 +201,29 @@ fn compile_content(
     let loaded_set = image_files.key_set();
     let (content_text, content_index) = markdown_to_typst(&params.markdown, Some(&loaded_set));
 
-    // 3. Compile content document
+    // 3. Build content world
+    let (theme_text, width_pt) = match page {
+        Some(page) => (
+            format!("{theme_text}\n{}", page.page_rule()),
+            page.paper.size_pt().0,
+        ),
+        None => (theme_text.to_string(), params.width_pt),
+    };
     let world = MluxWorld::new(
-        theme_text,
+        &theme_text,
         data_files,
         &content_text,
-        params.width_pt,
+        width_pt,
         params.scale,
         params.fonts,
         image_files,
     );
-    let document = compile_document(&world)?;
 
-    Ok(CompiledContent {
+    Ok(PreparedContent {
         theme_name: theme_name.to_string(),
         world,
-        document,
         content_index,
+        diagram_errors,
     })
 }
 
@@ -103,7 +233,7 @@ pub(crate) fn compile_and_dump(
     prescan: &Prescan,
     image_files: LoadedImages,
 ) -> Result<()> {
-    let compiled = compile_content(params, prescan, image_files)?;
+    let compiled = compile_content(params, prescan, image_files, None)?;
 
     // Print generated main.typ to stderr
     let source_text = compiled.world.main_source().text();
@@ -120,6 +250,51 @@ pub(crate) fn compile_and_dump(
     Ok(())
 }
 
+/// Compile from pre-loaded images into a paginated PDF.
+///
+/// Called from the forked child process in [`crate::renderer::build_pdf`].
+pub(crate) fn compile_pdf(
+    params: &BuildParams,
+    page: &PageSetup,
+    prescan: &Prescan,
+    image_files: LoadedImages,
+) -> Result<PdfOutput> {
+    let compiled = compile_content(params, prescan, image_files, Some(page))?;
+    let bytes = export_pdf(&compiled.document, &compiled.world)?;
+    Ok(PdfOutput {
+        bytes,
+        page_count: compiled.document.pages.len(),
+    })
+}
+
+/// Compile from pre-loaded images into SVG documents.
+///
+/// With `page`, one SVG per paper page; otherwise the single tall page is
+/// split into tiles of `params.tile_height_pt`, like PNG output.
+/// Called from the forked child process in [`crate::renderer::build_svg`].
+pub(crate) fn compile_svg(
+    params: &BuildParams,
+    page: Option<&PageSetup>,
+    prescan: &Prescan,
+    image_files: LoadedImages,
+) -> Result<Vec<String>> {
+    let compiled = compile_content(params, prescan, image_files, page)?;
+    let pages = &compiled.document.pages;
+    if page.is_some() {
+        return Ok(pages
+            .iter()
+            .map(|p| render_frame_to_svg(&p.frame, &p.fill))
+            .collect());
+    }
+    let Some(first) = pages.first() else {
+        bail!("[BUG] document has no pages");
+    };
+    Ok(split_frame(&first.frame, params.tile_height_pt)
+        .iter()
+        .map(|tile| render_frame_to_svg(tile, &first.fill))
+        .collect())
+}
+
 /// Build a TiledDocument from Markdown source (test-only convenience).
 ///
 /// Convenience wrapper that loads images internally then delegates to
@@ -156,7 +331,7 @@ pub(crate) fn compile_and_tile(
         world: content_world,
         document,
         content_index,
-    } = compile_content(params, prescan, image_files)?;
+    } = compile_content(params, prescan, image_files, None)?;
 
     // 4. Extract visual lines with source mapping
     let bound_index = BoundIndex::new(
@@ -293,3 +468,72 @@ pub fn generate_sidebar_typst(
 
     src
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn svg_params(markdown: &str) -> BuildParams {
+        BuildParams {
+            theme_spec: "catppuccin".into(),
+            detected_light: false,
+            markdown: markdown.into(),
+            base_dir: None,
+            file_path: None,
+            width_pt: 400.0,
+            sidebar_width_pt: 40.0,
+            tile_height_pt: 200.0,
+            ppi: 144.0,
+            scale: 1.0,
+            fonts: Box::leak(Box::new(FontCache::new())),
+            allow_remote_images: false,
+            fast_png: false,
+        }
+    }
+
+    #[test]
+    fn compile_svg_tiles_carry_text_layer() {
+        let md = "# Hello\n\nSome 1 < 2 & text.\n\n".repeat(8);
+        let params = svg_params(&md);
+        let ps = prescan(&params.markdown);
+        let svgs = compile_svg(&params, None, &ps, LoadedImages::default()).unwrap();
+        assert!(svgs.len() > 1, "tall document should split into tiles");
+        assert!(svgs[0].starts_with("<svg"));
+        assert!(svgs[0].contains(">Hello</text>"));
+        assert!(svgs[0].contains("1 &lt; 2 &amp; text."));
+    }
+
+    #[test]
+    fn compile_svg_one_per_page() {
+        let params = svg_params("# Hello\n");
+        let page = PageSetup {
+            paper: Paper::A4,
+            margin_pt: 50.0,
+            print: true,
+        };
+        let ps = prescan(&params.markdown);
+        let svgs = compile_svg(&params, Some(&page), &ps, LoadedImages::default()).unwrap();
+        assert_eq!(svgs.len(), 1);
+        assert!(svgs[0].contains("viewBox=\"0 0 595.28 841.89\""));
+    }
+
+    #[test]
+    fn page_rule_uses_paper_height_and_margin() {
+        let page = PageSetup {
+            paper: Paper::Letter,
+            margin_pt: 36.0,
+            print: false,
+        };
+        assert_eq!(
+            page.page_rule(),
+            "#set page(height: 792pt, margin: 36pt, numbering: \"1\")"
+        );
+        let print = PageSetup {
+            paper: Paper::A4,
+            margin_pt: 50.0,
+            print: true,
+        };
+        assert!(print.page_rule().contains("height: 841.89pt"));
+        assert!(print.page_rule().ends_with(", fill: white)"));
+    }
+}
@@ -5,13 +5,18 @@
 //! a [`TileRenderer`] or dump result — all fork/sandbox/IPC details are hidden.
 
 use anyhow::{Context, Result};
+use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
 
-use crate::compile::LoadedImages;
+use crate::check::check_compiled;
+use crate::compile::{Issue, LoadedImages, Prescan};
 use crate::fork_sandbox::{SandboxConfig, TypedReader, TypedWriter, fork_sandboxed};
 use crate::frame::{DocumentMeta, HighlightRect, HighlightSpec, TilePngs};
 use crate::log::{LogBuffer, LogEntry};
-use crate::pipeline::{BuildParams, compile_and_dump, compile_and_tile};
+use crate::pipeline::{
+    BuildParams, CompileError, PageSetup, PdfOutput, compile_and_dump, compile_and_tile,
+    compile_pdf, compile_svg,
+};
 
 pub use crate::fork_sandbox::ChildProcess;
 
@@ -29,11 +34,12 @@ enum Request {
 
 /// Response from child to parent.
 ///
-/// The first message is always `Meta`. Subsequent messages are `Tile`, `Rects`,
-/// or `Error`.
+/// The first message is always `Meta`, or `CompileFailed` if the document
+/// does not compile. Subsequent messages are `Tile`, `Rects`, or `Error`.
 #[derive(Serialize, Deserialize)]
 enum Response {
     Meta(DocumentMeta),
+    CompileFailed(CompileError),
     Tile {
         idx: usize,
         pngs: TilePngs,
@@ -86,6 +92,8 @@ impl TileRenderer {
             .context("failed to receive metadata from child")?
         {
             Response::Meta(m) => Ok(m),
+            // Typed, so the viewer can show it instead of exiting.
+            Response::CompileFailed(e) => Err(e.into()),
             Response::Error(e) => anyhow::bail!("child build error: {e}"),
             _ => anyhow::bail!("unexpected response, expected Meta"),
         }
@@ -119,6 +127,7 @@ impl TileRenderer {
             Response::Rects { idx, rects } => Ok(TileResponse::Rects { idx, rects }),
             Response::Error(e) => anyhow::bail!("{e}"),
             Response::Meta(_) => anyhow::bail!("unexpected Meta response"),
+            Response::CompileFailed(_) => anyhow::bail!("unexpected CompileFailed response"),
         }
     }
 
@@ -288,8 +297,11 @@ pub fn build_renderer(
                 Ok(doc) => doc,
                 Err(e) => {
                     log::error!("child: build failed: {e:#}");
-                    let _ =
-                        send_with_logs(&mut resp_tx, Response::Error(format!("{e:#}")), &log_buf);
+                    let resp = match e.downcast::<CompileError>() {
+                        Ok(e) => Response::CompileFailed(e),
+                        Err(e) => Response::Error(format!("{e:#}")),
+                    };
+                    let _ = send_with_logs(&mut resp_tx, resp, &log_buf);
                     return;
                 }
             };
@@ -365,6 +377,106 @@ pub fn build_renderer_blocking(
     Ok((meta, renderer, child))
 }
 
+/// Build a paginated PDF: prepare images (Fork 1) + compile and export (Fork 2).
+///
+/// The child runs under the same sandbox as [`build_renderer`] and returns
+/// the finished PDF; nothing is rendered to PNG.
+pub fn build_pdf(
+    params: &BuildParams,
+    page: &PageSetup,
+    no_sandbox: bool,
+    log_buffer: &LogBuffer,
+) -> Result<PdfOutput> {
+    let page = *page;
+    build_export(
+        params,
+        no_sandbox,
+        log_buffer,
+        move |params, prescan, images| compile_pdf(params, &page, prescan, images),
+    )
+}
+
+/// Build SVG documents: prepare images (Fork 1) + compile and export (Fork 2).
+///
+/// Returns one SVG per page when `page` is set, otherwise one per tile.
+pub fn build_svg(
+    params: &BuildParams,
+    page: Option<&PageSetup>,
+    no_sandbox: bool,
+    log_buffer: &LogBuffer,
+) -> Result<Vec<String>> {
+    let page = page.copied();
+    build_export(
+        params,
+        no_sandbox,
+        log_buffer,
+        move |params, prescan, images| compile_svg(params, page.as_ref(), prescan, images),
+    )
+}
+
+/// Shared one-shot export: the child loads images, runs `export` and sends
+/// back its result.
+fn build_export<T, F>(
+    params: &BuildParams,
+    no_sandbox: bool,
+    log_buffer: &LogBuffer,
+    export: F,
+) -> Result<T>
+where
+    T: Serialize + DeserializeOwned + Send,
+    F: FnOnce(&BuildParams, &Prescan, LoadedImages) -> Result<T> + Send,
+{
+    use crate::fork_sandbox::fork_compute;
+
+    let (prescan, remote_images) = prepare_remote_images(params, no_sandbox, log_buffer)?;
+    let sandbox = build_sandbox(params, no_sandbox);
+
+    let params = params.clone();
+
+    let result = fork_compute(sandbox, log_buffer, move || {
+        // Load local images (Landlock read scope allows git root)
+        let (mut images, errors) =
+            crate::compile::load_images(&prescan.image_paths, params.base_dir.as_deref(), false);
+        for err in &errors {
+            log::warn!("{err}");
+        }
+
+        // Merge pre-fetched remote images from parent
+        images.extend(remote_images);
+
+        export(&params, &prescan, images).map_err(|e| format!("{e:#}"))
+    })?;
+    result.map_err(|e| anyhow::anyhow!("child build error: {e}"))
+}
+
+/// Check a document: prepare images (Fork 1) + load images, compile and
+/// collect issues (Fork 2). See [`crate::check`].
+pub fn build_check(
+    params: &BuildParams,
+    no_sandbox: bool,
+    log_buffer: &LogBuffer,
+) -> Result<Vec<Issue>> {
+    use crate::fork_sandbox::fork_compute;
+
+    let (prescan, remote_images) = prepare_remote_images(params, no_sandbox, log_buffer)?;
+    let sandbox = build_sandbox(params, no_sandbox);
+
+    let params = params.clone();
+
+    let result = fork_compute(sandbox, log_buffer, move || {
+        // Load local images (Landlock read scope allows git root)
+        let (mut images, mut errors) =
+            crate::compile::load_images(&prescan.image_paths, params.base_dir.as_deref(), false);
+
+        // Merge pre-fetched remote images; they were rejected above.
+        images.extend(remote_images);
+        errors.retain(|e| images.get(e.path()).is_none());
+
+        check_compiled(&params, &prescan, images, &errors).map_err(|e| format!("{e:#}"))
+    })?;
+    result.map_err(|e| anyhow::anyhow!("child build error: {e}"))
+}
+
 /// Build and dump: prepare images (Fork 1) + fork dump (Fork 2).
 ///
 /// The child compiles the document and writes the generated Typst source
@@ -459,6 +571,29 @@ mod tests {
         }
     }
 
+    #[test]
+    fn response_compile_failed_serde_roundtrip() {
+        use crate::compile::{Issue, IssueKind, Severity};
+
+        let issue = Issue {
+            line: Some(7),
+            severity: Severity::Error,
+            kind: IssueKind::Typst,
+            message: "unclosed delimiter".into(),
+            notes: vec!["--> main.typ:173:4".into()],
+        };
+        let resp = Response::CompileFailed(CompileError {
+            issues: vec![issue.clone()],
+        });
+        let encoded = bincode::serde::encode_to_vec(&resp, bincode::config::standard()).unwrap();
+        let (decoded, _): (Response, _) =
+            bincode::serde::decode_from_slice(&encoded, bincode::config::standard()).unwrap();
+        match decoded {
+            Response::CompileFailed(e) => assert_eq!(e.issues, vec![issue]),
+            _ => panic!("wrong variant"),
+        }
+    }
+
     #[test]
     fn child_message_serde_roundtrip() {
         let msg = ChildMessage {
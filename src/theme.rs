@@ -23,6 +23,24 @@ pub struct MermaidColors {
     pub sequence_activation_border: &'static str,
 }
 
+/// Colours for the plain-text viewer (CSS hex), taken from the theme's
+/// Typst styles so text mode reads like the rendered document.
+pub struct TextColors {
+    pub text: &'static str,
+    /// Heading colours, h1 to h6.
+    pub headings: [&'static str; 6],
+    pub emph: &'static str,
+    /// Background of inline code and code blocks.
+    pub code_bg: &'static str,
+    /// Links, footnote references and the block quote bar.
+    pub link: &'static str,
+    /// Block quote text, also used for secondary text (image placeholders,
+    /// front matter details).
+    pub quote: &'static str,
+    /// Table and code block borders, horizontal rules.
+    pub border: &'static str,
+}
+
 /// A built-in theme entry with all required metadata.
 ///
 /// Adding a new theme requires filling every field; a missing field
@@ -37,6 +55,8 @@ pub struct ThemeEntry {
     pub sidebar_fg: &'static str,
     /// Mermaid diagram colour palette.
     pub mermaid: MermaidColors,
+    /// Plain-text viewer colours.
+    pub text: TextColors,
 }
 
 /// Mapping from base theme names to their latin variants.
@@ -76,6 +96,22 @@ pub const THEMES: &[ThemeEntry] = &[
             sequence_activation_fill: "#45475a",
             sequence_activation_border: "#585b70",
         },
+        text: TextColors {
+            text: "#cdd6f4", // Text
+            headings: [
+                "#cba6f7", // Mauve
+                "#f5c2e7", // Pink
+                "#b4befe", // Lavender
+                "#eba0ac", // Maroon
+                "#f9e2af", // Yellow
+                "#a6adc8", // Subtext0
+            ],
+            emph: "#94e2d5",    // Teal
+            code_bg: "#313244", // Surface0
+            link: "#89b4fa",    // Blue
+            quote: "#a6adc8",   // Subtext0
+            border: "#585b70",  // Surface2
+        },
     },
     ThemeEntry {
         name: "catppuccin-latte",
@@ -106,6 +142,22 @@ pub const THEMES: &[ThemeEntry] = &[
             sequence_activation_fill: "#E2E8F0",
             sequence_activation_border: "#94A3B8",
         },
+        text: TextColors {
+            text: "#4c4f69", // Text
+            headings: [
+                "#8839ef", // Mauve
+                "#ea76cb", // Pink
+                "#7287fd", // Lavender
+                "#e64553", // Maroon
+                "#df8e1d", // Yellow
+                "#5c5f77", // Subtext1
+            ],
+            emph: "#179299",    // Teal
+            code_bg: "#e6e9ef", // Mantle
+            link: "#1e66f5",    // Blue
+            quote: "#6c6f85",   // Subtext0
+            border: "#acb0be",  // Surface2
+        },
     },
     ThemeEntry {
         name: "catppuccin-latin",
@@ -136,6 +188,22 @@ pub const THEMES: &[ThemeEntry] = &[
             sequence_activation_fill: "#45475a",
             sequence_activation_border: "#585b70",
         },
+        text: TextColors {
+            text: "#cdd6f4", // Text
+            headings: [
+                "#cba6f7", // Mauve
+                "#f5c2e7", // Pink
+                "#b4befe", // Lavender
+                "#eba0ac", // Maroon
+                "#f9e2af", // Yellow
+                "#a6adc8", // Subtext0
+            ],
+            emph: "#94e2d5",    // Teal
+            code_bg: "#313244", // Surface0
+            link: "#89b4fa",    // Blue
+            quote: "#a6adc8",   // Subtext0
+            border: "#585b70",  // Surface2
+        },
     },
     ThemeEntry {
         name: "catppuccin-latte-latin",
@@ -166,6 +234,22 @@ pub const THEMES: &[ThemeEntry] = &[
             sequence_activation_fill: "#E2E8F0",
             sequence_activation_border: "#94A3B8",
         },
+        text: TextColors {
+            text: "#4c4f69", // Text
+            headings: [
+                "#8839ef", // Mauve
+                "#ea76cb", // Pink
+                "#7287fd", // Lavender
+                "#e64553", // Maroon
+                "#df8e1d", // Yellow
+                "#5c5f77", // Subtext1
+            ],
+            emph: "#179299",    // Teal
+            code_bg: "#e6e9ef", // Mantle
+            link: "#1e66f5",    // Blue
+            quote: "#6c6f85",   // Subtext0
+            border: "#acb0be",  // Surface2
+        },
     },
 ];
 
@@ -222,6 +306,11 @@ pub fn mermaid_colors(name: &str) -> &'static MermaidColors {
     find(name).map(|t| &t.mermaid).unwrap_or(&THEMES[0].mermaid)
 }
 
+/// Return plain-text viewer colours for a theme (falls back to catppuccin dark).
+pub fn text_colors(name: &str) -> &'static TextColors {
+    find(name).map(|t| &t.text).unwrap_or(&THEMES[0].text)
+}
+
 /// Check if a theme specifier is valid (alias or known theme name).
 ///
 /// Delegates to [`resolve_theme_name`] so that adding a new alias
@@ -287,6 +376,13 @@ mod tests {
         assert_eq!(mc.background, "#1e1e2e"); // falls back to catppuccin dark
     }
 
+    #[test]
+    fn text_colors_follow_theme() {
+        assert_eq!(text_colors("catppuccin").text, "#cdd6f4");
+        assert_eq!(text_colors("catppuccin-latte-latin").text, "#4c4f69");
+        assert_eq!(text_colors("nonexistent").text, "#cdd6f4");
+    }
+
     #[test]
     fn resolve_auto_dark() {
         assert_eq!(resolve_theme_name("auto", false, true), "catppuccin");
@@ -9,14 +9,21 @@ pub enum LinkTarget {
     ExternalUrl(String),
     /// Local markdown file — relative path as extracted, not yet resolved.
     LocalMarkdown(String),
+    /// Same-document anchor (`#section`), stored with the leading `#`.
+    Anchor(String),
+    /// Footnote reference (`[^label]`) — jump to its definition in the document.
+    Footnote(String),
 }
 
 impl LinkTarget {
-    /// Classify a URL string into external or local markdown.
+    /// Classify a URL string into external, local markdown, or anchor.
     pub fn classify(url: &str) -> Self {
         if url.contains("://") || url.starts_with("mailto:") {
             return Self::ExternalUrl(url.to_string());
         }
+        if url.starts_with('#') {
+            return Self::Anchor(url.to_string());
+        }
         let path_part = url.split('#').next().unwrap_or(url);
         if path_part.ends_with(".md") || path_part.ends_with(".markdown") {
             Self::LocalMarkdown(url.to_string())
@@ -25,10 +32,21 @@ impl LinkTarget {
         }
     }
 
-    /// Extract the inner URL string for display.
+    /// Extract the inner URL string (or footnote label) for display.
     pub fn display_url(&self) -> &str {
         match self {
-            Self::ExternalUrl(u) | Self::LocalMarkdown(u) => u,
+            Self::ExternalUrl(u) | Self::LocalMarkdown(u) | Self::Anchor(u) | Self::Footnote(u) => {
+                u
+            }
+        }
+    }
+
+    /// Status-bar message shown when the target is opened.
+    pub fn describe(&self) -> String {
+        match self {
+            Self::ExternalUrl(u) | Self::LocalMarkdown(u) => format!("Opening {u}"),
+            Self::Anchor(u) => format!("Jumping to {u}"),
+            Self::Footnote(label) => format!("Footnote [^{label}]"),
         }
     }
 }
@@ -43,6 +61,49 @@ pub fn resolve_link_path(url: &str, current_file: &Path) -> Option<PathBuf> {
     Some(base_dir.join(path_part))
 }
 
+/// The fragment of a link (`guide.md#setup` → `setup`), if non-empty.
+pub fn link_fragment(url: &str) -> Option<&str> {
+    url.split_once('#')
+        .map(|(_, fragment)| fragment)
+        .filter(|f| !f.is_empty())
+}
+
+/// GitHub-compatible anchor slug for a heading's plain text.
+///
+/// Lowercases, drops everything but letters, digits, `-`, `_` and spaces,
+/// then turns each space into `-`. Duplicate slugs within a document are
+/// disambiguated by the caller (`-1`, `-2`, …).
+pub fn heading_slug(text: &str) -> String {
+    text.trim()
+        .chars()
+        .filter(|c| c.is_alphanumeric() || matches!(c, '-' | '_' | ' '))
+        .flat_map(char::to_lowercase)
+        .map(|c| if c == ' ' { '-' } else { c })
+        .collect()
+}
+
+/// Decode `%XX` escapes in a link fragment (`%E6%97%A5` → `日`).
+///
+/// Sequences that don't form valid UTF-8 are kept as written.
+pub fn percent_decode(s: &str) -> String {
+    let bytes = s.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%'
+            && let Some(hex) = s.get(i + 1..i + 3)
+            && let Ok(b) = u8::from_str_radix(hex, 16)
+        {
+            out.push(b);
+            i += 3;
+        } else {
+            out.push(bytes[i]);
+            i += 1;
+        }
+    }
+    String::from_utf8(out).unwrap_or_else(|_| s.to_string())
+}
+
 /// Regex for bare URLs starting with `http://` or `https://`.
 ///
 /// Pattern approach inspired by John Gruber's "liberal URL regex":
@@ -168,6 +229,40 @@ mod tests {
         );
     }
 
+    #[test]
+    fn classify_anchor() {
+        assert_eq!(
+            LinkTarget::classify("#setup"),
+            LinkTarget::Anchor("#setup".into())
+        );
+    }
+
+    #[test]
+    fn link_fragment_extraction() {
+        assert_eq!(link_fragment("guide.md#setup"), Some("setup"));
+        assert_eq!(link_fragment("#top"), Some("top"));
+        assert_eq!(link_fragment("guide.md#"), None);
+        assert_eq!(link_fragment("guide.md"), None);
+    }
+
+    #[test]
+    fn heading_slug_github_rules() {
+        assert_eq!(heading_slug("Hello World"), "hello-world");
+        assert_eq!(heading_slug("What's new in v2.0?"), "whats-new-in-v20");
+        assert_eq!(heading_slug("foo_bar - baz"), "foo_bar---baz");
+        assert_eq!(heading_slug("  Trim me "), "trim-me");
+        assert_eq!(heading_slug("日本語 見出し"), "日本語-見出し");
+        assert_eq!(heading_slug("C++ & Rust"), "c--rust");
+    }
+
+    #[test]
+    fn percent_decode_fragment() {
+        assert_eq!(percent_decode("%E6%97%A5%E6%9C%AC"), "日本");
+        assert_eq!(percent_decode("a%20b"), "a b");
+        assert_eq!(percent_decode("100%"), "100%");
+        assert_eq!(percent_decode("%FF"), "%FF");
+    }
+
     #[test]
     fn classify_external() {
         assert_eq!(
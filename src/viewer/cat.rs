@@ -0,0 +1,115 @@
+//! `mlux cat`: print the rendered document into the terminal scrollback.
+//!
+//! The document is laid out at the terminal's pixel width with tiles aligned
+//! to whole cell rows, so tiles are shown 1:1 and stack without gaps. Each
+//! tile is sent with [`terminal::send_image`] and placed at the cursor,
+//! followed by one newline per cell row it covers. Every cell row is
+//! therefore a line on stdout: `mlux cat FILE | head -n 40` previews the
+//! first 40 rows.
+//!
+//! Inside tmux the tiles are sent through passthrough and shown as Unicode
+//! placeholder lines (see [`super::placeholder`]), so they stay in tmux's
+//! scrollback too. Tiles are then capped to the rows placeholders can
+//! address.
+
+use std::io;
+
+use crossterm::terminal as crossterm_terminal;
+use log::info;
+
+use super::transfer::{self, Transfer};
+use super::{graphics, layout, placeholder, terminal};
+use crate::app_context::AppContext;
+use crate::input_source::InputSource;
+
+/// Render `markdown` and print it inline, then return.
+pub fn cat(
+    app: &AppContext,
+    input: &InputSource,
+    markdown: String,
+    no_sandbox: bool,
+    log_buffer: &crate::log::LogBuffer,
+) -> anyhow::Result<()> {
+    let winsize = crossterm_terminal::window_size()
+        .map_err(|e| anyhow::anyhow!("failed to get terminal size: {e}"))?;
+    if winsize.width == 0 || winsize.height == 0 {
+        anyhow::bail!(
+            "terminal pixel size {}x{} is zero — Kitty graphics requires non-zero pixel dimensions",
+            winsize.width,
+            winsize.height
+        );
+    }
+    let layout = layout::compute_layout(
+        winsize.columns,
+        winsize.rows,
+        winsize.width,
+        winsize.height,
+        0,
+    );
+    let ppi = app.config.ppi as f64;
+    // The sidebar is not shown, but the pipeline always builds one.
+    let sidebar_width_pt =
+        app.config.viewer.sidebar_cols as f64 * layout.cell_w as f64 * 72.0 / ppi;
+
+    let file_path = match input {
+        InputSource::File(path) => Some(path.clone()),
+        InputSource::Stdin(_) => None,
+    };
+    let placeholders = graphics::in_tmux();
+    let mut tile_height_pt = layout.align_tile_height_pt(app.config.viewer.tile_height, ppi);
+    if placeholders {
+        // A placeholder placement addresses at most MAX_INDEX + 1 rows, so
+        // taller tiles would lose their bottom.
+        tile_height_pt = tile_height_pt.min(layout.rows_height_pt(placeholder::MAX_INDEX + 1, ppi));
+    }
+    let params = app.build_params(
+        markdown,
+        file_path
+            .as_ref()
+            .and_then(|p| p.parent().map(|d| d.to_path_buf())),
+        file_path,
+        layout.viewport_width_pt(ppi),
+        sidebar_width_pt,
+        tile_height_pt,
+        false,
+    );
+    let (meta, mut renderer, mut _child) =
+        crate::renderer::build_renderer_blocking(&params, no_sandbox, log_buffer)?;
+    info!(
+        "cat: {} tile(s) at {}x{} cells/px {}x{}",
+        meta.tile_count, layout.image_cols, layout.image_rows, layout.cell_w, layout.cell_h
+    );
+
+    terminal::set_tmux_passthrough(placeholders);
+    // The probe reads the reply from the tty, which needs raw mode.
+    crossterm_terminal::enable_raw_mode()?;
+    let transfer = Transfer::resolve(app.config.viewer.transfer);
+    crossterm_terminal::disable_raw_mode()?;
+    transfer::set(transfer);
+
+    // Images stay referenced from the scrollback, so their ids must not be
+    // reused by a later `mlux cat`; derive them from the pid.
+    let id_base = std::process::id().wrapping_mul(1024);
+    let cell_h = layout.cell_h as u32;
+    for idx in 0..meta.tile_count {
+        let png = renderer.render_tile_pair(idx)?.content;
+        let height = meta.tile_actual_height_px(idx);
+        let rows = height.div_ceil(cell_h).max(1) as u16;
+        let id = id_base.wrapping_add(idx as u32 + 1).max(1);
+        let shown = terminal::send_image(&png, id).and_then(|()| {
+            if placeholders {
+                terminal::place_inline_placeholders(id, layout.image_cols, rows)
+            } else {
+                terminal::place_inline(id, rows)
+            }
+        });
+        match shown {
+            Ok(()) => {}
+            // The reader (e.g. `head`) has seen enough.
+            Err(e) if e.kind() == io::ErrorKind::BrokenPipe => break,
+            Err(e) => return Err(e.into()),
+        }
+    }
+    renderer.shutdown();
+    Ok(())
+}
@@ -0,0 +1,273 @@
+//! Viewport compositing for protocols that cannot place uploaded images.
+//!
+//! Kitty keeps tiles in the terminal and moves placements around. Sixel (and
+//! any other protocol that only paints pixels) instead needs one bitmap of
+//! the whole viewport: the sidebar slice on the left, the content slice to
+//! its right, and search highlight rects blended on top — the same picture
+//! Kitty assembles from separate placements.
+
+use std::collections::HashMap;
+
+use crate::frame::{
+    HIGHLIGHT_ACTIVE_RGBA, HIGHLIGHT_RGBA, HighlightRect, Rgba, TileCache, VisibleTiles,
+    decode_rgba,
+};
+
+/// A composited viewport bitmap (RGBA8, row-major).
+pub(super) struct Canvas {
+    pub width: u32,
+    pub height: u32,
+    pub data: Vec<u8>,
+}
+
+/// One vertical slice of a tile shown on screen.
+struct Segment {
+    idx: usize,
+    src_y: u32,
+    src_h: u32,
+    dst_y: u32,
+}
+
+fn segments(visible: &VisibleTiles) -> Vec<Segment> {
+    match *visible {
+        VisibleTiles::Single { idx, src_y, src_h } => vec![Segment {
+            idx,
+            src_y,
+            src_h,
+            dst_y: 0,
+        }],
+        VisibleTiles::Split {
+            top_idx,
+            top_src_y,
+            top_src_h,
+            bot_idx,
+            bot_src_h,
+        } => vec![
+            Segment {
+                idx: top_idx,
+                src_y: top_src_y,
+                src_h: top_src_h,
+                dst_y: 0,
+            },
+            Segment {
+                idx: bot_idx,
+                src_y: 0,
+                src_h: bot_src_h,
+                dst_y: top_src_h,
+            },
+        ],
+    }
+}
+
+/// Decoded tile bitmaps, kept while their tiles stay on screen so that
+/// scrolling within a tile does not decode its PNGs again.
+#[derive(Default)]
+pub(super) struct Compositor {
+    decoded: HashMap<usize, (Rgba, Rgba)>,
+}
+
+impl Compositor {
+    /// Composite the visible tiles. All visible tiles must be in `cache`.
+    ///
+    /// `sidebar_w` and `content_w` are the on-screen pixel widths of the two
+    /// columns; `max_h` caps the height (the image area above the status bar).
+    pub(super) fn compose<'a>(
+        &mut self,
+        visible: &VisibleTiles,
+        cache: &TileCache,
+        rects: impl Fn(usize) -> &'a [HighlightRect],
+        sidebar_w: u32,
+        content_w: u32,
+        max_h: u32,
+    ) -> anyhow::Result<Canvas> {
+        let segments = segments(visible);
+        self.decoded
+            .retain(|idx, _| segments.iter().any(|s| s.idx == *idx));
+        for seg in &segments {
+            if self.decoded.contains_key(&seg.idx) {
+                continue;
+            }
+            let pngs = cache
+                .get(seg.idx)
+                .ok_or_else(|| anyhow::anyhow!("[BUG] tile {} not rendered", seg.idx))?;
+            let pair = (decode_rgba(&pngs.content)?, decode_rgba(&pngs.sidebar)?);
+            self.decoded.insert(seg.idx, pair);
+        }
+
+        let width = sidebar_w + content_w;
+        let height = segments
+            .iter()
+            .map(|s| s.dst_y + s.src_h)
+            .max()
+            .unwrap_or(0)
+            .min(max_h);
+        let mut canvas = Canvas {
+            width,
+            height,
+            data: vec![0; width as usize * height as usize * 4],
+        };
+        for seg in &segments {
+            let (content, sidebar) = &self.decoded[&seg.idx];
+            let rows = seg.src_h.min(height.saturating_sub(seg.dst_y));
+            blit_rows(
+                &mut canvas,
+                sidebar,
+                seg.src_y,
+                rows,
+                0,
+                seg.dst_y,
+                sidebar_w,
+            );
+            blit_rows(
+                &mut canvas,
+                content,
+                seg.src_y,
+                rows,
+                sidebar_w,
+                seg.dst_y,
+                content_w,
+            );
+            for r in rects(seg.idx) {
+                blend_rect(&mut canvas, r, seg, rows, sidebar_w, width);
+            }
+        }
+        Ok(canvas)
+    }
+}
+
+/// Copy `rows` rows of `img` starting at `src_y`, at most `max_w` pixels
+/// wide, to `(dst_x, dst_y)`.
+fn blit_rows(
+    canvas: &mut Canvas,
+    img: &Rgba,
+    src_y: u32,
+    rows: u32,
+    dst_x: u32,
+    dst_y: u32,
+    max_w: u32,
+) {
+    let w = img.width.min(max_w).min(canvas.width.saturating_sub(dst_x)) as usize;
+    let rows = rows.min(img.height.saturating_sub(src_y));
+    for y in 0..rows {
+        let src = ((src_y + y) * img.width) as usize * 4;
+        let dst = ((dst_y + y) * canvas.width + dst_x) as usize * 4;
+        canvas.data[dst..dst + w * 4].copy_from_slice(&img.data[src..src + w * 4]);
+    }
+}
+
+/// Blend a highlight rect (tile coordinates) into the canvas.
+fn blend_rect(
+    canvas: &mut Canvas,
+    r: &HighlightRect,
+    seg: &Segment,
+    rows: u32,
+    x0: u32,
+    x_end: u32,
+) {
+    let [cr, cg, cb, a] = if r.is_active {
+        HIGHLIGHT_ACTIVE_RGBA
+    } else {
+        HIGHLIGHT_RGBA
+    };
+    let top = r.y_px.max(seg.src_y);
+    let bottom = (r.y_px + r.h_px).min(seg.src_y + rows);
+    let left = x0 + r.x_px;
+    let right = (x0 + r.x_px + r.w_px).min(x_end);
+    let a = a as u32;
+    for y in top..bottom {
+        let row = (seg.dst_y + y - seg.src_y) * canvas.width;
+        for x in left..right {
+            let i = (row + x) as usize * 4;
+            let px = &mut canvas.data[i..i + 3];
+            for (c, v) in px.iter_mut().zip([cr, cg, cb]) {
+                *c = ((v as u32 * a + *c as u32 * (255 - a)) / 255) as u8;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::frame::{TilePngs, solid_png};
+
+    fn cache_with(tiles: &[(usize, [u8; 4])]) -> TileCache {
+        let mut cache = TileCache::new();
+        for &(idx, color) in tiles {
+            cache.insert(
+                idx,
+                TilePngs {
+                    content: solid_png(8, 10, color),
+                    sidebar: solid_png(2, 10, [0, 0, 255, 255]),
+                },
+            );
+        }
+        cache
+    }
+
+    fn pixel(c: &Canvas, x: u32, y: u32) -> [u8; 4] {
+        let i = ((y * c.width + x) * 4) as usize;
+        c.data[i..i + 4].try_into().unwrap()
+    }
+
+    #[test]
+    fn compose_places_sidebar_left_of_content() {
+        let cache = cache_with(&[(0, [255, 0, 0, 255])]);
+        let visible = VisibleTiles::Single {
+            idx: 0,
+            src_y: 2,
+            src_h: 6,
+        };
+        let canvas = Compositor::default()
+            .compose(&visible, &cache, |_| &[], 2, 8, 100)
+            .unwrap();
+        assert_eq!((canvas.width, canvas.height), (10, 6));
+        assert_eq!(pixel(&canvas, 1, 0), [0, 0, 255, 255]);
+        assert_eq!(pixel(&canvas, 2, 5), [255, 0, 0, 255]);
+    }
+
+    #[test]
+    fn compose_stacks_split_tiles() {
+        let cache = cache_with(&[(0, [255, 0, 0, 255]), (1, [0, 255, 0, 255])]);
+        let visible = VisibleTiles::Split {
+            top_idx: 0,
+            top_src_y: 7,
+            top_src_h: 3,
+            bot_idx: 1,
+            bot_src_h: 4,
+        };
+        let canvas = Compositor::default()
+            .compose(&visible, &cache, |_| &[], 2, 8, 100)
+            .unwrap();
+        assert_eq!(canvas.height, 7);
+        assert_eq!(pixel(&canvas, 4, 2), [255, 0, 0, 255]);
+        assert_eq!(pixel(&canvas, 4, 3), [0, 255, 0, 255]);
+    }
+
+    #[test]
+    fn compose_blends_highlights_in_tile_coordinates() {
+        let cache = cache_with(&[(0, [0, 0, 0, 255])]);
+        let visible = VisibleTiles::Single {
+            idx: 0,
+            src_y: 4,
+            src_h: 6,
+        };
+        let rects = [HighlightRect {
+            x_px: 1,
+            y_px: 5,
+            w_px: 2,
+            h_px: 1,
+            is_active: true,
+        }];
+        let canvas = Compositor::default()
+            .compose(&visible, &cache, |_| &rects, 2, 8, 4)
+            .unwrap();
+        assert_eq!(canvas.height, 4);
+        let [r, g, b, _] = HIGHLIGHT_ACTIVE_RGBA;
+        let a = HIGHLIGHT_ACTIVE_RGBA[3] as u32;
+        let expect = [r, g, b].map(|v| (v as u32 * a / 255) as u8);
+        assert_eq!(pixel(&canvas, 3, 1)[..3], expect);
+        assert_eq!(pixel(&canvas, 3, 0), [0, 0, 0, 255]);
+        assert_eq!(pixel(&canvas, 5, 1), [0, 0, 0, 255]);
+    }
+}
@@ -1,11 +1,23 @@
 //! Terminal display state: Kitty image cache, redraw orchestration, and prefetch.
+//!
+//! With [`Graphics::Sixel`] or [`Graphics::Iterm2`] nothing is kept in the
+//! terminal: [`redraw`] composites the visible tiles and repaints them every
+//! frame, and the Kitty bookkeeping below stays empty.
+//!
+//! With [`Graphics::KittyPlaceholder`] tiles are uploaded as usual but get
+//! one virtual placement each, and [`redraw`] prints Unicode placeholder
+//! cells instead of moving placements. Search highlights cannot be layered
+//! over placeholder cells, so frames with highlights are composited and
+//! uploaded as a single image.
 
 use log::debug;
 use std::collections::{HashMap, HashSet};
 use std::io::{self, Write};
 
+use super::composite::{Canvas, Compositor};
+use super::graphics::Graphics;
 use super::layout::{Layout, ScrollState};
-use super::terminal;
+use super::{placeholder, sixel, terminal};
 use crate::frame::{DocumentMeta, HighlightRect, HighlightSpec, TileCache, VisibleTiles};
 use crate::renderer::{TileRenderer, TileResponse};
 
@@ -112,6 +124,12 @@ pub(super) struct DisplayState {
     /// the same `(i, p)` pair (no delete), disappeared slots are deleted
     /// individually via `a=d,d=i,i=..,p=..`.
     live_slots: HashMap<PlacementSlot, u32>,
+    graphics: Graphics,
+    /// Decoded tiles for compositing (non-Kitty protocols, and highlighted
+    /// placeholder frames).
+    compositor: Compositor,
+    /// Image id of the composited placeholder frame while it is uploaded.
+    frame_id: Option<u32>,
 }
 
 /// Describes the actions needed to load a tile into the terminal.
@@ -125,14 +143,18 @@ pub(super) struct LoadAction {
 
 impl DisplayState {
     pub(super) fn new(evict_distance: usize) -> Self {
-        Self::new_with_start_id(evict_distance, 100)
+        Self::new_with_start_id(evict_distance, 100, Graphics::Kitty)
     }
 
     /// Create a DisplayState that allocates image IDs starting from `start_id`.
     ///
     /// Used by the double-buffer reload scheme: each generation starts from a
     /// fixed base (e.g. 100 or 5000) so old and new images coexist briefly.
-    pub(super) fn new_with_start_id(evict_distance: usize, start_id: u32) -> Self {
+    pub(super) fn new_with_start_id(
+        evict_distance: usize,
+        start_id: u32,
+        graphics: Graphics,
+    ) -> Self {
         Self {
             map: HashMap::new(),
             next_id: start_id,
@@ -140,9 +162,16 @@ impl DisplayState {
             overlay_rects: HashMap::new(),
             highlight_images: None,
             live_slots: HashMap::new(),
+            graphics,
+            compositor: Compositor::default(),
+            frame_id: None,
         }
     }
 
+    pub(super) fn graphics(&self) -> Graphics {
+        self.graphics
+    }
+
     /// All Kitty image IDs owned by this DisplayState (tiles + highlights).
     pub(super) fn all_image_ids(&self) -> Vec<u32> {
         let mut ids = Vec::new();
@@ -153,6 +182,7 @@ impl DisplayState {
         if let Some(ref imgs) = self.highlight_images {
             ids.extend_from_slice(&imgs.all_ids());
         }
+        ids.extend(self.frame_id);
         ids
     }
 
@@ -214,27 +244,64 @@ impl DisplayState {
         rh: &mut ForkHandle<'_>,
     ) -> anyhow::Result<()> {
         if let Some(action) = self.plan_load(idx) {
-            if !cache.contains(idx) {
-                if rh.in_flight.insert(idx) {
-                    let _ = rh.renderer.send_render_tile(idx);
+            self.ensure_cached(cache, idx, rh)?;
+            execute_load(&action, cache.get(idx).unwrap())?;
+        }
+        Ok(())
+    }
+
+    /// Ensure a tile is in the doc cache, requesting it from the fork child
+    /// and blocking until it arrives if necessary.
+    fn ensure_cached(
+        &mut self,
+        cache: &mut TileCache,
+        idx: usize,
+        rh: &mut ForkHandle<'_>,
+    ) -> anyhow::Result<()> {
+        if cache.contains(idx) {
+            return Ok(());
+        }
+        if rh.in_flight.insert(idx) {
+            let _ = rh.renderer.send_render_tile(idx);
+        }
+        while !cache.contains(idx) {
+            match rh.renderer.recv()? {
+                TileResponse::Tile { idx: i, pngs } => {
+                    rh.in_flight.remove(&i);
+                    cache.insert(i, pngs);
                 }
-                while !cache.contains(idx) {
-                    match rh.renderer.recv()? {
-                        TileResponse::Tile { idx: i, pngs } => {
-                            rh.in_flight.remove(&i);
-                            cache.insert(i, pngs);
-                        }
-                        TileResponse::Rects { idx: i, rects } => {
-                            self.set_overlay_rects(i, rects);
-                        }
-                    }
+                TileResponse::Rects { idx: i, rects } => {
+                    self.set_overlay_rects(i, rects);
                 }
             }
-            execute_load(&action, cache.get(idx).unwrap())?;
         }
         Ok(())
     }
 
+    /// Composite the visible tiles (and, if `include_overlays`, their
+    /// highlight rects) into one bitmap of the image area.
+    fn compose(
+        &mut self,
+        visible: &VisibleTiles,
+        cache: &TileCache,
+        layout: &Layout,
+        scroll: &ScrollState,
+        include_overlays: bool,
+    ) -> anyhow::Result<Canvas> {
+        let rects = &self.overlay_rects;
+        self.compositor.compose(
+            visible,
+            cache,
+            |idx| match rects.get(&idx) {
+                Some(r) if include_overlays => r.as_slice(),
+                _ => &[],
+            },
+            layout.sidebar_cols as u32 * layout.cell_w as u32,
+            scroll.vp_w,
+            layout.image_rows as u32 * layout.cell_h as u32,
+        )
+    }
+
     /// Store highlight rectangles for a tile.
     pub(super) fn set_overlay_rects(&mut self, idx: usize, rects: Vec<HighlightRect>) {
         self.overlay_rects.insert(idx, rects);
@@ -317,6 +384,7 @@ impl DisplayState {
         self.map.clear();
         self.highlight_images = None;
         self.live_slots.clear();
+        self.frame_id = None;
     }
 
     /// Clear overlay rect state only (no I/O).
@@ -342,7 +410,7 @@ impl DisplayState {
         let mut out = std::io::stdout();
         for (slot, image_id) in self.live_slots.drain() {
             let pid = slot.placement_id();
-            write!(out, "\x1b_Ga=d,d=i,i={image_id},p={pid},q=2\x1b\\")?;
+            terminal::kitty(&mut out, format_args!("a=d,d=i,i={image_id},p={pid},q=2"))?;
         }
         out.flush()
     }
@@ -362,11 +430,31 @@ impl DisplayState {
         if let Some(old_id) = self.live_slots.insert(slot, image_id)
             && old_id != image_id
         {
-            write!(out, "\x1b_Ga=d,d=i,i={old_id},p={pid},q=2\x1b\\")?;
+            terminal::kitty(out, format_args!("a=d,d=i,i={old_id},p={pid},q=2"))?;
         }
         Ok(pid)
     }
 
+    /// Give `slot`'s image a virtual placement (`U=1`) of `cols`×`rows`
+    /// cells for Unicode placeholders to show, unless it already has one.
+    fn place_virtual(
+        &mut self,
+        out: &mut impl Write,
+        slot: PlacementSlot,
+        image_id: u32,
+        cols: u16,
+        rows: u16,
+    ) -> io::Result<()> {
+        if self.live_slots.get(&slot) == Some(&image_id) {
+            return Ok(());
+        }
+        let pid = self.track_placement(out, slot, image_id)?;
+        terminal::kitty(
+            out,
+            format_args!("a=p,U=1,i={image_id},p={pid},c={cols},r={rows},q=2"),
+        )
+    }
+
     /// Emit `a=d,d=i,i=..,p=..` for every tracked slot absent from `keep`,
     /// and drop those entries from the live-slot tracker. Used in redraw
     /// Phase 2 to clear slots that no longer appear this frame.
@@ -384,7 +472,7 @@ impl DisplayState {
         for slot in stale {
             if let Some(image_id) = self.live_slots.remove(&slot) {
                 let pid = slot.placement_id();
-                write!(out, "\x1b_Ga=d,d=i,i={image_id},p={pid},q=2\x1b\\")?;
+                terminal::kitty(out, format_args!("a=d,d=i,i={image_id},p={pid},q=2"))?;
             }
         }
         Ok(())
@@ -405,7 +493,7 @@ impl DisplayState {
 fn delete_placements_for_ids(ids: &[u32]) -> io::Result<()> {
     let mut out = std::io::stdout();
     for &id in ids {
-        write!(out, "\x1b_Ga=d,d=i,i={id},q=2\x1b\\")?;
+        terminal::kitty(&mut out, format_args!("a=d,d=i,i={id},q=2"))?;
     }
     out.flush()
 }
@@ -504,14 +592,22 @@ pub(super) struct ForkHandle<'a> {
 /// In `Split`, `top_src_h = tile_h - src_y_in_tile` is not a cell multiple if
 /// `src_y_in_tile` is sub-cell, which forces Kitty to vertically compress the
 /// top image; we avoid that by snapping at tile boundaries only.
+///
+/// Unicode placeholders address whole cell rows, so with
+/// [`Graphics::KittyPlaceholder`] `y` is always snapped.
 fn visible_tiles_for_render(
     meta: &DocumentMeta,
     scroll: &ScrollState,
     layout: &Layout,
+    graphics: Graphics,
 ) -> VisibleTiles {
     let y = scroll.y_offset;
     let visible = meta.visible_tiles(y, scroll.vp_h);
     match &visible {
+        _ if graphics == Graphics::KittyPlaceholder => {
+            let cell_h = layout.cell_h as u32;
+            meta.visible_tiles((y / cell_h) * cell_h, scroll.vp_h)
+        }
         VisibleTiles::Split { .. } => {
             let cell_h = layout.cell_h as u32;
             let snapped = (y / cell_h) * cell_h;
@@ -527,6 +623,9 @@ fn visible_tiles_for_render(
 
 /// Full redraw: content tiles + sidebar + overlay + status bar.
 ///
+/// Without Kitty, the visible tiles are composited and painted as one
+/// Sixel or OSC 1337 image instead of the phases below.
+///
 /// Ordering:
 ///   Phase 1 — ensure visible tiles are rendered and uploaded.
 ///   Phase 2 — delete only the placement slots that are going away this
@@ -546,7 +645,47 @@ pub(super) fn redraw(
     include_overlays: bool,
     rh: &mut ForkHandle<'_>,
 ) -> anyhow::Result<()> {
-    let visible = visible_tiles_for_render(meta, scroll, layout);
+    let visible = visible_tiles_for_render(meta, scroll, layout, loaded.graphics);
+    if loaded.graphics == Graphics::KittyPlaceholder {
+        redraw_placeholders(
+            &visible,
+            meta,
+            cache,
+            loaded,
+            layout,
+            scroll,
+            include_overlays,
+            rh,
+        )?;
+        terminal::draw_status_bar(layout, scroll, filename, acc_peek, flash)?;
+        return Ok(());
+    }
+    if !loaded.graphics.keeps_images() {
+        let indices = match visible {
+            VisibleTiles::Single { idx, .. } => vec![idx],
+            VisibleTiles::Split {
+                top_idx, bot_idx, ..
+            } => vec![top_idx, bot_idx],
+        };
+        for idx in indices {
+            loaded.ensure_cached(cache, idx, rh)?;
+        }
+        let canvas = loaded.compose(&visible, cache, layout, scroll, include_overlays)?;
+        let image = match loaded.graphics {
+            Graphics::Iterm2 => {
+                let png = crate::frame::encode_png(canvas.width, canvas.height, &canvas.data)?;
+                terminal::iterm2_image(&png, canvas.width, canvas.height)
+            }
+            Graphics::Sixel => sixel::encode(&canvas),
+            Graphics::Kitty | Graphics::KittyPlaceholder => {
+                unreachable!("Kitty keeps images in the terminal")
+            }
+            Graphics::Text => unreachable!("text mode has no tiles"),
+        };
+        terminal::draw_frame(&image, canvas.height, layout)?;
+        terminal::draw_status_bar(layout, scroll, filename, acc_peek, flash)?;
+        return Ok(());
+    }
 
     // Phase 1: Ensure all needed tiles are rendered and sent to the terminal.
     match &visible {
@@ -577,6 +716,181 @@ pub(super) fn redraw(
     Ok(())
 }
 
+/// [`redraw`] for Unicode placeholders: upload the visible tiles, give each
+/// a virtual placement spanning the whole tile, and print the placeholder
+/// cells for the visible rows. With highlights on any visible tile, or a
+/// tile taller than placeholders can address, the frame is composited and
+/// uploaded as one image instead.
+#[allow(clippy::too_many_arguments)]
+fn redraw_placeholders(
+    visible: &VisibleTiles,
+    meta: &DocumentMeta,
+    cache: &mut TileCache,
+    loaded: &mut DisplayState,
+    layout: &Layout,
+    scroll: &ScrollState,
+    include_overlays: bool,
+    rh: &mut ForkHandle<'_>,
+) -> anyhow::Result<()> {
+    let indices = match *visible {
+        VisibleTiles::Single { idx, .. } => vec![idx],
+        VisibleTiles::Split {
+            top_idx, bot_idx, ..
+        } => vec![top_idx, bot_idx],
+    };
+    let cell_h = layout.cell_h as u32;
+    let mut out = std::io::stdout();
+
+    let highlighted = include_overlays
+        && indices
+            .iter()
+            .any(|&idx| !loaded.overlay_rects(idx).is_empty());
+    let tile_rows: Option<Vec<u16>> = indices
+        .iter()
+        .map(|&idx| placeholder_rows(meta.tile_actual_height_px(idx), cell_h))
+        .collect();
+    let tile_rows = match tile_rows {
+        Some(rows) if !highlighted => rows,
+        _ => {
+            return redraw_placeholder_frame(
+                visible,
+                &indices,
+                cache,
+                loaded,
+                layout,
+                scroll,
+                include_overlays,
+                rh,
+            );
+        }
+    };
+
+    if let Some(id) = loaded.frame_id.take() {
+        terminal::delete_image(id)?;
+    }
+    for &idx in &indices {
+        loaded.ensure_loaded(cache, idx, rh)?;
+    }
+    let keep: HashSet<PlacementSlot> = indices
+        .iter()
+        .flat_map(|&idx| [PlacementSlot::Content(idx), PlacementSlot::Sidebar(idx)])
+        .collect();
+    loaded.delete_stale_slots(&mut out, &keep)?;
+    for (&idx, &tile_rows) in indices.iter().zip(&tile_rows) {
+        let ids = &loaded.map[&idx];
+        let (content_id, sidebar_id) = (ids.content_id, ids.sidebar_id);
+        loaded.place_virtual(
+            &mut out,
+            PlacementSlot::Content(idx),
+            content_id,
+            layout.image_cols,
+            tile_rows,
+        )?;
+        loaded.place_virtual(
+            &mut out,
+            PlacementSlot::Sidebar(idx),
+            sidebar_id,
+            layout.sidebar_cols,
+            tile_rows,
+        )?;
+    }
+
+    // (tile, first tile row, row count) per visible slice, top to bottom.
+    let slices: Vec<(usize, u16, u16)> = match *visible {
+        VisibleTiles::Single { idx, src_y, src_h } => {
+            vec![(idx, (src_y / cell_h) as u16, src_h.div_ceil(cell_h) as u16)]
+        }
+        VisibleTiles::Split {
+            top_idx,
+            top_src_y,
+            top_src_h,
+            bot_idx,
+            bot_src_h,
+        } => vec![
+            (
+                top_idx,
+                (top_src_y / cell_h) as u16,
+                (top_src_h / cell_h) as u16,
+            ),
+            (bot_idx, 0, bot_src_h.div_ceil(cell_h) as u16),
+        ],
+    };
+    let id_rows = |get_id: fn(&TileImageIds) -> u32| -> Vec<(u32, u16, u16)> {
+        slices
+            .iter()
+            .map(|&(idx, first, rows)| (get_id(&loaded.map[&idx]), first, rows))
+            .collect()
+    };
+    terminal::draw_placeholders(
+        &id_rows(|ids| ids.sidebar_id),
+        0,
+        layout.sidebar_cols,
+        layout,
+    )?;
+    terminal::draw_placeholders(
+        &id_rows(|ids| ids.content_id),
+        layout.image_col,
+        layout.image_cols,
+        layout,
+    )?;
+    Ok(())
+}
+
+/// Composite the visible frame and show it as a single virtual placement.
+#[allow(clippy::too_many_arguments)]
+fn redraw_placeholder_frame(
+    visible: &VisibleTiles,
+    indices: &[usize],
+    cache: &mut TileCache,
+    loaded: &mut DisplayState,
+    layout: &Layout,
+    scroll: &ScrollState,
+    include_overlays: bool,
+    rh: &mut ForkHandle<'_>,
+) -> anyhow::Result<()> {
+    let cell_h = layout.cell_h as u32;
+    let mut out = std::io::stdout();
+    for &idx in indices {
+        loaded.ensure_cached(cache, idx, rh)?;
+    }
+    let mut canvas = loaded.compose(visible, cache, layout, scroll, include_overlays)?;
+    // Pad to whole rows so the placement is not scaled to fit.
+    let rows = canvas.height.div_ceil(cell_h);
+    canvas.height = rows * cell_h;
+    canvas
+        .data
+        .resize(canvas.width as usize * canvas.height as usize * 4, 0);
+    let png = crate::frame::encode_png(canvas.width, canvas.height, &canvas.data)?;
+
+    loaded.delete_stale_slots(&mut out, &HashSet::new())?;
+    let id = match loaded.frame_id {
+        Some(id) => id,
+        None => {
+            let id = loaded.next_id;
+            loaded.next_id += 1;
+            loaded.frame_id = Some(id);
+            id
+        }
+    };
+    // Re-sending an id replaces the image along with its placements.
+    terminal::send_image(&png, id)?;
+    let cols = layout.sidebar_cols + layout.image_cols;
+    let rows = (rows as u16).min(placeholder::MAX_INDEX + 1);
+    terminal::kitty(
+        &mut out,
+        format_args!("a=p,U=1,i={id},c={cols},r={rows},q=2"),
+    )?;
+    terminal::draw_placeholders(&[(id, 0, rows)], 0, cols, layout)?;
+    Ok(())
+}
+
+/// Cell rows of a `height_px` tall tile, or `None` when a virtual placement
+/// could not address them all.
+fn placeholder_rows(height_px: u32, cell_h: u32) -> Option<u16> {
+    let rows = height_px.div_ceil(cell_h);
+    (rows <= placeholder::MAX_INDEX as u32 + 1).then_some(rows as u16)
+}
+
 /// Request prefetch of tiles adjacent to the current viewport.
 ///
 /// Sends tile indices for 2 tiles ahead and 1 behind the current position.
@@ -625,7 +939,7 @@ pub(super) fn update_overlays(
     spec: &HighlightSpec,
     rh: &mut ForkHandle<'_>,
 ) -> anyhow::Result<()> {
-    let visible = visible_tiles_for_render(meta, scroll, layout);
+    let visible = visible_tiles_for_render(meta, scroll, layout, loaded.graphics);
 
     let indices: Vec<usize> = match &visible {
         VisibleTiles::Single { idx, .. } => vec![*idx],
@@ -657,9 +971,10 @@ pub(super) fn update_overlays(
     }
 
     // Ensure the shared highlight images are uploaded.
-    if indices
-        .iter()
-        .any(|idx| !loaded.overlay_rects(*idx).is_empty())
+    if loaded.graphics.places_overlays()
+        && indices
+            .iter()
+            .any(|idx| !loaded.overlay_rects(*idx).is_empty())
     {
         loaded.ensure_highlight_images()?;
     }
@@ -711,6 +1026,11 @@ pub(super) fn redraw_and_prefetch(
     rh: &mut ForkHandle<'_>,
 ) -> anyhow::Result<()> {
     drain_responses(rh, cache, display)?;
+    let composited = !display.graphics.places_overlays();
+    // A composited frame is painted once, so its rects must be known first.
+    if composited && let Some(spec) = search_spec {
+        update_overlays(meta, display, cache, layout, scroll, spec, rh)?;
+    }
     redraw(
         meta,
         cache,
@@ -723,9 +1043,9 @@ pub(super) fn redraw_and_prefetch(
         search_spec.is_some(),
         rh,
     )?;
-    if let Some(spec) = search_spec {
+    if !composited && let Some(spec) = search_spec {
         update_overlays(meta, display, cache, layout, scroll, spec, rh)?;
-        let visible = visible_tiles_for_render(meta, scroll, layout);
+        let visible = visible_tiles_for_render(meta, scroll, layout, display.graphics);
         // update_overlays may have populated new rects; re-emit overlay
         // placements. Stale slots (rects that vanished for the current
         // tile set) were already pruned by Phase 2's delete_stale_slots.
@@ -791,6 +1111,16 @@ mod tests {
         assert!(!loaded.has_overlay(0));
     }
 
+    #[test]
+    fn placeholder_rows_fit_addressable_rows() {
+        let max = placeholder::MAX_INDEX as u32 + 1;
+        assert_eq!(placeholder_rows(1, 20), Some(1));
+        assert_eq!(placeholder_rows(max * 20, 20), Some(max as u16));
+        // One pixel more needs a row no placeholder can address.
+        assert_eq!(placeholder_rows(max * 20 + 1, 20), None);
+        assert_eq!(placeholder_rows(4000, 8), None);
+    }
+
     #[test]
     fn placement_id_is_stable_by_slot() {
         assert_eq!(PlacementSlot::Content(0).placement_id(), 1);
@@ -37,6 +37,8 @@ pub(super) enum ExitReason {
     },
     Navigate {
         path: std::path::PathBuf,
+        /// Heading fragment (`other.md#setup` → `setup`) to land on after loading.
+        anchor: Option<String>,
     },
     GoBack,
 }
@@ -84,6 +86,10 @@ pub(super) enum Effect {
     /// animators glide smoothly to the new anchor (matches legacy
     /// `set_landing` semantics).
     ScrollAnchor(u32),
+    /// Absolute scroll jump within the document that records the current
+    /// position on the jump stack first, so `GoBack` returns here
+    /// (footnote reference → definition).
+    JumpWithin(u32),
     MarkDirty,
     Flash(String),
     RedrawStatusBar,
@@ -193,7 +199,7 @@ pub(super) fn execute_render_ops(
                 terminal::clear_screen()?;
             }
             RenderOp::DeleteAllImages => {
-                terminal::delete_all_images()?;
+                terminal::clear_images(vp.display.graphics())?;
             }
             RenderOp::CopyToClipboard(text) => {
                 let _ = terminal::send_osc52(&text);
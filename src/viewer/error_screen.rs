@@ -0,0 +1,161 @@
+//! Compile error screen.
+//!
+//! When the document does not compile, the viewer shows each Typst error
+//! with the Markdown line it came from instead of exiting. While the input
+//! is live (watch mode or an open stdin pipe) it stays on this screen until
+//! the input changes, then rebuilds; fixing the file brings the document
+//! back.
+
+use std::time::Duration;
+
+use crossterm::event::{self, Event};
+use unicode_width::UnicodeWidthChar;
+
+use super::session::Session;
+use super::{MarkdownSource, is_quit_key, terminal};
+use crate::pipeline::CompileError;
+
+/// How the error screen was left.
+pub(super) enum AfterError {
+    Quit,
+    /// The input changed or the terminal was resized: build again.
+    Rebuild,
+}
+
+/// Show `error` and wait for a change, a resize or quit.
+pub(super) fn show(
+    error: &CompileError,
+    markdown: &str,
+    session: &mut Session,
+    source: &mut MarkdownSource,
+    watch_interval: Duration,
+    sidebar_cols: u16,
+) -> anyhow::Result<AfterError> {
+    let live = source.is_live(session);
+    let width = (session.layout.sidebar_cols + session.layout.image_cols) as usize;
+    terminal::clear_images(session.graphics)?;
+    terminal::draw_error_screen(
+        &session.layout,
+        &session.filename,
+        &screen_lines(error, markdown, width),
+        live,
+    )?;
+    loop {
+        if event::poll(watch_interval)? {
+            match event::read()? {
+                Event::Key(k) if is_quit_key(&k) => return Ok(AfterError::Quit),
+                Event::Resize(new_cols, new_rows) => {
+                    session.update_layout_for_resize(new_cols, new_rows, sidebar_cols)?;
+                    return Ok(AfterError::Rebuild);
+                }
+                _ => {}
+            }
+        } else if live && source.changed(session) {
+            return Ok(AfterError::Rebuild);
+        }
+    }
+}
+
+/// The screen text, one entry per row, each fitted to `width` columns:
+/// a heading, then every error with its Markdown line and hints. Errors
+/// that could not be located keep Typst's own location notes instead.
+fn screen_lines(error: &CompileError, markdown: &str, width: usize) -> Vec<String> {
+    let source: Vec<&str> = markdown.lines().collect();
+    let mut lines = vec![
+        format!("Typst compilation failed — {} error(s)", error.issues.len()),
+        String::new(),
+    ];
+    for issue in &error.issues {
+        match issue.line {
+            Some(line) => {
+                lines.push(format!("line {line}: {}", issue.message));
+                if let Some(text) = source.get(line - 1) {
+                    lines.push(format!("{line:>6} | {text}"));
+                }
+                lines.extend(
+                    issue
+                        .notes
+                        .iter()
+                        .filter(|n| n.starts_with("hint:"))
+                        .map(|n| format!("         {n}")),
+                );
+            }
+            None => {
+                lines.push(issue.message.clone());
+                lines.extend(issue.notes.iter().map(|n| format!("    {n}")));
+            }
+        }
+        lines.push(String::new());
+    }
+    lines.iter().map(|l| fit(l, width)).collect()
+}
+
+/// `text` with tabs expanded, cut to at most `width` display columns.
+fn fit(text: &str, width: usize) -> String {
+    let mut out = String::new();
+    let mut used = 0;
+    for ch in text.chars() {
+        let (ch, w) = match ch {
+            '\t' => (' ', 1),
+            _ => (ch, ch.width().unwrap_or(0)),
+        };
+        if used + w > width {
+            break;
+        }
+        out.push(ch);
+        used += w;
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::compile::{Issue, IssueKind, Severity};
+
+    fn issue(line: Option<usize>, message: &str, notes: &[&str]) -> Issue {
+        Issue {
+            line,
+            severity: Severity::Error,
+            kind: IssueKind::Typst,
+            message: message.into(),
+            notes: notes.iter().map(|n| n.to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn errors_show_their_markdown_line() {
+        let error = CompileError {
+            issues: vec![
+                issue(
+                    Some(2),
+                    "unclosed delimiter",
+                    &["--> main.typ:173:4", "173 | $lr(\\( x $", "hint: close it"],
+                ),
+                issue(None, "unknown variable", &["--> main.typ:9:1"]),
+            ],
+        };
+        let md = "# Title\n$\\left( x$\n";
+        assert_eq!(
+            screen_lines(&error, md, 80),
+            vec![
+                "Typst compilation failed — 2 error(s)",
+                "",
+                "line 2: unclosed delimiter",
+                "     2 | $\\left( x$",
+                "         hint: close it",
+                "",
+                "unknown variable",
+                "    --> main.typ:9:1",
+                "",
+            ]
+        );
+    }
+
+    #[test]
+    fn fit_cuts_at_display_width() {
+        assert_eq!(fit("日本語テキスト", 7), "日本語");
+        assert_eq!(fit("a\tb", 10), "a b");
+        assert_eq!(fit("short", 10), "short");
+    }
+}
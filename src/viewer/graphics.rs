@@ -0,0 +1,251 @@
+//! Terminal graphics capability: which image protocol the viewer speaks.
+//!
+//! Kitty keeps uploaded tiles in the terminal and moves placements around.
+//! Inside tmux, which neither tracks nor forwards those placements, Kitty
+//! images are shown through Unicode placeholders instead (see
+//! [`super::placeholder`]) and every Kitty command is wrapped in tmux's
+//! passthrough.
+//! Sixel and iTerm2 inline images only paint pixels, so each frame is
+//! composited into one bitmap (see [`super::composite`]), search highlights
+//! included, and emitted as a Sixel or OSC 1337 image. Text mode shows no
+//! images at all: the document is reflowed into styled ANSI text (see
+//! [`super::text_view`]).
+//!
+//! `--graphics auto` probes the terminal once at startup: a Kitty graphics
+//! query, an XTVERSION request (`CSI > q`) and a Primary Device Attributes
+//! request (`CSI c`). Every terminal answers DA1, so its reply terminates
+//! the read. In order of preference: a Kitty reply means Kitty graphics, an
+//! XTVERSION name of `iTerm2` means OSC 1337, and attribute `4` in the DA1
+//! reply means Sixel. Without any of these, `$LC_TERMINAL` /
+//! `$TERM_PROGRAM` still identify iTerm2 (the former survives ssh), and a
+//! terminal that answered DA1 without advertising any of them gets text
+//! mode. A terminal that reports no pixel size gets text mode unprobed.
+//! Inside tmux the Kitty query is sent through passthrough; tmux itself
+//! answers XTVERSION and DA1.
+
+use log::{info, warn};
+use std::io::{Read, Write};
+use std::os::unix::io::AsRawFd;
+use std::time::{Duration, Instant};
+
+use crate::config::GraphicsMode;
+
+/// How long to wait for the probe replies.
+const PROBE_TIMEOUT: Duration = Duration::from_millis(200);
+
+/// Kitty query for a 1×1 RGB image (`a=q` never stores or displays it).
+const KITTY_QUERY: &str = "i=31,s=1,v=1,a=q,t=d,f=24;AAAA";
+
+/// XTVERSION request; sent after [`KITTY_QUERY`].
+const XTVERSION_QUERY: &[u8] = b"\x1b[>0q";
+
+/// Primary Device Attributes request, which ends every query.
+const DA1_QUERY: &[u8] = b"\x1b[c";
+
+/// Start of the terminal's reply to the Kitty query (`OK` or an error).
+const KITTY_REPLY: &[u8] = b"\x1b_Gi=31;";
+
+/// The image protocol in use.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(super) enum Graphics {
+    Kitty,
+    /// Kitty graphics shown through Unicode placeholder cells.
+    KittyPlaceholder,
+    Sixel,
+    Iterm2,
+    Text,
+}
+
+impl Graphics {
+    /// Resolve the configured mode, probing the terminal for `Auto`.
+    /// Must be called in raw mode, before the event loop reads input.
+    ///
+    /// `has_pixel_size` is whether the terminal reports its size in pixels;
+    /// no image protocol can be laid out without it.
+    pub(super) fn resolve(mode: GraphicsMode, has_pixel_size: bool) -> Self {
+        let graphics = match mode {
+            GraphicsMode::Kitty if in_tmux() => Self::KittyPlaceholder,
+            GraphicsMode::Kitty => Self::Kitty,
+            GraphicsMode::KittyPlaceholder => Self::KittyPlaceholder,
+            GraphicsMode::Sixel => Self::Sixel,
+            GraphicsMode::Iterm2 => Self::Iterm2,
+            GraphicsMode::Text => Self::Text,
+            GraphicsMode::Auto if !has_pixel_size => {
+                info!("terminal reports no pixel size, using text mode");
+                Self::Text
+            }
+            GraphicsMode::Auto => {
+                let var = |name| std::env::var(name).unwrap_or_default();
+                match probe() {
+                    Some(Self::Kitty) if in_tmux() => Self::KittyPlaceholder,
+                    Some(g) if g != Self::Text => g,
+                    _ if is_iterm2_env(&var("LC_TERMINAL"), &var("TERM_PROGRAM")) => Self::Iterm2,
+                    Some(text) => text,
+                    None => {
+                        warn!("graphics probe got no usable reply, assuming Kitty");
+                        Self::Kitty
+                    }
+                }
+            }
+        };
+        info!("graphics: {graphics:?} (configured {mode:?})");
+        graphics
+    }
+
+    /// Whether images live in the terminal (Kitty) rather than being
+    /// painted once per frame.
+    pub(super) fn keeps_images(self) -> bool {
+        matches!(self, Self::Kitty | Self::KittyPlaceholder)
+    }
+
+    /// Whether search highlights are placements of their own on top of the
+    /// tiles, rather than blended into the painted image.
+    pub(super) fn places_overlays(self) -> bool {
+        self == Self::Kitty
+    }
+}
+
+/// Whether we run inside tmux.
+pub(super) fn in_tmux() -> bool {
+    std::env::var_os("TMUX").is_some_and(|v| !v.is_empty())
+}
+
+fn probe() -> Option<Graphics> {
+    let mut request = Vec::new();
+    let _ = super::terminal::kitty(&mut request, format_args!("{KITTY_QUERY}"));
+    request.extend_from_slice(XTVERSION_QUERY);
+    parse_probe(&query_tty(&request)?)
+}
+
+/// Send `request` to the terminal followed by a DA1 request, and collect
+/// the replies up to the DA1 answer (every terminal sends one) or until
+/// [`PROBE_TIMEOUT`]. `None` if the tty cannot be used.
+pub(super) fn query_tty(request: &[u8]) -> Option<Vec<u8>> {
+    let mut tty = match std::fs::File::options()
+        .read(true)
+        .write(true)
+        .open("/dev/tty")
+    {
+        Ok(f) => f,
+        Err(e) => {
+            warn!("cannot open /dev/tty for terminal query: {e}");
+            return None;
+        }
+    };
+    if tty.write_all(request).is_err() || tty.write_all(DA1_QUERY).is_err() || tty.flush().is_err()
+    {
+        warn!("failed to send terminal query");
+        return None;
+    }
+
+    let deadline = Instant::now() + PROBE_TIMEOUT;
+    let mut pollfd = libc::pollfd {
+        fd: tty.as_raw_fd(),
+        events: libc::POLLIN,
+        revents: 0,
+    };
+    let mut buf = Vec::new();
+    while !has_da1_reply(&buf) {
+        let left = deadline.saturating_duration_since(Instant::now());
+        let ready = unsafe { libc::poll(&mut pollfd as *mut _, 1, left.as_millis() as i32) };
+        if ready <= 0 {
+            warn!(
+                "terminal query timed out after {}ms",
+                PROBE_TIMEOUT.as_millis()
+            );
+            break;
+        }
+        let mut chunk = [0u8; 256];
+        match tty.read(&mut chunk) {
+            Ok(0) | Err(_) => break,
+            Ok(n) => buf.extend_from_slice(&chunk[..n]),
+        }
+    }
+    Some(buf)
+}
+
+/// Find the DA1 reply (`CSI ? Ps ; ... c`) and return its parameters.
+fn da1_params(buf: &[u8]) -> Option<&[u8]> {
+    let start = buf.windows(3).position(|w| w == b"\x1b[?")? + 3;
+    let len = buf[start..].iter().position(|&b| b == b'c')?;
+    Some(&buf[start..start + len])
+}
+
+fn has_da1_reply(buf: &[u8]) -> bool {
+    da1_params(buf).is_some()
+}
+
+/// Find the XTVERSION reply (`DCS > | name ST`) and return the name.
+fn xtversion(buf: &[u8]) -> Option<&[u8]> {
+    let start = buf.windows(4).position(|w| w == b"\x1bP>|")? + 4;
+    let len = buf[start..].windows(2).position(|w| w == b"\x1b\\")?;
+    Some(&buf[start..start + len])
+}
+
+fn is_iterm2_env(lc_terminal: &str, term_program: &str) -> bool {
+    lc_terminal == "iTerm2" || term_program == "iTerm.app"
+}
+
+/// Interpret the probe replies. A reply to the Kitty query wins, then an
+/// iTerm2 XTVERSION; otherwise DA1 attribute 4 advertises Sixel, and a DA1
+/// reply without it means no image support. `None` if nothing answered.
+fn parse_probe(buf: &[u8]) -> Option<Graphics> {
+    if buf.windows(KITTY_REPLY.len()).any(|w| w == KITTY_REPLY) {
+        return Some(Graphics::Kitty);
+    }
+    if xtversion(buf).is_some_and(|name| name.starts_with(b"iTerm2")) {
+        return Some(Graphics::Iterm2);
+    }
+    let params = da1_params(buf)?;
+    let sixel = params.split(|&b| b == b';').any(|p| p == b"4");
+    Some(if sixel {
+        Graphics::Sixel
+    } else {
+        Graphics::Text
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn probe_kitty_reply_wins() {
+        let buf = b"\x1b_Gi=31;OK\x1b\\\x1b[?62;4;22c";
+        assert_eq!(parse_probe(buf), Some(Graphics::Kitty));
+    }
+
+    #[test]
+    fn probe_da1_with_sixel() {
+        assert_eq!(parse_probe(b"\x1b[?62;4;6;22c"), Some(Graphics::Sixel));
+        assert_eq!(parse_probe(b"\x1b[?4c"), Some(Graphics::Sixel));
+    }
+
+    #[test]
+    fn probe_da1_without_sixel() {
+        assert_eq!(parse_probe(b"\x1b[?62;22;42c"), Some(Graphics::Text));
+        assert_eq!(parse_probe(b"\x1b[?1;2c"), Some(Graphics::Text));
+        assert_eq!(parse_probe(b""), None);
+    }
+
+    #[test]
+    fn probe_iterm2_xtversion_beats_sixel() {
+        let buf = b"\x1bP>|iTerm2 3.5.0\x1b\\\x1b[?62;4c";
+        assert_eq!(parse_probe(buf), Some(Graphics::Iterm2));
+        let buf = b"\x1bP>|XTerm(390)\x1b\\\x1b[?62;4c";
+        assert_eq!(parse_probe(buf), Some(Graphics::Sixel));
+    }
+
+    #[test]
+    fn iterm2_env_hints() {
+        assert!(is_iterm2_env("iTerm2", ""));
+        assert!(is_iterm2_env("", "iTerm.app"));
+        assert!(!is_iterm2_env("", "WezTerm"));
+    }
+
+    #[test]
+    fn da1_reply_needs_terminator() {
+        assert!(!has_da1_reply(b"\x1b[?62;4"));
+        assert!(has_da1_reply(b"\x1b[?62;4c"));
+    }
+}
@@ -1,22 +1,38 @@
 //! Input processing layer: key mapping and numeric prefix accumulator.
 //!
 //! Pure logic, no I/O. All functions are deterministic and testable.
+//!
+//! Bindings live in a [`Keymap`]: the built-in tables below, overlaid
+//! with the user's `[keys]` config section.  Modes where the user types
+//! text (grep, inline search, log search, command) keep their typing
+//! behaviour hardcoded; only non-printable chords are remappable there.
+
+use std::collections::{BTreeMap, HashMap};
 
 use crossterm::event::{KeyCode, KeyEvent, KeyModifiers, MouseEvent, MouseEventKind};
 
+use crate::config::KeysConfig;
+
 const MAX_LINE_NUM: u32 = 999_999;
 
-/// Accumulated numeric prefix for vim/less-style commands.
+/// Accumulated numeric prefix and pending multi-key sequence for
+/// vim/less-style commands.
 ///
 /// Users type digits then a command character: `56g` jumps to line 56,
-/// `10j` scrolls 10 steps down, `56y` yanks line 56.
+/// `10j` scrolls 10 steps down, `56y` yanks line 56.  Keys that start a
+/// user-defined sequence (e.g. the first `g` of `g g`) are held in
+/// `pending` until the sequence completes or is broken.
 pub(super) struct InputAccumulator {
     count: Option<u32>,
+    pending: Vec<KeyChord>,
 }
 
 impl InputAccumulator {
     pub(super) fn new() -> Self {
-        Self { count: None }
+        Self {
+            count: None,
+            pending: Vec::new(),
+        }
     }
 
     /// Feed a digit character ('0'..='9'). Returns false if overflow would occur.
@@ -42,10 +58,11 @@ impl InputAccumulator {
 
     pub(super) fn reset(&mut self) {
         self.count = None;
+        self.pending.clear();
     }
 
     pub(super) fn is_active(&self) -> bool {
-        self.count.is_some()
+        self.count.is_some() || !self.pending.is_empty()
     }
 }
 
@@ -86,153 +103,609 @@ pub(super) enum Action {
     WheelZoomIn(u32),
     /// Ctrl + wheel down (zoom out).
     WheelZoomOut(u32),
-    /// A digit was accumulated; caller should redraw status bar.
-    Digit,
+    /// A count digit or the start of a key sequence was accumulated;
+    /// caller should redraw status bar.
+    Pending,
 }
 
-/// Map a key event to an `Action`, consuming/updating the accumulator as needed.
+// ---------------------------------------------------------------------------
+// Key chords
+// ---------------------------------------------------------------------------
+
+/// One key press as used for binding lookup.
 ///
-/// Returns `None` when the key is unrecognized and no accumulator is active.
-pub(super) fn map_key_event(key: KeyEvent, acc: &mut InputAccumulator) -> Option<Action> {
-    let KeyEvent {
-        code, modifiers, ..
-    } = key;
+/// For `Char` keys SHIFT is folded into the character itself (`G`, not
+/// `shift-g`), since terminals disagree on whether they report it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub(super) struct KeyChord {
+    code: KeyCode,
+    modifiers: KeyModifiers,
+}
 
-    match (code, modifiers) {
-        // 終了 (always immediate)
-        (KeyCode::Char('q'), _) | (KeyCode::Char('c'), KeyModifiers::CONTROL) => Some(Action::Quit),
+impl KeyChord {
+    const fn new(code: KeyCode, modifiers: KeyModifiers) -> Self {
+        Self { code, modifiers }
+    }
 
-        // Esc: cancel pending input
-        (KeyCode::Esc, _) => {
-            acc.reset();
-            Some(Action::CancelInput)
+    fn from_event(key: KeyEvent) -> Self {
+        let mut modifiers = key.modifiers & (KeyModifiers::CONTROL | KeyModifiers::ALT);
+        if !matches!(key.code, KeyCode::Char(_)) {
+            modifiers |= key.modifiers & KeyModifiers::SHIFT;
         }
-
-        // Digits: accumulate
-        (KeyCode::Char(c @ '0'..='9'), KeyModifiers::NONE) => {
-            let d = c as u32 - '0' as u32;
-            acc.push_digit(d);
-            Some(Action::Digit)
+        Self::new(key.code, modifiers)
+    }
+
+    /// Lookup candidates: the exact chord, then the chord without SHIFT
+    /// (so `shift-down` falls back to `down` unless bound separately).
+    fn candidates(self) -> impl Iterator<Item = KeyChord> {
+        let unshifted = (self.modifiers == KeyModifiers::SHIFT)
+            .then_some(Self::new(self.code, KeyModifiers::NONE));
+        std::iter::once(self).chain(unshifted)
+    }
+
+    /// A printable key without Ctrl/Alt — i.e. one that types text.
+    fn is_printable(self) -> bool {
+        matches!(self.code, KeyCode::Char(_)) && self.modifiers.is_empty()
+    }
+
+    /// Parse a chord like `j`, `G`, `ctrl-o`, `alt-shift-up`, `space`.
+    fn parse(spec: &str) -> Result<Self, String> {
+        let mut modifiers = KeyModifiers::NONE;
+        let mut rest = spec;
+        // A trailing `-` is the minus key itself (`ctrl--`), not a separator.
+        while let Some((prefix, tail)) = rest.split_once('-')
+            && !tail.is_empty()
+        {
+            modifiers |= match prefix.to_ascii_lowercase().as_str() {
+                "ctrl" => KeyModifiers::CONTROL,
+                "alt" => KeyModifiers::ALT,
+                "shift" => KeyModifiers::SHIFT,
+                _ => break,
+            };
+            rest = tail;
         }
+        let mut chars = rest.chars();
+        let code = match (chars.next(), chars.next()) {
+            (Some(c), None) => KeyCode::Char(c),
+            _ => match rest.to_ascii_lowercase().as_str() {
+                "esc" => KeyCode::Esc,
+                "enter" => KeyCode::Enter,
+                "tab" => KeyCode::Tab,
+                "backtab" => KeyCode::BackTab,
+                "backspace" => KeyCode::Backspace,
+                "space" => KeyCode::Char(' '),
+                "up" => KeyCode::Up,
+                "down" => KeyCode::Down,
+                "left" => KeyCode::Left,
+                "right" => KeyCode::Right,
+                "home" => KeyCode::Home,
+                "end" => KeyCode::End,
+                "pageup" => KeyCode::PageUp,
+                "pagedown" => KeyCode::PageDown,
+                "insert" => KeyCode::Insert,
+                "delete" => KeyCode::Delete,
+                name => match name.strip_prefix('f').and_then(|n| n.parse::<u8>().ok()) {
+                    Some(n @ 1..=12) => KeyCode::F(n),
+                    _ => return Err(format!("unknown key `{rest}`")),
+                },
+            },
+        };
+        let code = match code {
+            KeyCode::Char(c) if modifiers.contains(KeyModifiers::SHIFT) => {
+                modifiers.remove(KeyModifiers::SHIFT);
+                KeyCode::Char(c.to_ascii_uppercase())
+            }
+            other => other,
+        };
+        Ok(Self::new(code, modifiers))
+    }
+}
 
-        // 下スクロール
-        (KeyCode::Char('j'), _) | (KeyCode::Down, _) => {
-            let count = acc.take().unwrap_or(1);
-            Some(Action::ScrollDown(count))
-        }
-        // 上スクロール
-        (KeyCode::Char('k'), _) | (KeyCode::Up, _) => {
-            let count = acc.take().unwrap_or(1);
-            Some(Action::ScrollUp(count))
-        }
-        // 半画面下
-        (KeyCode::Char('d'), _) => {
-            let count = acc.take().unwrap_or(1);
-            Some(Action::HalfPageDown(count))
-        }
-        // 半画面上
-        (KeyCode::Char('u'), _) => {
-            let count = acc.take().unwrap_or(1);
-            Some(Action::HalfPageUp(count))
+/// Parse a space-separated key sequence (`"g g"`, `"ctrl-w j"`).
+fn parse_sequence(spec: &str) -> Result<Vec<KeyChord>, String> {
+    let seq = spec
+        .split_whitespace()
+        .map(KeyChord::parse)
+        .collect::<Result<Vec<_>, _>>()?;
+    if seq.is_empty() {
+        return Err("empty key".into());
+    }
+    Ok(seq)
+}
+
+// ---------------------------------------------------------------------------
+// Binding tables
+// ---------------------------------------------------------------------------
+
+/// A per-mode action type that can appear in a `[keys.<mode>]` table.
+trait Bindable: Copy + 'static {
+    /// Section name under `[keys]`, used in error messages.
+    const MODE: &'static str;
+    /// Config names of the bindable actions.
+    const NAMES: &'static [(&'static str, Self)];
+    /// Built-in bindings, in config syntax.
+    const DEFAULTS: &'static [(&'static str, Self)];
+    /// Whether multi-key sequences are allowed (normal mode only).
+    const SEQUENCES: bool = false;
+    /// Whether plain printable keys type text in this mode (and so
+    /// cannot be bound).
+    const TEXT_ENTRY: bool = false;
+
+    fn name(self) -> &'static str;
+}
+
+/// Resolved bindings for one mode: key sequence → action.
+struct ModeBindings<C> {
+    map: HashMap<Vec<KeyChord>, C>,
+}
+
+impl<C: Bindable> ModeBindings<C> {
+    fn defaults() -> Self {
+        let map = C::DEFAULTS
+            .iter()
+            .map(|&(spec, cmd)| {
+                let seq = parse_sequence(spec).expect("[BUG] built-in key binding must parse");
+                (seq, cmd)
+            })
+            .collect();
+        Self { map }
+    }
+
+    /// Overlay user bindings on the defaults, appending any problems to
+    /// `errors` (prefixed with the `[keys.<mode>]` location).
+    fn build(user: &BTreeMap<String, String>, errors: &mut Vec<String>) -> Self {
+        let mut bindings = Self::defaults();
+        let mut user_seqs: HashMap<Vec<KeyChord>, &str> = HashMap::new();
+        let mut err = |spec: &str, msg: String| {
+            errors.push(format!("[keys.{}] \"{spec}\": {msg}", C::MODE));
+        };
+
+        for (spec, action) in user {
+            let seq = match parse_sequence(spec) {
+                Ok(seq) => seq,
+                Err(e) => {
+                    err(spec, e);
+                    continue;
+                }
+            };
+            if seq.len() > 1 && !C::SEQUENCES {
+                err(
+                    spec,
+                    "multi-key sequences are only supported in [keys.normal]".into(),
+                );
+                continue;
+            }
+            if C::TEXT_ENTRY && seq[0].is_printable() {
+                err(
+                    spec,
+                    "printable keys type text in this mode and cannot be bound".into(),
+                );
+                continue;
+            }
+            if C::SEQUENCES
+                && matches!(seq[0].code, KeyCode::Char('0'..='9'))
+                && seq[0].is_printable()
+            {
+                err(spec, "digits are reserved for count prefixes".into());
+                continue;
+            }
+            if let Some(prev) = user_seqs.insert(seq.clone(), spec) {
+                err(spec, format!("conflicts with \"{prev}\" (same key)"));
+                continue;
+            }
+            if action == "none" {
+                bindings.map.remove(&seq);
+                continue;
+            }
+            match C::NAMES.iter().find(|(name, _)| name == action) {
+                Some(&(_, cmd)) => {
+                    bindings.map.insert(seq, cmd);
+                }
+                None => {
+                    let valid: Vec<_> = C::NAMES.iter().map(|(n, _)| *n).collect();
+                    err(
+                        spec,
+                        format!(
+                            "unknown action `{action}` (expected one of: none, {})",
+                            valid.join(", ")
+                        ),
+                    );
+                }
+            }
         }
-        // 先頭 / ジャンプ
-        (KeyCode::Char('g'), _) => match acc.take() {
-            None => Some(Action::JumpToTop),
-            Some(n) => Some(Action::JumpToLine(n)),
-        },
-        // 末尾 / ジャンプ
-        (KeyCode::Char('G'), _) => match acc.take() {
-            None => Some(Action::JumpToBottom),
-            Some(n) => Some(Action::JumpToLine(n)),
-        },
-
-        // 精密ヤンク (y)
-        (KeyCode::Char('y'), _) => match acc.take() {
-            None => Some(Action::YankExactPrompt),
-            Some(n) => Some(Action::YankExact(n)),
-        },
-
-        // ブロックヤンク (Y)
-        (KeyCode::Char('Y'), _) => match acc.take() {
-            None => Some(Action::YankBlockPrompt),
-            Some(n) => Some(Action::YankBlock(n)),
-        },
-
-        // Go back (Ctrl-O: jump stack pop)
-        (KeyCode::Char('o'), KeyModifiers::CONTROL) => {
-            acc.reset();
-            Some(Action::GoBack)
+
+        // A binding that is a strict prefix of another makes the longer
+        // one unreachable.  Only report pairs the user is responsible for.
+        let mut conflicts: Vec<String> = Vec::new();
+        for (seq, cmd) in &bindings.map {
+            for len in 1..seq.len() {
+                let Some(short) = bindings.map.get(&seq[..len]) else {
+                    continue;
+                };
+                let long_spec = user_seqs.get(seq).copied();
+                let short_spec = user_seqs.get(&seq[..len]).copied();
+                if long_spec.is_none() && short_spec.is_none() {
+                    continue;
+                }
+                let short_spec =
+                    short_spec.map_or_else(|| format_sequence(&seq[..len]), str::to_string);
+                let long_spec = long_spec.map_or_else(|| format_sequence(seq), str::to_string);
+                conflicts.push(format!(
+                    "[keys.{}] \"{long_spec}\" ({}) is shadowed by \"{short_spec}\" ({}); \
+                     unbind it with \"{short_spec}\" = \"none\"",
+                    C::MODE,
+                    cmd.name(),
+                    short.name(),
+                ));
+            }
         }
+        conflicts.sort();
+        errors.extend(conflicts);
+        bindings
+    }
 
-        // URL を開く (o)
-        (KeyCode::Char('o'), _) => match acc.take() {
-            None => Some(Action::OpenUrlPrompt),
-            Some(n) => Some(Action::OpenUrl(n)),
-        },
+    /// Look up a single key press.
+    fn lookup(&self, key: KeyEvent) -> Option<C> {
+        KeyChord::from_event(key)
+            .candidates()
+            .find_map(|chord| self.map.get(std::slice::from_ref(&chord)).copied())
+    }
 
-        // URL picker (O)
-        (KeyCode::Char('O'), _) => {
-            acc.reset();
-            Some(Action::EnterUrlPicker)
-        }
+    fn is_prefix(&self, seq: &[KeyChord]) -> bool {
+        self.map
+            .keys()
+            .any(|k| k.len() > seq.len() && k.starts_with(seq))
+    }
+}
 
-        // TOC overlay (t)
-        (KeyCode::Char('t'), KeyModifiers::NONE) => {
-            acc.reset();
-            Some(Action::EnterToc)
+/// Render a built-in sequence back to config syntax for error messages.
+fn format_sequence(seq: &[KeyChord]) -> String {
+    seq.iter()
+        .map(|c| {
+            let mut s = String::new();
+            if c.modifiers.contains(KeyModifiers::CONTROL) {
+                s.push_str("ctrl-");
+            }
+            if c.modifiers.contains(KeyModifiers::ALT) {
+                s.push_str("alt-");
+            }
+            if c.modifiers.contains(KeyModifiers::SHIFT) {
+                s.push_str("shift-");
+            }
+            match c.code {
+                KeyCode::Char(' ') => s.push_str("space"),
+                KeyCode::Char(ch) => s.push(ch),
+                KeyCode::F(n) => s.push_str(&format!("f{n}")),
+                other => s.push_str(&format!("{other:?}").to_ascii_lowercase()),
+            }
+            s
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// All viewer key bindings, built once at startup from the `[keys]` config.
+pub(super) struct Keymap {
+    normal: ModeBindings<NormalCommand>,
+    toc: ModeBindings<TocAction>,
+    url: ModeBindings<UrlAction>,
+    grep: ModeBindings<GrepAction>,
+    log: ModeBindings<LogAction>,
+    inline_search: ModeBindings<InlineSearchAction>,
+}
+
+impl Default for Keymap {
+    fn default() -> Self {
+        Self {
+            normal: ModeBindings::defaults(),
+            toc: ModeBindings::defaults(),
+            url: ModeBindings::defaults(),
+            grep: ModeBindings::defaults(),
+            log: ModeBindings::defaults(),
+            inline_search: ModeBindings::defaults(),
         }
+    }
+}
 
-        // 前方検索
-        (KeyCode::Char('/'), _) => {
-            acc.reset();
-            Some(Action::EnterInlineSearch)
+impl Keymap {
+    /// Build the keymap from the `[keys]` config section.
+    ///
+    /// All problems (unknown keys or actions, conflicting bindings) are
+    /// collected and reported together.
+    pub(super) fn from_config(keys: &KeysConfig) -> anyhow::Result<Self> {
+        let mut errors = Vec::new();
+        let keymap = Self {
+            normal: ModeBindings::build(&keys.normal, &mut errors),
+            toc: ModeBindings::build(&keys.toc, &mut errors),
+            url: ModeBindings::build(&keys.url, &mut errors),
+            grep: ModeBindings::build(&keys.grep, &mut errors),
+            log: ModeBindings::build(&keys.log, &mut errors),
+            inline_search: ModeBindings::build(&keys.inline_search, &mut errors),
+        };
+        if !errors.is_empty() {
+            anyhow::bail!("invalid key bindings:\n  {}", errors.join("\n  "));
         }
-        // 後方検索
-        (KeyCode::Char('?'), _) => {
-            acc.reset();
-            Some(Action::EnterBackwardSearch)
+        Ok(keymap)
+    }
+
+    /// Map a key event to an `Action`, consuming/updating the accumulator as needed.
+    ///
+    /// Returns `None` when the key is unrecognized and no accumulator is active.
+    pub(super) fn map_key_event(
+        &self,
+        key: KeyEvent,
+        acc: &mut InputAccumulator,
+    ) -> Option<Action> {
+        // Digits: accumulate (only outside a pending sequence)
+        if acc.pending.is_empty()
+            && let (KeyCode::Char(c @ '0'..='9'), KeyModifiers::NONE) = (key.code, key.modifiers)
+        {
+            acc.push_digit(c as u32 - '0' as u32);
+            return Some(Action::Pending);
         }
-        // コマンドモード
-        (KeyCode::Char(':'), _) => {
-            acc.reset();
-            Some(Action::EnterCommand)
+
+        for chord in KeyChord::from_event(key).candidates() {
+            let mut seq = acc.pending.clone();
+            seq.push(chord);
+            if let Some(&cmd) = self.normal.map.get(&seq) {
+                acc.pending.clear();
+                return Some(cmd.into_action(acc));
+            }
+            if self.normal.is_prefix(&seq) {
+                acc.pending = seq;
+                return Some(Action::Pending);
+            }
         }
-        // 次のマッチへジャンプ
-        (KeyCode::Char('n'), KeyModifiers::NONE) => {
+
+        if acc.is_active() {
             acc.reset();
-            Some(Action::SearchNextMatch)
+            Some(Action::CancelInput)
+        } else {
+            None
         }
-        // 前のマッチへジャンプ
-        (KeyCode::Char('N'), KeyModifiers::SHIFT) => {
-            acc.reset();
-            Some(Action::SearchPrevMatch)
+    }
+
+    /// Map a key event to a TOC action.
+    pub(super) fn map_toc_key(&self, key: KeyEvent) -> Option<TocAction> {
+        self.toc.lookup(key)
+    }
+
+    /// Map a key event to a URL picker action.
+    pub(super) fn map_url_key(&self, key: KeyEvent) -> Option<UrlAction> {
+        self.url.lookup(key)
+    }
+
+    /// Map a key event to a search-mode action.
+    pub(super) fn map_grep_key(&self, key: KeyEvent) -> Option<GrepAction> {
+        if let Some(a) = self.grep.lookup(key) {
+            return Some(a);
         }
+        let KeyEvent {
+            code, modifiers, ..
+        } = key;
 
-        // Zoom: + 拡大 / - 縮小 / = リセット
-        (KeyCode::Char('+'), _) => {
-            acc.reset();
-            Some(Action::ZoomIn)
+        match (code, modifiers) {
+            (KeyCode::Backspace, _) => Some(GrepAction::Backspace),
+            (KeyCode::Char(c @ '1'..='9'), KeyModifiers::ALT) => {
+                Some(GrepAction::SelectIndex((c as u8 - b'1') as usize))
+            }
+            (KeyCode::Char(c), _) => Some(GrepAction::Type(c)),
+            _ => None,
         }
-        (KeyCode::Char('-'), _) => {
-            acc.reset();
-            Some(Action::ZoomOut)
+    }
+
+    /// Map a key event to an inline search action.
+    pub(super) fn map_inline_search_key(&self, key: KeyEvent) -> Option<InlineSearchAction> {
+        if let Some(a) = self.inline_search.lookup(key) {
+            return Some(a);
         }
-        (KeyCode::Char('='), _) => {
-            acc.reset();
-            Some(Action::ZoomReset)
+        match key.code {
+            KeyCode::Backspace => Some(InlineSearchAction::Backspace),
+            KeyCode::Char(c) => Some(InlineSearchAction::Type(c)),
+            _ => None,
         }
+    }
 
-        _ => {
-            if acc.is_active() {
+    /// Map a key event to a log viewer action.
+    ///
+    /// When `search_mode` is true, all character keys become `Type(c)` so the user
+    /// can type any character into the search query (matching the pattern used by
+    /// `map_command_key`, `map_inline_search_key`, and `map_grep_key`).  Only the
+    /// non-search table is remappable.
+    pub(super) fn map_log_key(&self, key: KeyEvent, search_mode: bool) -> Option<LogAction> {
+        let KeyEvent {
+            code, modifiers, ..
+        } = key;
+        if search_mode {
+            match (code, modifiers) {
+                (KeyCode::Esc, _) | (KeyCode::Char('c'), KeyModifiers::CONTROL) => {
+                    Some(LogAction::Cancel)
+                }
+                (KeyCode::Enter, _) => Some(LogAction::ConfirmSearch),
+                (KeyCode::Backspace, _) => Some(LogAction::Backspace),
+                (KeyCode::Down, _) => Some(LogAction::ScrollDown),
+                (KeyCode::Up, _) => Some(LogAction::ScrollUp),
+                (KeyCode::Char(c), _) => Some(LogAction::Type(c)),
+                _ => None,
+            }
+        } else {
+            if let Some(a) = self.log.lookup(key) {
+                return Some(a);
+            }
+            match (code, modifiers) {
+                (KeyCode::Backspace, _) => Some(LogAction::Backspace),
+                (KeyCode::Char(c), _) => Some(LogAction::Type(c)),
+                _ => None,
+            }
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Normal mode
+// ---------------------------------------------------------------------------
+
+/// Bindable normal-mode commands.  Count-aware commands consume the
+/// accumulated numeric prefix when turned into an [`Action`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(super) enum NormalCommand {
+    Quit,
+    Cancel,
+    ScrollDown,
+    ScrollUp,
+    HalfPageDown,
+    HalfPageUp,
+    /// `g`: top, or line N with a count.
+    GotoTop,
+    /// `G`: bottom, or line N with a count.
+    GotoBottom,
+    YankExact,
+    YankBlock,
+    OpenUrl,
+    UrlPicker,
+    Toc,
+    SearchForward,
+    SearchBackward,
+    Command,
+    SearchNext,
+    SearchPrev,
+    GoBack,
+    ZoomIn,
+    ZoomOut,
+    ZoomReset,
+}
+
+impl NormalCommand {
+    fn into_action(self, acc: &mut InputAccumulator) -> Action {
+        match self {
+            // 終了 (always immediate)
+            Self::Quit => Action::Quit,
+            Self::ScrollDown => Action::ScrollDown(acc.take().unwrap_or(1)),
+            Self::ScrollUp => Action::ScrollUp(acc.take().unwrap_or(1)),
+            Self::HalfPageDown => Action::HalfPageDown(acc.take().unwrap_or(1)),
+            Self::HalfPageUp => Action::HalfPageUp(acc.take().unwrap_or(1)),
+            Self::GotoTop => match acc.take() {
+                None => Action::JumpToTop,
+                Some(n) => Action::JumpToLine(n),
+            },
+            Self::GotoBottom => match acc.take() {
+                None => Action::JumpToBottom,
+                Some(n) => Action::JumpToLine(n),
+            },
+            Self::YankExact => match acc.take() {
+                None => Action::YankExactPrompt,
+                Some(n) => Action::YankExact(n),
+            },
+            Self::YankBlock => match acc.take() {
+                None => Action::YankBlockPrompt,
+                Some(n) => Action::YankBlock(n),
+            },
+            Self::OpenUrl => match acc.take() {
+                None => Action::OpenUrlPrompt,
+                Some(n) => Action::OpenUrl(n),
+            },
+            // Everything else discards a pending count.
+            other => {
                 acc.reset();
-                Some(Action::CancelInput)
-            } else {
-                None
+                match other {
+                    Self::Cancel => Action::CancelInput,
+                    Self::UrlPicker => Action::EnterUrlPicker,
+                    Self::Toc => Action::EnterToc,
+                    Self::SearchForward => Action::EnterInlineSearch,
+                    Self::SearchBackward => Action::EnterBackwardSearch,
+                    Self::Command => Action::EnterCommand,
+                    Self::SearchNext => Action::SearchNextMatch,
+                    Self::SearchPrev => Action::SearchPrevMatch,
+                    Self::GoBack => Action::GoBack,
+                    Self::ZoomIn => Action::ZoomIn,
+                    Self::ZoomOut => Action::ZoomOut,
+                    Self::ZoomReset => Action::ZoomReset,
+                    _ => unreachable!("count-aware commands handled above"),
+                }
             }
         }
     }
 }
 
+impl Bindable for NormalCommand {
+    const MODE: &'static str = "normal";
+    const SEQUENCES: bool = true;
+    const NAMES: &'static [(&'static str, Self)] = &[
+        ("quit", Self::Quit),
+        ("cancel", Self::Cancel),
+        ("scroll-down", Self::ScrollDown),
+        ("scroll-up", Self::ScrollUp),
+        ("half-page-down", Self::HalfPageDown),
+        ("half-page-up", Self::HalfPageUp),
+        ("goto-top", Self::GotoTop),
+        ("goto-bottom", Self::GotoBottom),
+        ("yank-line", Self::YankExact),
+        ("yank-block", Self::YankBlock),
+        ("open-url", Self::OpenUrl),
+        ("url-picker", Self::UrlPicker),
+        ("toc", Self::Toc),
+        ("search-forward", Self::SearchForward),
+        ("search-backward", Self::SearchBackward),
+        ("command", Self::Command),
+        ("search-next", Self::SearchNext),
+        ("search-prev", Self::SearchPrev),
+        ("go-back", Self::GoBack),
+        ("zoom-in", Self::ZoomIn),
+        ("zoom-out", Self::ZoomOut),
+        ("zoom-reset", Self::ZoomReset),
+    ];
+    const DEFAULTS: &'static [(&'static str, Self)] = &[
+        ("q", Self::Quit),
+        ("ctrl-c", Self::Quit),
+        ("esc", Self::Cancel),
+        // 下スクロール / 上スクロール
+        ("j", Self::ScrollDown),
+        ("down", Self::ScrollDown),
+        ("k", Self::ScrollUp),
+        ("up", Self::ScrollUp),
+        // 半画面下 / 半画面上
+        ("d", Self::HalfPageDown),
+        ("ctrl-d", Self::HalfPageDown),
+        ("u", Self::HalfPageUp),
+        ("ctrl-u", Self::HalfPageUp),
+        // 先頭 / 末尾 / ジャンプ
+        ("g", Self::GotoTop),
+        ("G", Self::GotoBottom),
+        // 精密ヤンク / ブロックヤンク
+        ("y", Self::YankExact),
+        ("Y", Self::YankBlock),
+        // Go back (jump stack pop)
+        ("ctrl-o", Self::GoBack),
+        // URL を開く / URL picker
+        ("o", Self::OpenUrl),
+        ("O", Self::UrlPicker),
+        ("t", Self::Toc),
+        // 前方検索 / 後方検索 / コマンドモード
+        ("/", Self::SearchForward),
+        ("?", Self::SearchBackward),
+        (":", Self::Command),
+        // 次 / 前のマッチへジャンプ
+        ("n", Self::SearchNext),
+        ("N", Self::SearchPrev),
+        // Zoom: + 拡大 / - 縮小 / = リセット
+        ("+", Self::ZoomIn),
+        ("-", Self::ZoomOut),
+        ("=", Self::ZoomReset),
+    ];
+
+    fn name(self) -> &'static str {
+        name_of(Self::NAMES, self)
+    }
+}
+
+/// Reverse lookup in a `NAMES` table.
+fn name_of<C: Bindable + PartialEq>(names: &'static [(&'static str, C)], cmd: C) -> &'static str {
+    names
+        .iter()
+        .find(|(_, c)| *c == cmd)
+        .map_or("?", |(n, _)| n)
+}
+
 /// Map a mouse event to a Normal-mode `Action`.
 ///
 /// Stateless: wheel input never feeds the digit accumulator. Only vertical
@@ -251,7 +724,12 @@ pub(super) fn map_mouse_event(me: MouseEvent) -> Option<Action> {
     }
 }
 
+// ---------------------------------------------------------------------------
+// Other modes
+// ---------------------------------------------------------------------------
+
 /// Actions specific to search mode.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub(super) enum GrepAction {
     Type(char),
     Backspace,
@@ -262,6 +740,28 @@ pub(super) enum GrepAction {
     Cancel,
 }
 
+impl Bindable for GrepAction {
+    const MODE: &'static str = "grep";
+    const TEXT_ENTRY: bool = true;
+    const NAMES: &'static [(&'static str, Self)] = &[
+        ("select-next", Self::SelectNext),
+        ("select-prev", Self::SelectPrev),
+        ("confirm", Self::Confirm),
+        ("cancel", Self::Cancel),
+    ];
+    const DEFAULTS: &'static [(&'static str, Self)] = &[
+        ("esc", Self::Cancel),
+        ("ctrl-c", Self::Cancel),
+        ("enter", Self::Confirm),
+        ("down", Self::SelectNext),
+        ("up", Self::SelectPrev),
+    ];
+
+    fn name(self) -> &'static str {
+        name_of(Self::NAMES, self)
+    }
+}
+
 /// Actions specific to command mode (`:` prompt).
 pub(super) enum CommandAction {
     Type(char),
@@ -288,6 +788,7 @@ pub(super) fn map_command_key(key: KeyEvent) -> Option<CommandAction> {
 }
 
 /// Actions specific to inline search mode (`/` prompt).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub(super) enum InlineSearchAction {
     Type(char),
     Backspace,
@@ -295,24 +796,24 @@ pub(super) enum InlineSearchAction {
     Cancel,
 }
 
-/// Map a key event to an inline search action.
-pub(super) fn map_inline_search_key(key: KeyEvent) -> Option<InlineSearchAction> {
-    let KeyEvent {
-        code, modifiers, ..
-    } = key;
+impl Bindable for InlineSearchAction {
+    const MODE: &'static str = "inline-search";
+    const TEXT_ENTRY: bool = true;
+    const NAMES: &'static [(&'static str, Self)] =
+        &[("confirm", Self::Confirm), ("cancel", Self::Cancel)];
+    const DEFAULTS: &'static [(&'static str, Self)] = &[
+        ("esc", Self::Cancel),
+        ("ctrl-c", Self::Cancel),
+        ("enter", Self::Confirm),
+    ];
 
-    match (code, modifiers) {
-        (KeyCode::Esc, _) | (KeyCode::Char('c'), KeyModifiers::CONTROL) => {
-            Some(InlineSearchAction::Cancel)
-        }
-        (KeyCode::Enter, _) => Some(InlineSearchAction::Confirm),
-        (KeyCode::Backspace, _) => Some(InlineSearchAction::Backspace),
-        (KeyCode::Char(c), _) => Some(InlineSearchAction::Type(c)),
-        _ => None,
+    fn name(self) -> &'static str {
+        name_of(Self::NAMES, self)
     }
 }
 
 /// Actions specific to TOC overlay mode.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub(super) enum TocAction {
     SelectNext,
     SelectPrev,
@@ -321,25 +822,33 @@ pub(super) enum TocAction {
     Quit,
 }
 
-/// Map a key event to a TOC action.
-pub(super) fn map_toc_key(key: KeyEvent) -> Option<TocAction> {
-    let KeyEvent {
-        code, modifiers, ..
-    } = key;
-
-    match (code, modifiers) {
-        (KeyCode::Char('q'), _) => Some(TocAction::Quit),
-        (KeyCode::Esc, _) | (KeyCode::Char('c'), KeyModifiers::CONTROL) => Some(TocAction::Cancel),
-        (KeyCode::Enter, _) => Some(TocAction::Confirm),
-        (KeyCode::Char('j'), KeyModifiers::NONE) | (KeyCode::Down, _) => {
-            Some(TocAction::SelectNext)
-        }
-        (KeyCode::Char('k'), KeyModifiers::NONE) | (KeyCode::Up, _) => Some(TocAction::SelectPrev),
-        _ => None,
+impl Bindable for TocAction {
+    const MODE: &'static str = "toc";
+    const NAMES: &'static [(&'static str, Self)] = &[
+        ("select-next", Self::SelectNext),
+        ("select-prev", Self::SelectPrev),
+        ("confirm", Self::Confirm),
+        ("cancel", Self::Cancel),
+        ("quit", Self::Quit),
+    ];
+    const DEFAULTS: &'static [(&'static str, Self)] = &[
+        ("q", Self::Quit),
+        ("esc", Self::Cancel),
+        ("ctrl-c", Self::Cancel),
+        ("enter", Self::Confirm),
+        ("j", Self::SelectNext),
+        ("down", Self::SelectNext),
+        ("k", Self::SelectPrev),
+        ("up", Self::SelectPrev),
+    ];
+
+    fn name(self) -> &'static str {
+        name_of(Self::NAMES, self)
     }
 }
 
 /// Actions specific to URL picker mode.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub(super) enum UrlAction {
     SelectNext,
     SelectPrev,
@@ -347,8 +856,31 @@ pub(super) enum UrlAction {
     Cancel,
 }
 
+impl Bindable for UrlAction {
+    const MODE: &'static str = "url";
+    const NAMES: &'static [(&'static str, Self)] = &[
+        ("select-next", Self::SelectNext),
+        ("select-prev", Self::SelectPrev),
+        ("confirm", Self::Confirm),
+        ("cancel", Self::Cancel),
+    ];
+    const DEFAULTS: &'static [(&'static str, Self)] = &[
+        ("esc", Self::Cancel),
+        ("ctrl-c", Self::Cancel),
+        ("enter", Self::Confirm),
+        ("j", Self::SelectNext),
+        ("down", Self::SelectNext),
+        ("k", Self::SelectPrev),
+        ("up", Self::SelectPrev),
+    ];
+
+    fn name(self) -> &'static str {
+        name_of(Self::NAMES, self)
+    }
+}
+
 /// Actions specific to log viewer mode.
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub(super) enum LogAction {
     ScrollDown,
     ScrollUp,
@@ -364,85 +896,37 @@ pub(super) enum LogAction {
     Cancel,
 }
 
-/// Map a key event to a log viewer action.
-///
-/// When `search_mode` is true, all character keys become `Type(c)` so the user
-/// can type any character into the search query (matching the pattern used by
-/// `map_command_key`, `map_inline_search_key`, and `map_grep_key`).
-pub(super) fn map_log_key(key: KeyEvent, search_mode: bool) -> Option<LogAction> {
-    let KeyEvent {
-        code, modifiers, ..
-    } = key;
-    if search_mode {
-        match (code, modifiers) {
-            (KeyCode::Esc, _) | (KeyCode::Char('c'), KeyModifiers::CONTROL) => {
-                Some(LogAction::Cancel)
-            }
-            (KeyCode::Enter, _) => Some(LogAction::ConfirmSearch),
-            (KeyCode::Backspace, _) => Some(LogAction::Backspace),
-            (KeyCode::Down, _) => Some(LogAction::ScrollDown),
-            (KeyCode::Up, _) => Some(LogAction::ScrollUp),
-            (KeyCode::Char(c), _) => Some(LogAction::Type(c)),
-            _ => None,
-        }
-    } else {
-        match (code, modifiers) {
-            (KeyCode::Char('q'), _)
-            | (KeyCode::Esc, _)
-            | (KeyCode::Char('c'), KeyModifiers::CONTROL) => Some(LogAction::Cancel),
-            (KeyCode::Char('j'), KeyModifiers::NONE) | (KeyCode::Down, _) => {
-                Some(LogAction::ScrollDown)
-            }
-            (KeyCode::Char('k'), KeyModifiers::NONE) | (KeyCode::Up, _) => {
-                Some(LogAction::ScrollUp)
-            }
-            (KeyCode::Char('g'), KeyModifiers::NONE) => Some(LogAction::JumpToTop),
-            (KeyCode::Char('G'), _) => Some(LogAction::JumpToBottom),
-            (KeyCode::Char('/'), _) => Some(LogAction::EnterSearch),
-            (KeyCode::Char('n'), KeyModifiers::NONE) => Some(LogAction::SearchNext),
-            (KeyCode::Char('N'), _) => Some(LogAction::SearchPrev),
-            (KeyCode::Char('y'), KeyModifiers::NONE) => Some(LogAction::Yank),
-            (KeyCode::Backspace, _) => Some(LogAction::Backspace),
-            (KeyCode::Char(c), _) => Some(LogAction::Type(c)),
-            _ => None,
-        }
-    }
-}
-
-/// Map a key event to a URL picker action.
-pub(super) fn map_url_key(key: KeyEvent) -> Option<UrlAction> {
-    let KeyEvent {
-        code, modifiers, ..
-    } = key;
-
-    match (code, modifiers) {
-        (KeyCode::Esc, _) | (KeyCode::Char('c'), KeyModifiers::CONTROL) => Some(UrlAction::Cancel),
-        (KeyCode::Enter, _) => Some(UrlAction::Confirm),
-        (KeyCode::Char('j'), KeyModifiers::NONE) | (KeyCode::Down, _) => {
-            Some(UrlAction::SelectNext)
-        }
-        (KeyCode::Char('k'), KeyModifiers::NONE) | (KeyCode::Up, _) => Some(UrlAction::SelectPrev),
-        _ => None,
-    }
-}
-
-/// Map a key event to a search-mode action.
-pub(super) fn map_grep_key(key: KeyEvent) -> Option<GrepAction> {
-    let KeyEvent {
-        code, modifiers, ..
-    } = key;
-
-    match (code, modifiers) {
-        (KeyCode::Esc, _) | (KeyCode::Char('c'), KeyModifiers::CONTROL) => Some(GrepAction::Cancel),
-        (KeyCode::Enter, _) => Some(GrepAction::Confirm),
-        (KeyCode::Backspace, _) => Some(GrepAction::Backspace),
-        (KeyCode::Down, _) => Some(GrepAction::SelectNext),
-        (KeyCode::Up, _) => Some(GrepAction::SelectPrev),
-        (KeyCode::Char(c @ '1'..='9'), KeyModifiers::ALT) => {
-            Some(GrepAction::SelectIndex((c as u8 - b'1') as usize))
-        }
-        (KeyCode::Char(c), _) => Some(GrepAction::Type(c)),
-        _ => None,
+impl Bindable for LogAction {
+    const MODE: &'static str = "log";
+    const NAMES: &'static [(&'static str, Self)] = &[
+        ("scroll-down", Self::ScrollDown),
+        ("scroll-up", Self::ScrollUp),
+        ("goto-top", Self::JumpToTop),
+        ("goto-bottom", Self::JumpToBottom),
+        ("search", Self::EnterSearch),
+        ("search-next", Self::SearchNext),
+        ("search-prev", Self::SearchPrev),
+        ("yank", Self::Yank),
+        ("cancel", Self::Cancel),
+    ];
+    const DEFAULTS: &'static [(&'static str, Self)] = &[
+        ("q", Self::Cancel),
+        ("esc", Self::Cancel),
+        ("ctrl-c", Self::Cancel),
+        ("j", Self::ScrollDown),
+        ("down", Self::ScrollDown),
+        ("k", Self::ScrollUp),
+        ("up", Self::ScrollUp),
+        ("g", Self::JumpToTop),
+        ("G", Self::JumpToBottom),
+        ("/", Self::EnterSearch),
+        ("n", Self::SearchNext),
+        ("N", Self::SearchPrev),
+        ("y", Self::Yank),
+    ];
+
+    fn name(self) -> &'static str {
+        name_of(Self::NAMES, self)
     }
 }
 
@@ -464,53 +948,57 @@ mod tests {
         key(code, KeyModifiers::NONE)
     }
 
+    fn km() -> Keymap {
+        Keymap::default()
+    }
+
     #[test]
     fn test_5j_scroll_down() {
         let mut acc = InputAccumulator::new();
         // Type '5'
-        let a = map_key_event(simple_key(KeyCode::Char('5')), &mut acc);
-        assert!(matches!(a, Some(Action::Digit)));
+        let a = km().map_key_event(simple_key(KeyCode::Char('5')), &mut acc);
+        assert!(matches!(a, Some(Action::Pending)));
         // Type 'j'
-        let a = map_key_event(simple_key(KeyCode::Char('j')), &mut acc);
+        let a = km().map_key_event(simple_key(KeyCode::Char('j')), &mut acc);
         assert!(matches!(a, Some(Action::ScrollDown(5))));
     }
 
     #[test]
     fn test_g_without_prefix_jumps_top() {
         let mut acc = InputAccumulator::new();
-        let a = map_key_event(simple_key(KeyCode::Char('g')), &mut acc);
+        let a = km().map_key_event(simple_key(KeyCode::Char('g')), &mut acc);
         assert!(matches!(a, Some(Action::JumpToTop)));
     }
 
     #[test]
     fn test_56g_jumps_to_line() {
         let mut acc = InputAccumulator::new();
-        map_key_event(simple_key(KeyCode::Char('5')), &mut acc);
-        map_key_event(simple_key(KeyCode::Char('6')), &mut acc);
-        let a = map_key_event(simple_key(KeyCode::Char('g')), &mut acc);
+        km().map_key_event(simple_key(KeyCode::Char('5')), &mut acc);
+        km().map_key_event(simple_key(KeyCode::Char('6')), &mut acc);
+        let a = km().map_key_event(simple_key(KeyCode::Char('g')), &mut acc);
         assert!(matches!(a, Some(Action::JumpToLine(56))));
     }
 
     #[test]
     fn test_q_quits() {
         let mut acc = InputAccumulator::new();
-        let a = map_key_event(simple_key(KeyCode::Char('q')), &mut acc);
+        let a = km().map_key_event(simple_key(KeyCode::Char('q')), &mut acc);
         assert!(matches!(a, Some(Action::Quit)));
     }
 
     #[test]
     fn test_ctrl_c_quits() {
         let mut acc = InputAccumulator::new();
-        let a = map_key_event(key(KeyCode::Char('c'), KeyModifiers::CONTROL), &mut acc);
+        let a = km().map_key_event(key(KeyCode::Char('c'), KeyModifiers::CONTROL), &mut acc);
         assert!(matches!(a, Some(Action::Quit)));
     }
 
     #[test]
     fn test_esc_cancels_input() {
         let mut acc = InputAccumulator::new();
-        map_key_event(simple_key(KeyCode::Char('5')), &mut acc);
+        km().map_key_event(simple_key(KeyCode::Char('5')), &mut acc);
         assert!(acc.is_active());
-        let a = map_key_event(simple_key(KeyCode::Esc), &mut acc);
+        let a = km().map_key_event(simple_key(KeyCode::Esc), &mut acc);
         assert!(matches!(a, Some(Action::CancelInput)));
         assert!(!acc.is_active());
     }
@@ -518,16 +1006,16 @@ mod tests {
     #[test]
     fn test_unknown_key_returns_none() {
         let mut acc = InputAccumulator::new();
-        let a = map_key_event(simple_key(KeyCode::Char('x')), &mut acc);
+        let a = km().map_key_event(simple_key(KeyCode::Char('x')), &mut acc);
         assert!(a.is_none());
     }
 
     #[test]
     fn test_unknown_key_with_accumulator_cancels_input() {
         let mut acc = InputAccumulator::new();
-        map_key_event(simple_key(KeyCode::Char('5')), &mut acc);
+        km().map_key_event(simple_key(KeyCode::Char('5')), &mut acc);
         assert!(acc.is_active());
-        let a = map_key_event(simple_key(KeyCode::Char('x')), &mut acc);
+        let a = km().map_key_event(simple_key(KeyCode::Char('x')), &mut acc);
         assert!(matches!(a, Some(Action::CancelInput)));
         assert!(!acc.is_active());
     }
@@ -535,22 +1023,22 @@ mod tests {
     #[test]
     fn test_yank_with_prefix() {
         let mut acc = InputAccumulator::new();
-        map_key_event(simple_key(KeyCode::Char('3')), &mut acc);
-        let a = map_key_event(simple_key(KeyCode::Char('y')), &mut acc);
+        km().map_key_event(simple_key(KeyCode::Char('3')), &mut acc);
+        let a = km().map_key_event(simple_key(KeyCode::Char('y')), &mut acc);
         assert!(matches!(a, Some(Action::YankExact(3))));
     }
 
     #[test]
     fn test_yank_without_prefix() {
         let mut acc = InputAccumulator::new();
-        let a = map_key_event(simple_key(KeyCode::Char('y')), &mut acc);
+        let a = km().map_key_event(simple_key(KeyCode::Char('y')), &mut acc);
         assert!(matches!(a, Some(Action::YankExactPrompt)));
     }
 
     #[test]
     fn test_big_g_bottom() {
         let mut acc = InputAccumulator::new();
-        let a = map_key_event(key(KeyCode::Char('G'), KeyModifiers::SHIFT), &mut acc);
+        let a = km().map_key_event(key(KeyCode::Char('G'), KeyModifiers::SHIFT), &mut acc);
         assert!(matches!(a, Some(Action::JumpToBottom)));
     }
 
@@ -559,16 +1047,16 @@ mod tests {
     #[test]
     fn test_ctrl_o_goes_back() {
         let mut acc = InputAccumulator::new();
-        let a = map_key_event(key(KeyCode::Char('o'), KeyModifiers::CONTROL), &mut acc);
+        let a = km().map_key_event(key(KeyCode::Char('o'), KeyModifiers::CONTROL), &mut acc);
         assert!(matches!(a, Some(Action::GoBack)));
     }
 
     #[test]
     fn test_ctrl_o_resets_accumulator() {
         let mut acc = InputAccumulator::new();
-        map_key_event(simple_key(KeyCode::Char('5')), &mut acc);
+        km().map_key_event(simple_key(KeyCode::Char('5')), &mut acc);
         assert!(acc.is_active());
-        map_key_event(key(KeyCode::Char('o'), KeyModifiers::CONTROL), &mut acc);
+        km().map_key_event(key(KeyCode::Char('o'), KeyModifiers::CONTROL), &mut acc);
         assert!(!acc.is_active());
     }
 
@@ -577,22 +1065,22 @@ mod tests {
     #[test]
     fn test_o_open_url_prompt() {
         let mut acc = InputAccumulator::new();
-        let a = map_key_event(simple_key(KeyCode::Char('o')), &mut acc);
+        let a = km().map_key_event(simple_key(KeyCode::Char('o')), &mut acc);
         assert!(matches!(a, Some(Action::OpenUrlPrompt)));
     }
 
     #[test]
     fn test_5o_open_url() {
         let mut acc = InputAccumulator::new();
-        map_key_event(simple_key(KeyCode::Char('5')), &mut acc);
-        let a = map_key_event(simple_key(KeyCode::Char('o')), &mut acc);
+        km().map_key_event(simple_key(KeyCode::Char('5')), &mut acc);
+        let a = km().map_key_event(simple_key(KeyCode::Char('o')), &mut acc);
         assert!(matches!(a, Some(Action::OpenUrl(5))));
     }
 
     #[test]
     fn test_big_o_enters_url_picker() {
         let mut acc = InputAccumulator::new();
-        let a = map_key_event(key(KeyCode::Char('O'), KeyModifiers::SHIFT), &mut acc);
+        let a = km().map_key_event(key(KeyCode::Char('O'), KeyModifiers::SHIFT), &mut acc);
         assert!(matches!(a, Some(Action::EnterUrlPicker)));
     }
 
@@ -601,30 +1089,30 @@ mod tests {
     #[test]
     fn test_slash_enters_inline_search() {
         let mut acc = InputAccumulator::new();
-        let a = map_key_event(simple_key(KeyCode::Char('/')), &mut acc);
+        let a = km().map_key_event(simple_key(KeyCode::Char('/')), &mut acc);
         assert!(matches!(a, Some(Action::EnterInlineSearch)));
     }
 
     #[test]
     fn test_slash_resets_accumulator() {
         let mut acc = InputAccumulator::new();
-        map_key_event(simple_key(KeyCode::Char('5')), &mut acc);
+        km().map_key_event(simple_key(KeyCode::Char('5')), &mut acc);
         assert!(acc.is_active());
-        map_key_event(simple_key(KeyCode::Char('/')), &mut acc);
+        km().map_key_event(simple_key(KeyCode::Char('/')), &mut acc);
         assert!(!acc.is_active());
     }
 
     #[test]
     fn test_n_search_next() {
         let mut acc = InputAccumulator::new();
-        let a = map_key_event(simple_key(KeyCode::Char('n')), &mut acc);
+        let a = km().map_key_event(simple_key(KeyCode::Char('n')), &mut acc);
         assert!(matches!(a, Some(Action::SearchNextMatch)));
     }
 
     #[test]
     fn test_big_n_search_prev() {
         let mut acc = InputAccumulator::new();
-        let a = map_key_event(key(KeyCode::Char('N'), KeyModifiers::SHIFT), &mut acc);
+        let a = km().map_key_event(key(KeyCode::Char('N'), KeyModifiers::SHIFT), &mut acc);
         assert!(matches!(a, Some(Action::SearchPrevMatch)));
     }
 
@@ -632,101 +1120,107 @@ mod tests {
 
     #[test]
     fn test_search_type_char() {
-        let a = map_grep_key(simple_key(KeyCode::Char('a')));
+        let a = km().map_grep_key(simple_key(KeyCode::Char('a')));
         assert!(matches!(a, Some(GrepAction::Type('a'))));
     }
 
     #[test]
     fn test_search_backspace() {
-        let a = map_grep_key(simple_key(KeyCode::Backspace));
+        let a = km().map_grep_key(simple_key(KeyCode::Backspace));
         assert!(matches!(a, Some(GrepAction::Backspace)));
     }
 
     #[test]
     fn test_search_select_next_down() {
-        let a = map_grep_key(simple_key(KeyCode::Down));
+        let a = km().map_grep_key(simple_key(KeyCode::Down));
         assert!(matches!(a, Some(GrepAction::SelectNext)));
     }
 
     #[test]
     fn test_search_select_prev_up() {
-        let a = map_grep_key(simple_key(KeyCode::Up));
+        let a = km().map_grep_key(simple_key(KeyCode::Up));
         assert!(matches!(a, Some(GrepAction::SelectPrev)));
     }
 
     #[test]
     fn test_search_confirm() {
-        let a = map_grep_key(simple_key(KeyCode::Enter));
+        let a = km().map_grep_key(simple_key(KeyCode::Enter));
         assert!(matches!(a, Some(GrepAction::Confirm)));
     }
 
     #[test]
     fn test_search_cancel_esc() {
-        let a = map_grep_key(simple_key(KeyCode::Esc));
+        let a = km().map_grep_key(simple_key(KeyCode::Esc));
         assert!(matches!(a, Some(GrepAction::Cancel)));
     }
 
     #[test]
     fn test_search_cancel_ctrl_c() {
-        let a = map_grep_key(key(KeyCode::Char('c'), KeyModifiers::CONTROL));
+        let a = km().map_grep_key(key(KeyCode::Char('c'), KeyModifiers::CONTROL));
         assert!(matches!(a, Some(GrepAction::Cancel)));
     }
 
     #[test]
     fn test_search_unknown_returns_none() {
-        let a = map_grep_key(simple_key(KeyCode::Tab));
+        let a = km().map_grep_key(simple_key(KeyCode::Tab));
         assert!(a.is_none());
     }
 
     #[test]
     fn test_search_alt_digit_selects_index() {
         let e = key(KeyCode::Char('1'), KeyModifiers::ALT);
-        assert!(matches!(map_grep_key(e), Some(GrepAction::SelectIndex(0))));
+        assert!(matches!(
+            km().map_grep_key(e),
+            Some(GrepAction::SelectIndex(0))
+        ));
         let e = key(KeyCode::Char('9'), KeyModifiers::ALT);
-        assert!(matches!(map_grep_key(e), Some(GrepAction::SelectIndex(8))));
+        assert!(matches!(
+            km().map_grep_key(e),
+            Some(GrepAction::SelectIndex(8))
+        ));
     }
 
     #[test]
     fn test_search_alt_digit_not_plain_digit() {
         let e = key(KeyCode::Char('1'), KeyModifiers::NONE);
-        assert!(matches!(map_grep_key(e), Some(GrepAction::Type('1'))));
+        assert!(matches!(km().map_grep_key(e), Some(GrepAction::Type('1'))));
     }
 
     // --- InlineSearch mode: map_inline_search_key ---
 
     #[test]
     fn inline_search_type_char() {
-        let a = map_inline_search_key(simple_key(KeyCode::Char('a')));
+        let a = km().map_inline_search_key(simple_key(KeyCode::Char('a')));
         assert!(matches!(a, Some(InlineSearchAction::Type('a'))));
     }
 
     #[test]
     fn inline_search_backspace() {
-        let a = map_inline_search_key(simple_key(KeyCode::Backspace));
+        let a = km().map_inline_search_key(simple_key(KeyCode::Backspace));
         assert!(matches!(a, Some(InlineSearchAction::Backspace)));
     }
 
     #[test]
     fn inline_search_confirm() {
-        let a = map_inline_search_key(simple_key(KeyCode::Enter));
+        let a = km().map_inline_search_key(simple_key(KeyCode::Enter));
         assert!(matches!(a, Some(InlineSearchAction::Confirm)));
     }
 
     #[test]
     fn inline_search_cancel_esc() {
-        let a = map_inline_search_key(simple_key(KeyCode::Esc));
+        let a = km().map_inline_search_key(simple_key(KeyCode::Esc));
         assert!(matches!(a, Some(InlineSearchAction::Cancel)));
     }
 
     #[test]
     fn inline_search_cancel_ctrl_c() {
-        let a = map_inline_search_key(key(KeyCode::Char('c'), KeyModifiers::CONTROL));
+        let a = km().map_inline_search_key(key(KeyCode::Char('c'), KeyModifiers::CONTROL));
         assert!(matches!(a, Some(InlineSearchAction::Cancel)));
     }
 
     #[test]
     fn inline_search_unknown_returns_none() {
-        let a = map_inline_search_key(simple_key(KeyCode::Tab));
+        let a = km().map_inline_search_key(simple_key(KeyCode::Tab));
         assert!(a.is_none());
     }
 
@@ -735,58 +1229,58 @@ mod tests {
     #[test]
     fn test_t_enters_toc() {
         let mut acc = InputAccumulator::new();
-        let a = map_key_event(simple_key(KeyCode::Char('t')), &mut acc);
+        let a = km().map_key_event(simple_key(KeyCode::Char('t')), &mut acc);
         assert!(matches!(a, Some(Action::EnterToc)));
     }
 
     #[test]
     fn test_t_resets_accumulator() {
         let mut acc = InputAccumulator::new();
-        map_key_event(simple_key(KeyCode::Char('5')), &mut acc);
+        km().map_key_event(simple_key(KeyCode::Char('5')), &mut acc);
         assert!(acc.is_active());
-        map_key_event(simple_key(KeyCode::Char('t')), &mut acc);
+        km().map_key_event(simple_key(KeyCode::Char('t')), &mut acc);
         assert!(!acc.is_active());
     }
 
     #[test]
     fn test_toc_select_next_j() {
-        let a = map_toc_key(simple_key(KeyCode::Char('j')));
+        let a = km().map_toc_key(simple_key(KeyCode::Char('j')));
         assert!(matches!(a, Some(TocAction::SelectNext)));
     }
 
     #[test]
     fn test_toc_select_prev_k() {
-        let a = map_toc_key(simple_key(KeyCode::Char('k')));
+        let a = km().map_toc_key(simple_key(KeyCode::Char('k')));
         assert!(matches!(a, Some(TocAction::SelectPrev)));
     }
 
     #[test]
     fn test_toc_confirm() {
-        let a = map_toc_key(simple_key(KeyCode::Enter));
+        let a = km().map_toc_key(simple_key(KeyCode::Enter));
         assert!(matches!(a, Some(TocAction::Confirm)));
     }
 
     #[test]
     fn test_toc_cancel_esc() {
-        let a = map_toc_key(simple_key(KeyCode::Esc));
+        let a = km().map_toc_key(simple_key(KeyCode::Esc));
         assert!(matches!(a, Some(TocAction::Cancel)));
     }
 
     #[test]
     fn test_toc_cancel_ctrl_c() {
-        let a = map_toc_key(key(KeyCode::Char('c'), KeyModifiers::CONTROL));
+        let a = km().map_toc_key(key(KeyCode::Char('c'), KeyModifiers::CONTROL));
         assert!(matches!(a, Some(TocAction::Cancel)));
     }
 
     #[test]
     fn test_toc_quit() {
-        let a = map_toc_key(simple_key(KeyCode::Char('q')));
+        let a = km().map_toc_key(simple_key(KeyCode::Char('q')));
         assert!(matches!(a, Some(TocAction::Quit)));
     }
 
     #[test]
     fn test_toc_unknown_returns_none() {
-        let a = map_toc_key(simple_key(KeyCode::Tab));
+        let a = km().map_toc_key(simple_key(KeyCode::Tab));
         assert!(a.is_none());
     }
 
@@ -795,30 +1289,30 @@ mod tests {
     #[test]
     fn test_plus_zoom_in() {
         let mut acc = InputAccumulator::new();
-        let a = map_key_event(simple_key(KeyCode::Char('+')), &mut acc);
+        let a = km().map_key_event(simple_key(KeyCode::Char('+')), &mut acc);
         assert!(matches!(a, Some(Action::ZoomIn)));
     }
 
     #[test]
     fn test_minus_zoom_out() {
         let mut acc = InputAccumulator::new();
-        let a = map_key_event(simple_key(KeyCode::Char('-')), &mut acc);
+        let a = km().map_key_event(simple_key(KeyCode::Char('-')), &mut acc);
         assert!(matches!(a, Some(Action::ZoomOut)));
     }
 
     #[test]
     fn test_equals_zoom_reset() {
         let mut acc = InputAccumulator::new();
-        let a = map_key_event(simple_key(KeyCode::Char('=')), &mut acc);
+        let a = km().map_key_event(simple_key(KeyCode::Char('=')), &mut acc);
         assert!(matches!(a, Some(Action::ZoomReset)));
     }
 
     #[test]
     fn test_zoom_resets_accumulator() {
         let mut acc = InputAccumulator::new();
-        map_key_event(simple_key(KeyCode::Char('5')), &mut acc);
+        km().map_key_event(simple_key(KeyCode::Char('5')), &mut acc);
         assert!(acc.is_active());
-        map_key_event(simple_key(KeyCode::Char('+')), &mut acc);
+        km().map_key_event(simple_key(KeyCode::Char('+')), &mut acc);
         assert!(!acc.is_active());
     }
 
@@ -827,7 +1321,7 @@ mod tests {
     #[test]
     fn test_colon_enters_command() {
         let mut acc = InputAccumulator::new();
-        let a = map_key_event(simple_key(KeyCode::Char(':')), &mut acc);
+        let a = km().map_key_event(simple_key(KeyCode::Char(':')), &mut acc);
         assert!(matches!(a, Some(Action::EnterCommand)));
     }
 
@@ -868,11 +1362,11 @@ mod tests {
     #[test]
     fn log_scroll_down() {
         assert_eq!(
-            map_log_key(simple_key(KeyCode::Char('j')), false),
+            km().map_log_key(simple_key(KeyCode::Char('j')), false),
             Some(LogAction::ScrollDown)
         );
         assert_eq!(
-            map_log_key(simple_key(KeyCode::Down), false),
+            km().map_log_key(simple_key(KeyCode::Down), false),
             Some(LogAction::ScrollDown)
         );
     }
@@ -880,11 +1374,11 @@ mod tests {
     #[test]
     fn log_scroll_up() {
         assert_eq!(
-            map_log_key(simple_key(KeyCode::Char('k')), false),
+            km().map_log_key(simple_key(KeyCode::Char('k')), false),
             Some(LogAction::ScrollUp)
         );
         assert_eq!(
-            map_log_key(simple_key(KeyCode::Up), false),
+            km().map_log_key(simple_key(KeyCode::Up), false),
             Some(LogAction::ScrollUp)
         );
     }
@@ -892,11 +1386,11 @@ mod tests {
     #[test]
     fn log_top_bottom() {
         assert_eq!(
-            map_log_key(simple_key(KeyCode::Char('g')), false),
+            km().map_log_key(simple_key(KeyCode::Char('g')), false),
             Some(LogAction::JumpToTop)
         );
         assert_eq!(
-            map_log_key(key(KeyCode::Char('G'), KeyModifiers::SHIFT), false),
+            km().map_log_key(key(KeyCode::Char('G'), KeyModifiers::SHIFT), false),
             Some(LogAction::JumpToBottom)
         );
     }
@@ -904,7 +1398,7 @@ mod tests {
     #[test]
     fn log_search() {
         assert_eq!(
-            map_log_key(simple_key(KeyCode::Char('/')), false),
+            km().map_log_key(simple_key(KeyCode::Char('/')), false),
             Some(LogAction::EnterSearch)
         );
     }
@@ -912,11 +1406,11 @@ mod tests {
     #[test]
     fn log_search_nav() {
         assert_eq!(
-            map_log_key(simple_key(KeyCode::Char('n')), false),
+            km().map_log_key(simple_key(KeyCode::Char('n')), false),
             Some(LogAction::SearchNext)
         );
         assert_eq!(
-            map_log_key(key(KeyCode::Char('N'), KeyModifiers::SHIFT), false),
+            km().map_log_key(key(KeyCode::Char('N'), KeyModifiers::SHIFT), false),
             Some(LogAction::SearchPrev)
         );
     }
@@ -924,7 +1418,7 @@ mod tests {
     #[test]
     fn log_yank() {
         assert_eq!(
-            map_log_key(simple_key(KeyCode::Char('y')), false),
+            km().map_log_key(simple_key(KeyCode::Char('y')), false),
             Some(LogAction::Yank)
         );
     }
@@ -932,11 +1426,11 @@ mod tests {
     #[test]
     fn log_cancel() {
         assert_eq!(
-            map_log_key(simple_key(KeyCode::Char('q')), false),
+            km().map_log_key(simple_key(KeyCode::Char('q')), false),
             Some(LogAction::Cancel)
         );
         assert_eq!(
-            map_log_key(simple_key(KeyCode::Esc), false),
+            km().map_log_key(simple_key(KeyCode::Esc), false),
             Some(LogAction::Cancel)
         );
     }
@@ -944,11 +1438,11 @@ mod tests {
     #[test]
     fn log_type_fallback() {
         assert_eq!(
-            map_log_key(simple_key(KeyCode::Char('a')), false),
+            km().map_log_key(simple_key(KeyCode::Char('a')), false),
             Some(LogAction::Type('a'))
         );
         assert_eq!(
-            map_log_key(simple_key(KeyCode::Backspace), false),
+            km().map_log_key(simple_key(KeyCode::Backspace), false),
             Some(LogAction::Backspace)
         );
     }
@@ -959,17 +1453,17 @@ mod tests {
     fn log_search_mode_chars_become_type() {
         for c in ['j', 'k', 'g', 'n', 'y', 'q', '/'] {
             assert_eq!(
-                map_log_key(simple_key(KeyCode::Char(c)), true),
+                km().map_log_key(simple_key(KeyCode::Char(c)), true),
                 Some(LogAction::Type(c)),
                 "'{c}' should be Type in search mode"
             );
         }
         assert_eq!(
-            map_log_key(key(KeyCode::Char('G'), KeyModifiers::SHIFT), true),
+            km().map_log_key(key(KeyCode::Char('G'), KeyModifiers::SHIFT), true),
             Some(LogAction::Type('G'))
         );
         assert_eq!(
-            map_log_key(key(KeyCode::Char('N'), KeyModifiers::SHIFT), true),
+            km().map_log_key(key(KeyCode::Char('N'), KeyModifiers::SHIFT), true),
             Some(LogAction::Type('N'))
         );
     }
@@ -977,11 +1471,11 @@ mod tests {
     #[test]
     fn log_search_mode_cancel() {
         assert_eq!(
-            map_log_key(simple_key(KeyCode::Esc), true),
+            km().map_log_key(simple_key(KeyCode::Esc), true),
             Some(LogAction::Cancel)
         );
         assert_eq!(
-            map_log_key(key(KeyCode::Char('c'), KeyModifiers::CONTROL), true),
+            km().map_log_key(key(KeyCode::Char('c'), KeyModifiers::CONTROL), true),
             Some(LogAction::Cancel)
         );
     }
@@ -989,7 +1483,7 @@ mod tests {
     #[test]
     fn log_search_mode_confirm() {
         assert_eq!(
-            map_log_key(simple_key(KeyCode::Enter), true),
+            km().map_log_key(simple_key(KeyCode::Enter), true),
             Some(LogAction::ConfirmSearch)
         );
     }
@@ -997,11 +1491,11 @@ mod tests {
     #[test]
     fn log_search_mode_arrows_scroll() {
         assert_eq!(
-            map_log_key(simple_key(KeyCode::Down), true),
+            km().map_log_key(simple_key(KeyCode::Down), true),
             Some(LogAction::ScrollDown)
         );
         assert_eq!(
-            map_log_key(simple_key(KeyCode::Up), true),
+            km().map_log_key(simple_key(KeyCode::Up), true),
             Some(LogAction::ScrollUp)
         );
     }
@@ -1009,7 +1503,7 @@ mod tests {
     #[test]
     fn log_search_mode_backspace() {
         assert_eq!(
-            map_log_key(simple_key(KeyCode::Backspace), true),
+            km().map_log_key(simple_key(KeyCode::Backspace), true),
             Some(LogAction::Backspace)
         );
     }
@@ -1057,4 +1551,186 @@ mod tests {
         ));
         assert!(a.is_none());
     }
+
+    // --- User key bindings ---
+
+    fn keymap_with(mode: &str, bindings: &[(&str, &str)]) -> anyhow::Result<Keymap> {
+        let map: BTreeMap<String, String> = bindings
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect();
+        let mut keys = KeysConfig::default();
+        match mode {
+            "normal" => keys.normal = map,
+            "toc" => keys.toc = map,
+            "grep" => keys.grep = map,
+            "log" => keys.log = map,
+            _ => unreachable!(),
+        }
+        Keymap::from_config(&keys)
+    }
+
+    #[test]
+    fn chord_parse() {
+        let p = |s| KeyChord::parse(s).unwrap();
+        assert_eq!(
+            p("j"),
+            KeyChord::new(KeyCode::Char('j'), KeyModifiers::NONE)
+        );
+        assert_eq!(
+            p("shift-g"),
+            KeyChord::new(KeyCode::Char('G'), KeyModifiers::NONE)
+        );
+        assert_eq!(
+            p("ctrl-o"),
+            KeyChord::new(KeyCode::Char('o'), KeyModifiers::CONTROL)
+        );
+        assert_eq!(
+            p("Ctrl--"),
+            KeyChord::new(KeyCode::Char('-'), KeyModifiers::CONTROL)
+        );
+        assert_eq!(
+            p("-"),
+            KeyChord::new(KeyCode::Char('-'), KeyModifiers::NONE)
+        );
+        assert_eq!(
+            p("space"),
+            KeyChord::new(KeyCode::Char(' '), KeyModifiers::NONE)
+        );
+        assert_eq!(
+            p("shift-down"),
+            KeyChord::new(KeyCode::Down, KeyModifiers::SHIFT)
+        );
+        assert_eq!(p("f5"), KeyChord::new(KeyCode::F(5), KeyModifiers::NONE));
+        assert!(KeyChord::parse("hyper-x").is_err());
+        assert!(KeyChord::parse("escape").is_err());
+    }
+
+    #[test]
+    fn defaults_parse() {
+        // Built-in tables go through the same parser; a typo would panic here.
+        let _ = Keymap::default();
+    }
+
+    #[test]
+    fn ctrl_d_ctrl_u_half_page() {
+        let mut acc = InputAccumulator::new();
+        let a = km().map_key_event(key(KeyCode::Char('d'), KeyModifiers::CONTROL), &mut acc);
+        assert!(matches!(a, Some(Action::HalfPageDown(1))));
+        let a = km().map_key_event(key(KeyCode::Char('u'), KeyModifiers::CONTROL), &mut acc);
+        assert!(matches!(a, Some(Action::HalfPageUp(1))));
+    }
+
+    #[test]
+    fn user_binding_remaps_and_keeps_count() {
+        let km = keymap_with(
+            "normal",
+            &[("J", "scroll-down"), ("ctrl-f", "half-page-down")],
+        )
+        .unwrap();
+        let mut acc = InputAccumulator::new();
+        km.map_key_event(simple_key(KeyCode::Char('4')), &mut acc);
+        let a = km.map_key_event(key(KeyCode::Char('J'), KeyModifiers::SHIFT), &mut acc);
+        assert!(matches!(a, Some(Action::ScrollDown(4))));
+        let a = km.map_key_event(key(KeyCode::Char('f'), KeyModifiers::CONTROL), &mut acc);
+        assert!(matches!(a, Some(Action::HalfPageDown(1))));
+        // Defaults remain.
+        let a = km.map_key_event(simple_key(KeyCode::Char('j')), &mut acc);
+        assert!(matches!(a, Some(Action::ScrollDown(1))));
+    }
+
+    #[test]
+    fn user_binding_none_unbinds() {
+        let km = keymap_with("normal", &[("q", "none")]).unwrap();
+        let mut acc = InputAccumulator::new();
+        assert!(
+            km.map_key_event(simple_key(KeyCode::Char('q')), &mut acc)
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn multi_key_sequence_with_count() {
+        let km = keymap_with("normal", &[("g", "none"), ("g g", "goto-top")]).unwrap();
+        let mut acc = InputAccumulator::new();
+        km.map_key_event(simple_key(KeyCode::Char('1')), &mut acc);
+        km.map_key_event(simple_key(KeyCode::Char('2')), &mut acc);
+        let a = km.map_key_event(simple_key(KeyCode::Char('g')), &mut acc);
+        assert!(matches!(a, Some(Action::Pending)));
+        assert_eq!(acc.peek(), Some(12));
+        let a = km.map_key_event(simple_key(KeyCode::Char('g')), &mut acc);
+        assert!(matches!(a, Some(Action::JumpToLine(12))));
+        assert!(!acc.is_active());
+    }
+
+    #[test]
+    fn broken_sequence_cancels() {
+        let km = keymap_with("normal", &[("g", "none"), ("g g", "goto-top")]).unwrap();
+        let mut acc = InputAccumulator::new();
+        km.map_key_event(simple_key(KeyCode::Char('g')), &mut acc);
+        let a = km.map_key_event(simple_key(KeyCode::Char('x')), &mut acc);
+        assert!(matches!(a, Some(Action::CancelInput)));
+        assert!(!acc.is_active());
+    }
+
+    #[test]
+    fn prefix_conflict_is_reported() {
+        let err = keymap_with("normal", &[("g g", "goto-top")]).err().unwrap();
+        let msg = err.to_string();
+        assert!(
+            msg.contains("\"g g\" (goto-top) is shadowed by \"g\""),
+            "{msg}"
+        );
+    }
+
+    #[test]
+    fn unknown_action_and_key_are_reported_together() {
+        let err = keymap_with("toc", &[("l", "open"), ("hyper-x", "confirm")])
+            .err()
+            .unwrap();
+        let msg = err.to_string();
+        assert!(
+            msg.contains("[keys.toc] \"l\": unknown action `open`"),
+            "{msg}"
+        );
+        assert!(msg.contains("[keys.toc] \"hyper-x\": unknown key"), "{msg}");
+    }
+
+    #[test]
+    fn duplicate_chord_spellings_conflict() {
+        let err = keymap_with("normal", &[("G", "goto-top"), ("shift-g", "goto-bottom")])
+            .err()
+            .unwrap();
+        assert!(err.to_string().contains("same key"), "{err}");
+    }
+
+    #[test]
+    fn text_entry_modes_reject_printable_keys() {
+        assert!(keymap_with("grep", &[("j", "select-next")]).is_err());
+        let km = keymap_with("grep", &[("ctrl-n", "select-next")]).unwrap();
+        let a = km.map_grep_key(key(KeyCode::Char('n'), KeyModifiers::CONTROL));
+        assert!(matches!(a, Some(GrepAction::SelectNext)));
+        // Typing still works.
+        let a = km.map_grep_key(simple_key(KeyCode::Char('n')));
+        assert!(matches!(a, Some(GrepAction::Type('n'))));
+    }
+
+    #[test]
+    fn digits_reserved_in_normal_mode() {
+        assert!(keymap_with("normal", &[("0", "goto-top")]).is_err());
+    }
+
+    #[test]
+    fn log_remap() {
+        let km = keymap_with("log", &[("e", "scroll-down")]).unwrap();
+        assert_eq!(
+            km.map_log_key(simple_key(KeyCode::Char('e')), false),
+            Some(LogAction::ScrollDown)
+        );
+        // Search-mode typing is not affected.
+        assert_eq!(
+            km.map_log_key(simple_key(KeyCode::Char('e')), true),
+            Some(LogAction::Type('e'))
+        );
+    }
 }
@@ -149,6 +149,11 @@ impl Layout {
         let aligned_px = raw_px.div_ceil(cell_h) * cell_h;
         aligned_px as f64 * 72.0 / ppi
     }
+
+    /// Height (pt) of `rows` cell rows, for capping aligned tile heights.
+    pub(super) fn rows_height_pt(&self, rows: u16, ppi: f64) -> f64 {
+        rows as f64 * self.cell_h as f64 * 72.0 / ppi
+    }
 }
 
 pub(super) fn compute_layout(
@@ -175,6 +180,12 @@ pub(super) fn compute_layout(
     }
 }
 
+/// Layout for text mode: one "pixel" per cell, so scroll offsets and visual
+/// line positions count terminal rows.
+pub(super) fn compute_text_layout(term_cols: u16, term_rows: u16, sidebar_cols: u16) -> Layout {
+    compute_layout(term_cols, term_rows, term_cols, term_rows, sidebar_cols)
+}
+
 pub(super) fn vp_dims(layout: &Layout, img_w: u32, img_h: u32) -> (u32, u32) {
     let vp_w = (layout.image_cols as u32 * layout.cell_w as u32).min(img_w);
     let vp_h = (layout.image_rows as u32 * layout.cell_h as u32).min(img_h);
@@ -379,4 +390,15 @@ mod tests {
         // 277pt → 277 * 144 / 72 = 554px → div_ceil(554, 24) = 24 → 24*24 = 576px → 288pt
         assert_eq!(l.align_tile_height_pt(277.0, ppi), 288.0);
     }
+
+    #[test]
+    fn rows_height_caps_aligned_tile_height() {
+        let l = compute_layout(80, 24, 1280, 576, 6);
+        let ppi = 144.0;
+        // cell_h = 24px: 297 rows = 7128px = 3564pt
+        let cap = l.rows_height_pt(297, ppi);
+        assert_eq!(cap, 3564.0);
+        assert_eq!(l.align_tile_height_pt(cap, ppi), cap);
+        assert_eq!(l.align_tile_height_pt(5000.0, ppi).min(cap), cap);
+    }
 }
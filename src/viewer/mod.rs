@@ -1,4 +1,5 @@
-//! Terminal Markdown viewer with Kitty Graphics Protocol
+//! Terminal Markdown viewer with Kitty Graphics Protocol (or Sixel, see
+//! [`graphics`])
 //!
 //! Layout:
 //!   col 0..sidebar_cols : sidebar image (pixel-precise line numbers)
@@ -17,8 +18,12 @@
 //!   causing phantom scrolling. `q=2` suppresses both OK and error responses.
 //!   Since the viewer never reads Kitty responses, this is always safe.
 
+mod cat;
+mod composite;
 mod display_state;
 mod effect;
+mod error_screen;
+mod graphics;
 mod input_history;
 mod keymap;
 mod layout;
@@ -27,14 +32,19 @@ mod mode_grep;
 mod mode_inline_search;
 mod mode_log;
 mod mode_normal;
-mod mode_toc;
+pub(crate) mod mode_toc;
 mod mode_url;
+mod placeholder;
 pub mod query;
 mod scroll;
 mod scroll_animator;
 mod scroll_policy;
 mod session;
+mod sixel;
 mod terminal;
+mod text_layout;
+mod text_view;
+mod transfer;
 mod viewport;
 
 #[cfg(test)]
@@ -42,10 +52,11 @@ mod test_harness;
 #[cfg(test)]
 mod test_highlight;
 
+pub use cat::cat;
 pub use terminal::{TerminalTheme, detect_terminal_theme};
 
 use crossterm::{
-    event::{self, Event, KeyCode, KeyModifiers},
+    event::{self, Event, KeyCode, KeyEvent, KeyModifiers, MouseEvent},
     terminal as crossterm_terminal,
 };
 use log::{debug, info, warn};
@@ -53,17 +64,17 @@ use std::collections::HashSet;
 use std::time::{Duration, Instant};
 
 use crate::app_context::AppContext;
+use crate::config::GraphicsMode;
 use crate::frame::TileCache;
 use crate::input_source::InputSource;
+use crate::pipeline::CompileError;
 use crate::watch::FileWatcher;
 
 use display_state::{DisplayState, ForkHandle};
 use effect::{Effect, ExitReason, ViewerMode};
+use error_screen::AfterError;
 use input_history::ScrollDirection;
-use keymap::{
-    Action, InputAccumulator, map_command_key, map_grep_key, map_inline_search_key, map_key_event,
-    map_log_key, map_mouse_event, map_toc_key, map_url_key,
-};
+use keymap::{Action, InputAccumulator, Keymap, map_command_key, map_mouse_event};
 use layout::ScrollState;
 use query::DocumentQuery;
 use scroll::ScrollStrategy;
@@ -93,16 +104,28 @@ pub fn run(
         .map_err(|e| anyhow::anyhow!("failed to get terminal size: {e}"))?;
     let (term_cols, term_rows) = (winsize.columns, winsize.rows);
     let (pixel_w, pixel_h) = (winsize.width, winsize.height);
+    let has_pixel_size = pixel_w != 0 && pixel_h != 0;
+    let mode = app.config.viewer.graphics;
 
-    if pixel_w == 0 || pixel_h == 0 {
+    if !has_pixel_size && !matches!(mode, GraphicsMode::Auto | GraphicsMode::Text) {
         anyhow::bail!(
-            "terminal pixel size {}x{} is zero — Kitty graphics requires non-zero pixel dimensions",
+            "terminal pixel size {}x{} is zero — terminal graphics require non-zero pixel dimensions \
+             (use --graphics text)",
             pixel_w,
             pixel_h
         );
     }
 
+    // Validate user key bindings before touching the terminal so errors
+    // print cleanly.
+    let keymap = Keymap::from_config(&app.config.viewer.keys)?;
+
+    terminal::set_tmux_passthrough(graphics::in_tmux());
     let mut guard = terminal::RawGuard::enter(app.config.viewer.mouse)?;
+    let graphics = graphics::Graphics::resolve(mode, has_pixel_size);
+    if graphics.keeps_images() {
+        transfer::set(transfer::Transfer::resolve(app.config.viewer.transfer));
+    }
 
     // Session: persistent state across document rebuilds
     let watcher_init = if watch {
@@ -113,30 +136,37 @@ pub fn run(
     } else {
         None
     };
+    let sidebar_cols = app.config.viewer.sidebar_cols;
     let mut session = Session {
-        layout: layout::compute_layout(
-            term_cols,
-            term_rows,
-            pixel_w,
-            pixel_h,
-            app.config.viewer.sidebar_cols,
-        ),
+        layout: match graphics {
+            graphics::Graphics::Text => {
+                layout::compute_text_layout(term_cols, term_rows, sidebar_cols)
+            }
+            _ => layout::compute_layout(term_cols, term_rows, pixel_w, pixel_h, sidebar_cols),
+        },
         filename: input.display_name().to_string(),
         input,
         watcher: watcher_init,
         jump_stack: Vec::new(),
         scroll_carry: 0,
         pending_flash: None,
+        pending_anchor: None,
         watch,
+        graphics,
         log_buffer,
     };
 
-    // Stdin buffer and EOF flag (stdin mode only)
-    let mut stdin_buf = String::new();
-    let mut stdin_eof = false;
+    let mut source = MarkdownSource {
+        cached: Some(initial_markdown),
+        stdin_buf: String::new(),
+        stdin_eof: false,
+    };
 
-    // Initial markdown from main.rs prescan (used on first iteration only)
-    let mut cached_markdown: Option<String> = Some(initial_markdown);
+    if graphics == graphics::Graphics::Text {
+        text_view::run(&app, &mut session, &keymap, &mut source)?;
+        guard.cleanup();
+        return Ok(());
+    }
 
     // Content-addressed tile cache for merge across rebuilds
     let mut tile_cache = TileCache::new();
@@ -149,32 +179,7 @@ pub fn run(
 
     // Outer loop: each iteration builds a new TiledDocument (initial + resize + reload)
     'outer: loop {
-        // 5a. Read markdown (re-read on each iteration for reload support)
-        // First iteration uses cached_markdown from prescan; subsequent iterations re-read.
-        let markdown = if let Some(md) = cached_markdown.take() {
-            // First iteration: also seed stdin_buf if stdin mode
-            if let InputSource::Stdin(ref reader) = session.input {
-                stdin_buf = md.clone();
-                stdin_eof |= reader.drain_into(&mut stdin_buf).eof;
-                stdin_buf.clone()
-            } else {
-                md
-            }
-        } else {
-            if let InputSource::Stdin(ref reader) = session.input {
-                stdin_eof |= reader.drain_into(&mut stdin_buf).eof;
-            }
-            match &session.input {
-                InputSource::File(path) => read_with_retry(path)?,
-                InputSource::Stdin(_) => {
-                    if stdin_buf.trim().is_empty() {
-                        "*(waiting for input...)*".into()
-                    } else {
-                        stdin_buf.clone()
-                    }
-                }
-            }
-        };
+        let markdown = source.load(&mut session)?;
         let base_dir = match &session.input {
             InputSource::File(path) => path.parent(),
             InputSource::Stdin(_) => None,
@@ -230,9 +235,33 @@ pub fn run(
             let mut loading_shown = false;
             loop {
                 if renderer.has_pending_data() {
-                    let meta = renderer.wait_for_meta()?;
-                    info!("fork build complete: {} tiles", meta.tile_count);
-                    break (meta, renderer);
+                    let e = match renderer.wait_for_meta() {
+                        Ok(meta) => {
+                            info!("fork build complete: {} tiles", meta.tile_count);
+                            break (meta, renderer);
+                        }
+                        Err(e) => e,
+                    };
+                    let Some(error) = e.downcast_ref::<CompileError>() else {
+                        return Err(e);
+                    };
+                    warn!("build failed: {error}");
+                    // The error screen clears every image.
+                    stale_image_ids.clear();
+                    active_gen = 0;
+                    match error_screen::show(
+                        error,
+                        &markdown,
+                        &mut session,
+                        &mut source,
+                        app.config.viewer.watch_interval,
+                        sidebar_cols,
+                    )? {
+                        AfterError::Rebuild => continue 'outer,
+                        AfterError::Quit if source.is_live(&session) => break 'outer,
+                        // Nothing will change: leave the error on the console.
+                        AfterError::Quit => return Err(e),
+                    }
                 }
                 if !loading_shown && Instant::now() >= fast_deadline {
                     // Don't clear screen if old-gen images are still displayed
@@ -242,20 +271,9 @@ pub fn run(
                 }
                 if event::poll(Duration::from_millis(16))? {
                     match event::read()? {
-                        Event::Key(k)
-                            if k.code == KeyCode::Char('q')
-                                || k.code == KeyCode::Esc
-                                || (k.code == KeyCode::Char('c')
-                                    && k.modifiers.contains(KeyModifiers::CONTROL)) =>
-                        {
-                            break 'outer;
-                        }
+                        Event::Key(k) if is_quit_key(&k) => break 'outer,
                         Event::Resize(new_cols, new_rows) => {
-                            session.update_layout_for_resize(
-                                new_cols,
-                                new_rows,
-                                app.config.viewer.sidebar_cols,
-                            )?;
+                            session.update_layout_for_resize(new_cols, new_rows, sidebar_cols)?;
                             stale_image_ids.clear(); // resize deletes all images
                             active_gen = 0;
                             continue 'outer;
@@ -282,6 +300,26 @@ pub fn run(
         let img_h = meta.total_height_px;
         let (vp_w, vp_h) = layout::vp_dims(&session.layout, img_w, img_h);
 
+        // Land on the heading named by a cross-file `#fragment` link.
+        if let Some(anchor) = session.pending_anchor.take() {
+            let doc = DocumentQuery::new(
+                &markdown,
+                &meta.visual_lines,
+                &meta.content_index,
+                meta.content_offset,
+            );
+            match doc.find_anchor(&anchor) {
+                Some(vl_idx) => {
+                    session.scroll_carry = layout::visual_line_offset(
+                        &meta.visual_lines,
+                        meta.max_scroll(vp_h),
+                        (vl_idx + 1) as u32,
+                    );
+                }
+                None => session.pending_flash = Some(format!("No heading for #{anchor}")),
+            }
+        }
+
         // 6. Inner event loop
         let mut vp = Viewport {
             mode: ViewerMode::Normal,
@@ -295,6 +333,7 @@ pub fn run(
             display: DisplayState::new_with_start_id(
                 app.config.viewer.evict_distance,
                 GEN_BASES[active_gen],
+                session.graphics,
             ),
             flash: session.pending_flash.take(),
             dirty: false,
@@ -362,14 +401,12 @@ pub fn run(
                     vp.dirty = true;
                 }
 
-                let has_live_source = session.watcher.is_some()
-                    || (matches!(&session.input, InputSource::Stdin(_)) && !stdin_eof);
                 let timeout = if vp.dirty || vp.scroll.is_animating() {
                     app.config
                         .viewer
                         .frame_budget
                         .saturating_sub(last_render.elapsed())
-                } else if has_live_source {
+                } else if source.is_live(&session) {
                     app.config.viewer.watch_interval
                 } else {
                     Duration::from_secs(86400)
@@ -388,164 +425,22 @@ pub fn run(
                                 &meta.content_index,
                                 meta.content_offset,
                             );
-
-                            // Clear flash on any keypress in Normal mode
-                            let had_flash =
-                                matches!(vp.mode, ViewerMode::Normal) && vp.flash.take().is_some();
-
-                            let mut effects = match &mut vp.mode {
-                                ViewerMode::Normal => match map_key_event(key_event, &mut acc) {
-                                    Some(a) => {
-                                        let dir = match &a {
-                                            Action::ScrollDown(_) | Action::HalfPageDown(_) => {
-                                                Some(ScrollDirection::Down)
-                                            }
-                                            Action::ScrollUp(_) | Action::HalfPageUp(_) => {
-                                                Some(ScrollDirection::Up)
-                                            }
-                                            _ => None,
-                                        };
-                                        let scroll_step = match dir {
-                                            Some(d) => scroll_strategy.step(
-                                                app.config.viewer.scroll_step,
-                                                session.layout.cell_h as u32,
-                                                d,
-                                                &vp.scroll.input_history,
-                                            ),
-                                            None => {
-                                                app.config.viewer.scroll_step
-                                                    * session.layout.cell_h as u32
-                                            }
-                                        };
-
-                                        let mut ctx = mode_normal::NormalCtx {
-                                            scroll: &vp.scroll,
-                                            doc: &doc,
-                                            max_scroll: max_y,
-                                            scroll_step,
-                                            wheel_step: app.config.viewer.wheel_step
-                                                * session.layout.cell_h as u32,
-                                            half_page: (session.layout.image_rows as u32 / 2)
-                                                .max(1)
-                                                * session.layout.cell_h as u32,
-                                            last_search: &mut vp.last_search,
-                                            current_file: session.current_file_path(),
-                                            current_scale: app.config.scale,
-                                        };
-                                        mode_normal::handle(a, &mut ctx)
-                                    }
-                                    None => vec![],
-                                },
-                                ViewerMode::Grep(gs) => match map_grep_key(key_event) {
-                                    Some(a) => {
-                                        let visible_count =
-                                            (session.layout.status_row - 1) as usize;
-                                        mode_grep::handle(a, gs, &doc, visible_count, max_y)
-                                    }
-                                    None => vec![],
-                                },
-                                ViewerMode::InlineSearch(is) => {
-                                    match map_inline_search_key(key_event) {
-                                        Some(a) => mode_inline_search::handle(a, is, &doc, max_y),
-                                        None => vec![],
-                                    }
-                                }
-                                ViewerMode::Command(cs) => match map_command_key(key_event) {
-                                    Some(a) => mode_command::handle(a, cs),
-                                    None => vec![],
-                                },
-                                ViewerMode::Toc(ts) => match map_toc_key(key_event) {
-                                    Some(a) => {
-                                        let visible_count =
-                                            (session.layout.status_row - 1) as usize;
-                                        mode_toc::handle(
-                                            a,
-                                            ts,
-                                            doc.visual_lines,
-                                            visible_count,
-                                            max_y,
-                                        )
-                                    }
-                                    None => vec![],
-                                },
-                                ViewerMode::UrlPicker(up) => match map_url_key(key_event) {
-                                    Some(a) => {
-                                        let visible_count =
-                                            (session.layout.status_row - 1) as usize;
-                                        mode_url::handle(
-                                            a,
-                                            up,
-                                            visible_count,
-                                            session.current_file_path(),
-                                        )
-                                    }
-                                    None => vec![],
-                                },
-                                ViewerMode::Log(ls) => match map_log_key(key_event, ls.search_mode)
-                                {
-                                    Some(a) => {
-                                        let visible_count =
-                                            (session.layout.status_row - 1) as usize;
-                                        let total_cols = (session.layout.sidebar_cols
-                                            + session.layout.image_cols)
-                                            as usize;
-                                        mode_log::handle(a, ls, visible_count, total_cols)
-                                    }
-                                    None => vec![],
-                                },
-                            };
-
-                            // Post: if flash was just cleared, ensure redraw
-                            if had_flash && effects.is_empty() {
-                                effects.push(Effect::RedrawStatusBar);
-                            }
-
-                            let ctx = ViewContext {
-                                layout: &session.layout,
-                                acc_value: acc.peek(),
-                                filename: &session.filename,
-                                jump_stack: &session.jump_stack,
-                                doc: &doc,
-                                log_buffer: &session.log_buffer,
-                            };
-                            for effect in effects {
-                                if matches!(effect, Effect::ToggleWatch) {
-                                    match session.current_file_path() {
-                                        Some(path) => {
-                                            let path = path.to_path_buf();
-                                            if session.watch {
-                                                session.watch = false;
-                                                session.watcher = None;
-                                                vp.flash = Some("watch: off".into());
-                                            } else {
-                                                match FileWatcher::new(&path) {
-                                                    Ok(w) => {
-                                                        session.watch = true;
-                                                        session.watcher = Some(w);
-                                                        vp.flash = Some("watch: on".into());
-                                                    }
-                                                    Err(e) => {
-                                                        vp.flash =
-                                                            Some(format!("watch: failed ({e})"));
-                                                    }
-                                                }
-                                            }
-                                        }
-                                        None => {
-                                            vp.flash =
-                                                Some("watch: not available for stdin".into());
-                                        }
-                                    }
-                                    continue;
-                                }
-                                let (new_vp, render_ops) = vp.apply(effect, &ctx);
-                                vp = new_vp;
-                                if let Some(reason) =
-                                    effect::execute_render_ops(render_ops, &mut vp, &ctx)?
-                                {
-                                    stale_image_ids = vp.display.all_image_ids();
-                                    return Ok((reason, vp.scroll.y_offset));
-                                }
+                            let effects = key_effects(
+                                key_event,
+                                &mut vp,
+                                &mut acc,
+                                &keymap,
+                                &scroll_strategy,
+                                &app,
+                                &session,
+                                &doc,
+                                max_y,
+                            );
+                            if let Some(reason) =
+                                apply_effects(effects, &mut vp, &mut session, &doc, acc.peek())?
+                            {
+                                stale_image_ids = vp.display.all_image_ids();
+                                return Ok((reason, vp.scroll.y_offset));
                             }
                         }
 
@@ -557,50 +452,12 @@ pub fn run(
                                 &meta.content_index,
                                 meta.content_offset,
                             );
-
-                            // Wheel input is Normal-mode-only; other modes ignore it.
-                            let effects = match &mut vp.mode {
-                                ViewerMode::Normal => match map_mouse_event(me) {
-                                    Some(a) => {
-                                        let mut ctx = mode_normal::NormalCtx {
-                                            scroll: &vp.scroll,
-                                            doc: &doc,
-                                            max_scroll: max_y,
-                                            scroll_step: app.config.viewer.scroll_step
-                                                * session.layout.cell_h as u32,
-                                            wheel_step: app.config.viewer.wheel_step
-                                                * session.layout.cell_h as u32,
-                                            half_page: (session.layout.image_rows as u32 / 2)
-                                                .max(1)
-                                                * session.layout.cell_h as u32,
-                                            last_search: &mut vp.last_search,
-                                            current_file: session.current_file_path(),
-                                            current_scale: app.config.scale,
-                                        };
-                                        mode_normal::handle(a, &mut ctx)
-                                    }
-                                    None => vec![],
-                                },
-                                _ => vec![],
-                            };
-
-                            let ctx = ViewContext {
-                                layout: &session.layout,
-                                acc_value: acc.peek(),
-                                filename: &session.filename,
-                                jump_stack: &session.jump_stack,
-                                doc: &doc,
-                                log_buffer: &session.log_buffer,
-                            };
-                            for effect in effects {
-                                let (new_vp, render_ops) = vp.apply(effect, &ctx);
-                                vp = new_vp;
-                                if let Some(reason) =
-                                    effect::execute_render_ops(render_ops, &mut vp, &ctx)?
-                                {
-                                    stale_image_ids = vp.display.all_image_ids();
-                                    return Ok((reason, vp.scroll.y_offset));
-                                }
+                            let effects = mouse_effects(me, &mut vp, &app, &session, &doc, max_y);
+                            if let Some(reason) =
+                                apply_effects(effects, &mut vp, &mut session, &doc, acc.peek())?
+                            {
+                                stale_image_ids = vp.display.all_image_ids();
+                                return Ok((reason, vp.scroll.y_offset));
                             }
                         }
 
@@ -640,22 +497,12 @@ pub fn run(
                         &meta.content_index,
                         meta.content_offset,
                     );
-                    let ctx = ViewContext {
-                        layout: &session.layout,
-                        acc_value: acc.peek(),
-                        filename: &session.filename,
-                        jump_stack: &session.jump_stack,
-                        doc: &doc,
-                        log_buffer: &session.log_buffer,
-                    };
-                    for effect in mode_normal::zoom_effects(app.config.scale, target) {
-                        let (new_vp, render_ops) = vp.apply(effect, &ctx);
-                        vp = new_vp;
-                        if let Some(reason) = effect::execute_render_ops(render_ops, &mut vp, &ctx)?
-                        {
-                            stale_image_ids = vp.display.all_image_ids();
-                            return Ok((reason, vp.scroll.y_offset));
-                        }
+                    let effects = mode_normal::zoom_effects(app.config.scale, target);
+                    if let Some(reason) =
+                        apply_effects(effects, &mut vp, &mut session, &doc, acc.peek())?
+                    {
+                        stale_image_ids = vp.display.all_image_ids();
+                        return Ok((reason, vp.scroll.y_offset));
                     }
                 }
 
@@ -702,18 +549,7 @@ pub fn run(
                 }
                 last_render = Instant::now();
 
-                // Check for content changes (file watcher or stdin new data)
-                let content_changed = match &session.input {
-                    InputSource::File(_) => {
-                        session.watcher.as_ref().is_some_and(|w| w.has_changed())
-                    }
-                    InputSource::Stdin(reader) => {
-                        let result = reader.drain_into(&mut stdin_buf);
-                        stdin_eof |= result.eof;
-                        result.got_data
-                    }
-                };
-                if content_changed {
+                if source.changed(&session) {
                     info!("file change detected, reloading");
                     stale_image_ids = vp.display.all_image_ids();
                     return Ok((ExitReason::Reload, vp.scroll.y_offset));
@@ -755,7 +591,7 @@ pub fn run(
             stale_image_ids.clear();
         }
 
-        if session.handle_exit(exit, scroll_y, app.config.viewer.sidebar_cols)? {
+        if session.handle_exit(exit, scroll_y, sidebar_cols)? {
             break 'outer;
         }
     }
@@ -764,6 +600,240 @@ pub fn run(
     Ok(())
 }
 
+/// `q`, Esc or Ctrl+C: leave a screen that has no document to act on
+/// (loading, compile error).
+fn is_quit_key(k: &KeyEvent) -> bool {
+    k.code == KeyCode::Char('q')
+        || k.code == KeyCode::Esc
+        || (k.code == KeyCode::Char('c') && k.modifiers.contains(KeyModifiers::CONTROL))
+}
+
+/// Markdown input: the prescanned first read, then file re-reads or the
+/// growing stdin buffer. Shared by the image and text event loops.
+struct MarkdownSource {
+    /// Initial markdown from main.rs prescan (used on first iteration only).
+    cached: Option<String>,
+    /// Stdin buffer and EOF flag (stdin mode only).
+    stdin_buf: String,
+    stdin_eof: bool,
+}
+
+impl MarkdownSource {
+    /// Read the markdown for the next build and set the status bar filename.
+    fn load(&mut self, session: &mut Session) -> anyhow::Result<String> {
+        let markdown = if let Some(md) = self.cached.take() {
+            // First iteration: also seed stdin_buf if stdin mode
+            if let InputSource::Stdin(ref reader) = session.input {
+                self.stdin_buf = md.clone();
+                self.stdin_eof |= reader.drain_into(&mut self.stdin_buf).eof;
+                self.stdin_buf.clone()
+            } else {
+                md
+            }
+        } else {
+            if let InputSource::Stdin(ref reader) = session.input {
+                self.stdin_eof |= reader.drain_into(&mut self.stdin_buf).eof;
+            }
+            match &session.input {
+                InputSource::File(path) => read_with_retry(path)?,
+                InputSource::Stdin(_) => {
+                    if self.stdin_buf.trim().is_empty() {
+                        "*(waiting for input...)*".into()
+                    } else {
+                        self.stdin_buf.clone()
+                    }
+                }
+            }
+        };
+        // Front matter fields take over the status bar filename slot.
+        let display_name = session.input.display_name();
+        session.filename = match crate::compile::front_matter(&markdown) {
+            Some(fm) => fm.status_label(display_name),
+            None => display_name.to_string(),
+        };
+        Ok(markdown)
+    }
+
+    /// Whether new content can still arrive (file watcher or open stdin).
+    fn is_live(&self, session: &Session) -> bool {
+        session.watcher.is_some()
+            || (matches!(&session.input, InputSource::Stdin(_)) && !self.stdin_eof)
+    }
+
+    /// Check for content changes (file watcher or stdin new data).
+    fn changed(&mut self, session: &Session) -> bool {
+        match &session.input {
+            InputSource::File(_) => session.watcher.as_ref().is_some_and(|w| w.has_changed()),
+            InputSource::Stdin(reader) => {
+                let result = reader.drain_into(&mut self.stdin_buf);
+                self.stdin_eof |= result.eof;
+                result.got_data
+            }
+        }
+    }
+}
+
+/// Map a key event to effects through the handler of the current mode.
+#[allow(clippy::too_many_arguments)]
+fn key_effects(
+    key_event: KeyEvent,
+    vp: &mut Viewport,
+    acc: &mut InputAccumulator,
+    keymap: &Keymap,
+    scroll_strategy: &ScrollStrategy,
+    app: &AppContext,
+    session: &Session,
+    doc: &DocumentQuery,
+    max_y: u32,
+) -> Vec<Effect> {
+    // Clear flash on any keypress in Normal mode
+    let had_flash = matches!(vp.mode, ViewerMode::Normal) && vp.flash.take().is_some();
+    let layout = &session.layout;
+    let visible_count = (layout.status_row - 1) as usize;
+
+    let mut effects = match &mut vp.mode {
+        ViewerMode::Normal => match keymap.map_key_event(key_event, acc) {
+            Some(a) => {
+                let dir = match &a {
+                    Action::ScrollDown(_) | Action::HalfPageDown(_) => Some(ScrollDirection::Down),
+                    Action::ScrollUp(_) | Action::HalfPageUp(_) => Some(ScrollDirection::Up),
+                    _ => None,
+                };
+                let scroll_step = match dir {
+                    Some(d) => scroll_strategy.step(
+                        app.config.viewer.scroll_step,
+                        layout.cell_h as u32,
+                        d,
+                        &vp.scroll.input_history,
+                    ),
+                    None => app.config.viewer.scroll_step * layout.cell_h as u32,
+                };
+                let mut ctx = mode_normal::NormalCtx {
+                    scroll: &vp.scroll,
+                    doc,
+                    max_scroll: max_y,
+                    scroll_step,
+                    wheel_step: app.config.viewer.wheel_step * layout.cell_h as u32,
+                    half_page: (layout.image_rows as u32 / 2).max(1) * layout.cell_h as u32,
+                    last_search: &mut vp.last_search,
+                    current_file: session.current_file_path(),
+                    current_scale: app.config.scale,
+                };
+                mode_normal::handle(a, &mut ctx)
+            }
+            None => vec![],
+        },
+        ViewerMode::Grep(gs) => match keymap.map_grep_key(key_event) {
+            Some(a) => mode_grep::handle(a, gs, doc, visible_count, max_y),
+            None => vec![],
+        },
+        ViewerMode::InlineSearch(is) => match keymap.map_inline_search_key(key_event) {
+            Some(a) => mode_inline_search::handle(a, is, doc, max_y),
+            None => vec![],
+        },
+        ViewerMode::Command(cs) => match map_command_key(key_event) {
+            Some(a) => mode_command::handle(a, cs),
+            None => vec![],
+        },
+        ViewerMode::Toc(ts) => match keymap.map_toc_key(key_event) {
+            Some(a) => mode_toc::handle(a, ts, doc.visual_lines, visible_count, max_y),
+            None => vec![],
+        },
+        ViewerMode::UrlPicker(up) => match keymap.map_url_key(key_event) {
+            Some(a) => mode_url::handle(
+                a,
+                up,
+                visible_count,
+                session.current_file_path(),
+                doc,
+                max_y,
+            ),
+            None => vec![],
+        },
+        ViewerMode::Log(ls) => match keymap.map_log_key(key_event, ls.search_mode) {
+            Some(a) => {
+                let total_cols = (layout.sidebar_cols + layout.image_cols) as usize;
+                mode_log::handle(a, ls, visible_count, total_cols)
+            }
+            None => vec![],
+        },
+    };
+
+    // Post: if flash was just cleared, ensure redraw
+    if had_flash && effects.is_empty() {
+        effects.push(Effect::RedrawStatusBar);
+    }
+    effects
+}
+
+/// Map a mouse event to effects. Wheel input is Normal-mode-only; other
+/// modes ignore it.
+fn mouse_effects(
+    me: MouseEvent,
+    vp: &mut Viewport,
+    app: &AppContext,
+    session: &Session,
+    doc: &DocumentQuery,
+    max_y: u32,
+) -> Vec<Effect> {
+    let layout = &session.layout;
+    match &mut vp.mode {
+        ViewerMode::Normal => match map_mouse_event(me) {
+            Some(a) => {
+                let mut ctx = mode_normal::NormalCtx {
+                    scroll: &vp.scroll,
+                    doc,
+                    max_scroll: max_y,
+                    scroll_step: app.config.viewer.scroll_step * layout.cell_h as u32,
+                    wheel_step: app.config.viewer.wheel_step * layout.cell_h as u32,
+                    half_page: (layout.image_rows as u32 / 2).max(1) * layout.cell_h as u32,
+                    last_search: &mut vp.last_search,
+                    current_file: session.current_file_path(),
+                    current_scale: app.config.scale,
+                };
+                mode_normal::handle(a, &mut ctx)
+            }
+            None => vec![],
+        },
+        _ => vec![],
+    }
+}
+
+/// Apply effects to the viewport in order and execute their render ops.
+///
+/// In-document jumps go through the session's jump stack and `ToggleWatch`
+/// is handled here (both need `Session`). Returns the exit reason as soon as
+/// one is produced; remaining effects are dropped.
+fn apply_effects(
+    effects: Vec<Effect>,
+    vp: &mut Viewport,
+    session: &mut Session,
+    doc: &DocumentQuery,
+    acc_value: Option<u32>,
+) -> anyhow::Result<Option<ExitReason>> {
+    for effect in effects {
+        let effect = session.resolve_jump(effect, vp.scroll.y_offset);
+        if matches!(effect, Effect::ToggleWatch) {
+            vp.flash = Some(session.toggle_watch());
+            continue;
+        }
+        let ctx = ViewContext {
+            layout: &session.layout,
+            acc_value,
+            filename: &session.filename,
+            jump_stack: &session.jump_stack,
+            doc,
+            log_buffer: &session.log_buffer,
+        };
+        let (new_vp, render_ops) = std::mem::take(vp).apply(effect, &ctx);
+        *vp = new_vp;
+        if let Some(reason) = effect::execute_render_ops(render_ops, vp, &ctx)? {
+            return Ok(Some(reason));
+        }
+    }
+    Ok(None)
+}
+
 /// Interval between retries when a file is temporarily missing (atomic save).
 const RETRY_INTERVAL: Duration = Duration::from_millis(50);
 /// Maximum number of retries before giving up.
@@ -41,7 +41,11 @@ pub(super) fn handle(action: CommandAction, cs: &mut CommandState) -> Vec<Effect
                 }
                 "reload" | "rel" => vec![Effect::Exit(ExitReason::Reload)],
                 "q" | "quit" => vec![Effect::Exit(ExitReason::Quit)],
-                "back" | "b" => vec![Effect::Exit(ExitReason::GoBack)],
+                "back" | "b" => vec![
+                    Effect::ExitToNormal(ScreenRestore::StatusBarRefresh),
+                    Effect::GoBack,
+                    Effect::MarkDirty,
+                ],
                 "open" => vec![Effect::EnterUrlPickerAll],
                 "log" => vec![Effect::EnterLog],
                 "watch" | "w" => vec![
@@ -20,12 +20,41 @@ use super::{Effect, ViewerMode};
 /// Produce the effect(s) to open a classified link target.
 ///
 /// Local markdown links are resolved against `current_file` and become
-/// `Exit(Navigate)`; everything else becomes `OpenExternalUrl`.
-pub(super) fn open_link_target(target: &LinkTarget, current_file: Option<&Path>) -> Vec<Effect> {
+/// `Exit(Navigate)` (carrying any `#fragment`); same-document anchors and
+/// footnotes become `JumpWithin` to the heading / definition; everything
+/// else becomes `OpenExternalUrl`.
+pub(super) fn open_link_target(
+    target: &LinkTarget,
+    current_file: Option<&Path>,
+    doc: &DocumentQuery,
+    max_scroll: u32,
+) -> Vec<Effect> {
     match target {
+        LinkTarget::Footnote(label) => match doc.find_footnote_definition(label) {
+            Some(vl_idx) => {
+                let y = visual_line_offset(doc.visual_lines, max_scroll, (vl_idx + 1) as u32);
+                vec![Effect::JumpWithin(y)]
+            }
+            None => vec![Effect::Flash(format!(
+                "Footnote [^{label}] has no definition"
+            ))],
+        },
+        LinkTarget::Anchor(url) => {
+            let fragment = url.trim_start_matches('#');
+            match doc.find_anchor(fragment) {
+                Some(vl_idx) => {
+                    let y = visual_line_offset(doc.visual_lines, max_scroll, (vl_idx + 1) as u32);
+                    vec![Effect::JumpWithin(y)]
+                }
+                None => vec![Effect::Flash(format!("No heading for {url}"))],
+            }
+        }
         LinkTarget::LocalMarkdown(rel) => {
             match current_file.and_then(|f| crate::url::resolve_link_path(rel, f)) {
-                Some(path) => vec![Effect::Exit(ExitReason::Navigate { path })],
+                Some(path) => vec![Effect::Exit(ExitReason::Navigate {
+                    path,
+                    anchor: crate::url::link_fragment(rel).map(str::to_string),
+                })],
                 None => vec![Effect::OpenExternalUrl(rel.clone())],
             }
         }
@@ -130,7 +159,7 @@ pub(super) fn handle(action: Action, ctx: &mut NormalCtx) -> Vec<Effect> {
 
         Action::CancelInput => vec![Effect::RedrawStatusBar],
 
-        Action::Digit => vec![Effect::RedrawStatusBar],
+        Action::Pending => vec![Effect::RedrawStatusBar],
 
         Action::ZoomIn => zoom_effects(ctx.current_scale, next_zoom_preset(ctx.current_scale, 1)),
         Action::ZoomOut => zoom_effects(ctx.current_scale, next_zoom_preset(ctx.current_scale, -1)),
@@ -432,10 +461,16 @@ fn open_url(ctx: &NormalCtx, line_num: u32) -> Vec<Effect> {
         ];
     }
     if urls.len() == 1 {
-        let display = urls[0].target.display_url().to_string();
-        debug!("open_url L{line_num}: {display}");
-        let mut effects = open_link_target(&urls[0].target, ctx.current_file);
-        effects.push(Effect::Flash(format!("Opening {display}")));
+        let target = &urls[0].target;
+        debug!("open_url L{line_num}: {}", target.display_url());
+        // Flash first so a failure message from open_link_target wins.
+        let mut effects = vec![Effect::Flash(target.describe())];
+        effects.extend(open_link_target(
+            target,
+            ctx.current_file,
+            ctx.doc,
+            ctx.max_scroll,
+        ));
         effects.push(Effect::RedrawStatusBar);
         effects
     } else {
@@ -683,6 +718,53 @@ mod tests {
         assert!(matches!(&effects[0], Effect::Flash(msg) if msg.contains("no source mapping")));
     }
 
+    #[test]
+    fn open_url_footnote_jumps_to_definition() {
+        let state = make_state(0);
+        let md = "Body[^n]\n\n[^n]: Note.\n";
+        let (_, ci) = crate::compile::markdown_to_typst(md, None);
+        let mut vls = super::super::query::test_helpers::make_visual_lines(md);
+        for (i, vl) in vls.iter_mut().enumerate() {
+            vl.y_px = i as u32 * 100;
+        }
+        let doc = DocumentQuery::new(md, &vls, &ci, 0);
+        let mut ls = None;
+        let mut ctx = make_ctx(&state, &doc, &mut ls);
+        let effects = handle(Action::OpenUrl(1), &mut ctx);
+        assert!(matches!(&effects[0], Effect::Flash(msg) if msg == "Footnote [^n]"));
+        // Definition is on visual line 3; the jump targets the previous baseline.
+        assert!(matches!(effects[1], Effect::JumpWithin(100)));
+    }
+
+    #[test]
+    fn open_url_anchor_jumps_to_heading() {
+        let state = make_state(0);
+        let md = "See [setup](#setup).\n\n## Setup\n";
+        let mut vls = super::super::query::test_helpers::make_visual_lines(md);
+        for (i, vl) in vls.iter_mut().enumerate() {
+            vl.y_px = i as u32 * 100;
+        }
+        let ci = empty_ci();
+        let doc = DocumentQuery::new(md, &vls, &ci, 0);
+        let mut ls = None;
+        let mut ctx = make_ctx(&state, &doc, &mut ls);
+        let effects = handle(Action::OpenUrl(1), &mut ctx);
+        assert!(matches!(&effects[0], Effect::Flash(msg) if msg == "Jumping to #setup"));
+        assert!(matches!(effects[1], Effect::JumpWithin(100)));
+
+        let target = LinkTarget::Anchor("#gone".into());
+        let effects = open_link_target(&target, None, &doc, 1000);
+        assert!(matches!(&effects[0], Effect::Flash(msg) if msg == "No heading for #gone"));
+
+        let target = LinkTarget::LocalMarkdown("guide.md#setup".into());
+        let effects = open_link_target(&target, Some(Path::new("/docs/readme.md")), &doc, 1000);
+        assert!(matches!(
+            &effects[0],
+            Effect::Exit(ExitReason::Navigate { path, anchor })
+                if path == Path::new("/docs/guide.md") && anchor.as_deref() == Some("setup")
+        ));
+    }
+
     #[test]
     fn search_next_without_results_flashes() {
         let state = make_state(0);
@@ -5,6 +5,7 @@ use crossterm::{
     style::{self, Stylize},
     terminal::{Clear, ClearType},
 };
+use pulldown_cmark::{Event, Parser, Tag, TagEnd};
 use std::io::{self, Write, stdout};
 
 use super::Effect;
@@ -13,14 +14,23 @@ use super::effect::ScreenRestore;
 use super::keymap::TocAction;
 use super::layout::{Layout, visual_line_offset};
 use super::query::DocumentQuery;
-use crate::frame::VisualLine;
+use crate::frame::{VisualLine, byte_offset_to_line};
 
 /// A single heading entry in the TOC.
-pub(super) struct TocEntry {
+pub(crate) struct TocEntry {
     pub level: u8,
     pub text: String,
     pub md_line: usize,
     pub visual_line_idx: usize,
+    /// Progress of each task list in this heading's section, in document order.
+    pub tasks: Vec<TaskProgress>,
+}
+
+/// Checked / total task items of one top-level task list.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct TaskProgress {
+    pub done: usize,
+    pub total: usize,
 }
 
 /// Mutable state for TOC overlay mode.
@@ -43,8 +53,9 @@ impl TocState {
 /// Collect headings from markdown source and map them to visual lines.
 ///
 /// Parses ATX headings (`# heading`) by scanning lines directly.
-/// Lines inside fenced code blocks are ignored.
-pub(super) fn collect_headings(doc: &DocumentQuery) -> Vec<TocEntry> {
+/// Lines inside fenced code blocks are ignored. Each heading is annotated
+/// with the task lists between it and the next heading.
+pub(crate) fn collect_headings(doc: &DocumentQuery) -> Vec<TocEntry> {
     let mut entries = Vec::new();
     let mut in_code_block = false;
 
@@ -79,12 +90,56 @@ pub(super) fn collect_headings(doc: &DocumentQuery) -> Vec<TocEntry> {
                 text,
                 md_line,
                 visual_line_idx: vl_idx,
+                tasks: Vec::new(),
             });
         }
     }
+
+    for (md_line, progress) in collect_task_lists(doc.markdown) {
+        if let Some(entry) = entries.iter_mut().rev().find(|e| e.md_line < md_line) {
+            entry.tasks.push(progress);
+        }
+    }
     entries
 }
 
+/// Find top-level lists containing task items (`- [ ]` / `- [x]`).
+///
+/// Returns the 1-based starting line of each such list with its progress.
+/// Nested task items count toward their outermost list.
+fn collect_task_lists(markdown: &str) -> Vec<(usize, TaskProgress)> {
+    let parser = Parser::new_ext(markdown, crate::compile::parser_options());
+    let mut lists = Vec::new();
+    let mut depth = 0usize;
+    let mut start = 0usize;
+    let mut progress = TaskProgress { done: 0, total: 0 };
+    for (event, range) in parser.into_offset_iter() {
+        match event {
+            Event::Start(Tag::List(_)) => {
+                if depth == 0 {
+                    start = range.start;
+                    progress = TaskProgress { done: 0, total: 0 };
+                }
+                depth += 1;
+            }
+            Event::End(TagEnd::List(_)) => {
+                depth -= 1;
+                if depth == 0 && progress.total > 0 {
+                    lists.push((byte_offset_to_line(markdown, start), progress));
+                }
+            }
+            Event::TaskListMarker(checked) => {
+                progress.total += 1;
+                if checked {
+                    progress.done += 1;
+                }
+            }
+            _ => {}
+        }
+    }
+    lists
+}
+
 /// Draw the TOC overlay screen.
 pub(super) fn draw_toc_screen(layout: &Layout, state: &TocState) -> io::Result<()> {
     let mut out = stdout();
@@ -119,12 +174,15 @@ pub(super) fn draw_toc_screen(layout: &Layout, state: &TocState) -> io::Result<(
         let marker = if is_selected { " > " } else { "   " };
         let line_label = format!("L{:<4}", e.md_line);
         let hashes = "#".repeat(e.level as usize);
-        let content = format!(
+        let mut content = format!(
             "{marker}{line_label} {:indent$}{hashes} {}",
             "",
             e.text,
             indent = indent
         );
+        for t in &e.tasks {
+            content.push_str(&format!("  {}/{} done", t.done, t.total));
+        }
 
         let display: String = content.chars().take(total_cols).collect();
         let pad = total_cols.saturating_sub(display.len());
@@ -271,6 +329,45 @@ mod tests {
         }
     }
 
+    #[test]
+    fn collect_headings_task_progress_per_list() {
+        let md = "# Intro\n- [ ] draft\n# Todo\n- [x] a\n- [ ] b\n  - [x] nested\n\ntext\n\n- [x] c\n# Notes\n- plain\n";
+        let vls = make_visual_lines(md);
+        let ci = empty_ci();
+        let doc = DocumentQuery::new(md, &vls, &ci, 0);
+        let entries = collect_headings(&doc);
+        assert_eq!(entries.len(), 3);
+        assert_eq!(entries[0].tasks, vec![TaskProgress { done: 0, total: 1 }]);
+        assert_eq!(
+            entries[1].tasks,
+            vec![
+                TaskProgress { done: 2, total: 3 },
+                TaskProgress { done: 1, total: 1 },
+            ]
+        );
+        assert!(entries[2].tasks.is_empty());
+    }
+
+    #[test]
+    fn collect_task_lists_skips_front_matter() {
+        let md = "---\ntodo:\n- [ ] not a task\n---\n# Head\n- [x] a\n";
+        assert_eq!(
+            collect_task_lists(md),
+            vec![(6, TaskProgress { done: 1, total: 1 })]
+        );
+    }
+
+    #[test]
+    fn collect_task_lists_ignores_lists_before_first_heading() {
+        let md = "- [x] early\n\n# Head\n";
+        let vls = make_visual_lines(md);
+        let ci = empty_ci();
+        let doc = DocumentQuery::new(md, &vls, &ci, 0);
+        let entries = collect_headings(&doc);
+        assert_eq!(entries.len(), 1);
+        assert!(entries[0].tasks.is_empty());
+    }
+
     #[test]
     fn handle_select_next() {
         let entries = vec![
@@ -279,12 +376,14 @@ mod tests {
                 text: "A".into(),
                 md_line: 1,
                 visual_line_idx: 0,
+                tasks: Vec::new(),
             },
             TocEntry {
                 level: 2,
                 text: "B".into(),
                 md_line: 3,
                 visual_line_idx: 2,
+                tasks: Vec::new(),
             },
         ];
         let mut state = TocState::new(entries);
@@ -305,12 +404,14 @@ mod tests {
                 text: "A".into(),
                 md_line: 1,
                 visual_line_idx: 0,
+                tasks: Vec::new(),
             },
             TocEntry {
                 level: 2,
                 text: "B".into(),
                 md_line: 3,
                 visual_line_idx: 2,
+                tasks: Vec::new(),
             },
         ];
         let mut state = TocState::new(entries);
@@ -331,12 +432,14 @@ mod tests {
                 text: "Title".into(),
                 md_line: 1,
                 visual_line_idx: 0,
+                tasks: Vec::new(),
             },
             TocEntry {
                 level: 2,
                 text: "Section".into(),
                 md_line: 5,
                 visual_line_idx: 3,
+                tasks: Vec::new(),
             },
         ];
         let mut state = TocState::new(entries);
@@ -363,6 +466,7 @@ mod tests {
             text: "A".into(),
             md_line: 1,
             visual_line_idx: 0,
+            tasks: Vec::new(),
         }];
         let mut state = TocState::new(entries);
         let vls = vec![make_vl("", None)];
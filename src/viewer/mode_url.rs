@@ -156,6 +156,8 @@ pub(super) fn handle(
     state: &mut UrlPickerState,
     visible_count: usize,
     current_file: Option<&std::path::Path>,
+    doc: &DocumentQuery,
+    max_scroll: u32,
 ) -> Vec<Effect> {
     match action {
         UrlAction::SelectNext => {
@@ -184,9 +186,14 @@ pub(super) fn handle(
                 ];
             }
             let entry = &state.entries[state.selected];
-            let display = entry.target.display_url().to_string();
-            let mut effects = open_link_target(&entry.target, current_file);
-            effects.push(Effect::Flash(format!("Opening {display}")));
+            // Flash first so a failure message from open_link_target wins.
+            let mut effects = vec![Effect::Flash(entry.target.describe())];
+            effects.extend(open_link_target(
+                &entry.target,
+                current_file,
+                doc,
+                max_scroll,
+            ));
             effects.push(Effect::ExitToNormal(ScreenRestore::FullRefresh));
             effects
         }
@@ -258,11 +265,13 @@ mod tests {
             },
         ];
         let mut state = UrlPickerState::new(entries);
+        let ci = empty_ci();
+        let doc = DocumentQuery::new("", &[], &ci, 0);
         assert_eq!(state.selected, 0);
-        let _ = handle(UrlAction::SelectNext, &mut state, 20, None);
+        let _ = handle(UrlAction::SelectNext, &mut state, 20, None, &doc, 0);
         assert_eq!(state.selected, 1);
         // Should clamp at end
-        let _ = handle(UrlAction::SelectNext, &mut state, 20, None);
+        let _ = handle(UrlAction::SelectNext, &mut state, 20, None, &doc, 0);
         assert_eq!(state.selected, 1);
     }
 
@@ -281,11 +290,13 @@ mod tests {
             },
         ];
         let mut state = UrlPickerState::new(entries);
+        let ci = empty_ci();
+        let doc = DocumentQuery::new("", &[], &ci, 0);
         state.selected = 1;
-        let _ = handle(UrlAction::SelectPrev, &mut state, 20, None);
+        let _ = handle(UrlAction::SelectPrev, &mut state, 20, None, &doc, 0);
         assert_eq!(state.selected, 0);
         // Should clamp at 0
-        let _ = handle(UrlAction::SelectPrev, &mut state, 20, None);
+        let _ = handle(UrlAction::SelectPrev, &mut state, 20, None, &doc, 0);
         assert_eq!(state.selected, 0);
     }
 
@@ -304,8 +315,10 @@ mod tests {
             },
         ];
         let mut state = UrlPickerState::new(entries);
+        let ci = empty_ci();
+        let doc = DocumentQuery::new("", &[], &ci, 0);
         state.selected = 1;
-        let effects = handle(UrlAction::Confirm, &mut state, 20, None);
+        let effects = handle(UrlAction::Confirm, &mut state, 20, None, &doc, 0);
         assert!(
             effects
                 .iter()
@@ -321,7 +334,9 @@ mod tests {
             visual_line: 1,
         }];
         let mut state = UrlPickerState::new(entries);
-        let effects = handle(UrlAction::Cancel, &mut state, 20, None);
+        let ci = empty_ci();
+        let doc = DocumentQuery::new("", &[], &ci, 0);
+        let effects = handle(UrlAction::Cancel, &mut state, 20, None, &doc, 0);
         assert!(
             effects
                 .iter()
@@ -6,7 +6,7 @@
 
 use pulldown_cmark::{Event, Options, Parser, Tag, TagEnd};
 
-use crate::compile::ContentIndex;
+use crate::compile::{ContentIndex, parser_options};
 use crate::frame::{VisualLine, byte_offset_to_line};
 use crate::url::LinkTarget;
 
@@ -131,7 +131,32 @@ impl<'a> DocumentQuery<'a> {
         let start = byte_offset_to_line(self.markdown, r.start);
         let end = byte_offset_to_line(self.markdown, r.end.saturating_sub(1).max(r.start));
 
-        extract_urls_from_lines(self.markdown, start, end)
+        let mut urls = extract_urls_from_lines(self.markdown, start, end);
+        for footnote in self.content_index.footnotes() {
+            if footnote.references.iter().any(|fr| r.contains(&fr.start)) {
+                urls.push(UrlEntry {
+                    target: LinkTarget::Footnote(footnote.label.clone()),
+                    text: format!("footnote {}", footnote.number),
+                });
+            }
+        }
+        urls
+    }
+
+    /// Find the visual line index where the definition of footnote `label` starts.
+    pub fn find_footnote_definition(&self, label: &str) -> Option<usize> {
+        let def = self.content_index.footnote(label)?.definition.as_ref()?;
+        self.find_visual_line_by_offset(def.start)
+    }
+
+    /// Find the visual line index of the heading whose GitHub-style slug
+    /// matches `fragment` (without the leading `#`, percent-decoding applied).
+    pub fn find_anchor(&self, fragment: &str) -> Option<usize> {
+        let wanted = crate::url::percent_decode(fragment).to_lowercase();
+        let (_, offset) = heading_anchors(self.markdown)
+            .into_iter()
+            .find(|(slug, _)| *slug == wanted)?;
+        self.find_visual_line_by_offset(offset)
     }
 
     /// Delegate to `byte_offset_to_line`.
@@ -140,9 +165,42 @@ impl<'a> DocumentQuery<'a> {
     }
 }
 
+/// Heading anchors in document order: `(slug, heading start byte offset)`.
+///
+/// Slugs follow GitHub: repeated headings get `-1`, `-2`, … suffixes.
+pub fn heading_anchors(markdown: &str) -> Vec<(String, usize)> {
+    let mut anchors: Vec<(String, usize)> = Vec::new();
+    let mut current: Option<(String, usize)> = None;
+    for (event, range) in Parser::new_ext(markdown, parser_options()).into_offset_iter() {
+        match event {
+            Event::Start(Tag::Heading { .. }) => current = Some((String::new(), range.start)),
+            Event::Text(t) | Event::Code(t) => {
+                if let Some((text, _)) = current.as_mut() {
+                    text.push_str(&t);
+                }
+            }
+            Event::End(TagEnd::Heading(_)) => {
+                if let Some((text, start)) = current.take() {
+                    let base = crate::url::heading_slug(&text);
+                    let mut slug = base.clone();
+                    let mut n = 0;
+                    while anchors.iter().any(|(s, _)| *s == slug) {
+                        n += 1;
+                        slug = format!("{base}-{n}");
+                    }
+                    anchors.push((slug, start));
+                }
+            }
+            _ => {}
+        }
+    }
+    anchors
+}
+
 /// Extract URLs from a range of Markdown source lines (1-based, inclusive).
 ///
-/// Step 1: Parse with pulldown-cmark to extract `[text](url)` links.
+/// Step 1: Parse with pulldown-cmark to extract `[text](url)` links and
+///         `<a href>` tags in raw HTML.
 /// Step 2: Extract bare URLs (e.g., `https://example.com`) from plain text
 ///         using regex, deduplicating against URLs already found in step 1.
 pub fn extract_urls_from_lines(md_source: &str, start: usize, end: usize) -> Vec<UrlEntry> {
@@ -154,7 +212,9 @@ pub fn extract_urls_from_lines(md_source: &str, start: usize, end: usize) -> Vec
     }
     let block_text = lines[start_idx..end_idx].join("\n");
 
-    let parser = Parser::new_ext(&block_text, Options::empty());
+    // Footnotes enabled so `[^x]: text` parses as a definition (whose links
+    // are extractable) rather than a link reference definition.
+    let parser = Parser::new_ext(&block_text, Options::ENABLE_FOOTNOTES);
     let mut urls = Vec::new();
     let mut in_link = false;
     let mut current_url = String::new();
@@ -185,6 +245,14 @@ pub fn extract_urls_from_lines(md_source: &str, start: usize, end: usize) -> Vec
             Event::Text(t) => {
                 plain_texts.push(t.into_string());
             }
+            Event::Html(html) | Event::InlineHtml(html) => {
+                for href in crate::compile::extract_link_hrefs(&html) {
+                    urls.push(UrlEntry {
+                        target: LinkTarget::classify(&href),
+                        text: href,
+                    });
+                }
+            }
             _ => {}
         }
     }
@@ -208,7 +276,7 @@ pub fn extract_urls_from_lines(md_source: &str, start: usize, end: usize) -> Vec
 /// Provides common factory functions so that each mode's `#[cfg(test)]` module
 /// can `use super::query::test_helpers::*` instead of duplicating them.
 #[cfg(test)]
-pub(super) mod test_helpers {
+pub(crate) mod test_helpers {
     use crate::compile::ContentIndex;
     use crate::frame::VisualLine;
 
@@ -388,6 +456,56 @@ mod tests {
         assert_eq!(urls[1].text, "link2");
     }
 
+    #[test]
+    fn extract_urls_includes_footnote_references() {
+        let md = "See [site](https://a.invalid/)[^n].\n\n[^n]: Note.\n";
+        let (_, ci) = crate::compile::markdown_to_typst(md, None);
+        let vl = make_visual_lines(md);
+        let doc = DocumentQuery::new(md, &vl, &ci, 0);
+        let urls = doc.extract_urls(0);
+        assert_eq!(urls.len(), 2);
+        assert_eq!(urls[0].target.display_url(), "https://a.invalid/");
+        assert_eq!(urls[1].target, LinkTarget::Footnote("n".into()));
+        assert_eq!(urls[1].text, "footnote 1");
+        assert_eq!(doc.find_footnote_definition("n"), Some(2));
+        assert_eq!(doc.find_footnote_definition("missing"), None);
+    }
+
+    #[test]
+    fn heading_anchors_dedupe_and_inline_code() {
+        let md = "# Intro\n\n## Use `mlux` Now\n\n# Intro\n";
+        let slugs: Vec<String> = heading_anchors(md).into_iter().map(|(s, _)| s).collect();
+        assert_eq!(slugs, vec!["intro", "use-mlux-now", "intro-1"]);
+    }
+
+    #[test]
+    fn find_anchor_resolves_heading_line() {
+        let md = "# Top\n\nText\n\n## Café Setup\n";
+        let vl = make_visual_lines(md);
+        let ci = empty_ci();
+        let doc = DocumentQuery::new(md, &vl, &ci, 0);
+        assert_eq!(doc.find_anchor("top"), Some(0));
+        assert_eq!(doc.find_anchor("Caf%C3%A9-setup"), Some(4));
+        assert_eq!(doc.find_anchor("nope"), None);
+    }
+
+    #[test]
+    fn extract_urls_from_footnote_definition() {
+        let md = "[^n]: See <https://a.invalid/>.\n";
+        let urls = extract_urls_from_lines(md, 1, 1);
+        assert_eq!(urls.len(), 1);
+        assert_eq!(urls[0].target.display_url(), "https://a.invalid/");
+    }
+
+    #[test]
+    fn extract_urls_from_lines_html_anchor() {
+        let md = "See <a href=\"https://a.invalid/\">docs</a> and <a href='#setup'>setup</a>\n";
+        let urls = extract_urls_from_lines(md, 1, 1);
+        assert_eq!(urls.len(), 2);
+        assert_eq!(urls[0].target.display_url(), "https://a.invalid/");
+        assert_eq!(urls[1].target, LinkTarget::Anchor("#setup".into()));
+    }
+
     #[test]
     fn extract_urls_from_lines_bare_url() {
         let md = "Check https://rust-lang.invalid/ for more\n";
@@ -10,12 +10,16 @@ use std::path::{Path, PathBuf};
 use crate::input_source::InputSource;
 use crate::watch::FileWatcher;
 
+use super::Effect;
+use super::graphics::Graphics;
 use super::layout::{self, Layout};
 use super::terminal;
 
-/// Jump stack entry for markdown link navigation.
+/// Jump stack entry for markdown link and footnote navigation.
 pub(super) struct JumpEntry {
-    pub path: PathBuf,
+    /// File to return to, or `None` for a jump within the same document
+    /// (which is always the document on screen when the entry is on top).
+    pub path: Option<PathBuf>,
     pub y_offset: u32,
 }
 
@@ -41,7 +45,11 @@ pub(super) struct Session {
     pub jump_stack: Vec<JumpEntry>,
     pub scroll_carry: u32,
     pub pending_flash: Option<String>,
+    /// Heading fragment to scroll to once the next document is built
+    /// (set by `Navigate` to `other.md#heading`).
+    pub pending_anchor: Option<String>,
     pub watch: bool,
+    pub graphics: Graphics,
     pub log_buffer: crate::log::LogBuffer,
 }
 
@@ -54,6 +62,49 @@ impl Session {
         }
     }
 
+    /// Route in-document jumps through the jump stack.
+    ///
+    /// `JumpWithin` records `scroll_position` before scrolling; `GoBack` onto a
+    /// same-document entry becomes a plain scroll instead of a rebuild. Other
+    /// effects pass through unchanged.
+    pub(super) fn resolve_jump(&mut self, effect: Effect, scroll_position: u32) -> Effect {
+        match effect {
+            Effect::JumpWithin(_) => {
+                self.jump_stack.push(JumpEntry {
+                    path: None,
+                    y_offset: scroll_position,
+                });
+                effect
+            }
+            Effect::GoBack if self.jump_stack.last().is_some_and(|e| e.path.is_none()) => {
+                let entry = self.jump_stack.pop().expect("checked non-empty");
+                debug!("go back: returning to y={} in document", entry.y_offset);
+                Effect::ScrollAnchor(entry.y_offset)
+            }
+            other => other,
+        }
+    }
+
+    /// Toggle file watching (`:watch`); returns the status bar message.
+    pub(super) fn toggle_watch(&mut self) -> String {
+        let Some(path) = self.current_file_path().map(Path::to_path_buf) else {
+            return "watch: not available for stdin".into();
+        };
+        if self.watch {
+            self.watch = false;
+            self.watcher = None;
+            return "watch: off".into();
+        }
+        match FileWatcher::new(&path) {
+            Ok(w) => {
+                self.watch = true;
+                self.watcher = Some(w);
+                "watch: on".into()
+            }
+            Err(e) => format!("watch: failed ({e})"),
+        }
+    }
+
     /// Recompute layout for new terminal dimensions and clear stale images.
     pub(super) fn update_layout_for_resize(
         &mut self,
@@ -61,15 +112,19 @@ impl Session {
         new_rows: u16,
         sidebar_cols: u16,
     ) -> anyhow::Result<()> {
-        let new_winsize = crossterm_terminal::window_size()?;
-        self.layout = layout::compute_layout(
-            new_cols,
-            new_rows,
-            new_winsize.width,
-            new_winsize.height,
-            sidebar_cols,
-        );
-        terminal::delete_all_images()?;
+        self.layout = if self.graphics == Graphics::Text {
+            layout::compute_text_layout(new_cols, new_rows, sidebar_cols)
+        } else {
+            let new_winsize = crossterm_terminal::window_size()?;
+            layout::compute_layout(
+                new_cols,
+                new_rows,
+                new_winsize.width,
+                new_winsize.height,
+                sidebar_cols,
+            )
+        };
+        terminal::clear_images(self.graphics)?;
         Ok(())
     }
 
@@ -105,16 +160,16 @@ impl Session {
                     self.scroll_carry
                 );
             }
-            ExitReason::Navigate { path } => {
+            ExitReason::Navigate { path, anchor } => {
                 if !path.exists() {
                     self.pending_flash = Some(format!("File not found: {}", path.display()));
-                    terminal::delete_all_images()?;
+                    terminal::clear_images(self.graphics)?;
                     return Ok(false);
                 }
                 // Push current location onto jump stack
                 if let InputSource::File(cur) = &self.input {
                     self.jump_stack.push(JumpEntry {
-                        path: cur.clone(),
+                        path: Some(cur.clone()),
                         y_offset: scroll_position,
                     });
                 }
@@ -123,23 +178,28 @@ impl Session {
                 self.input = InputSource::File(canonical.clone());
                 self.filename = self.input.display_name().to_string();
                 self.scroll_carry = 0;
+                self.pending_anchor = anchor;
                 if self.watch {
                     self.watcher = Some(FileWatcher::new(&canonical)?);
                 }
-                terminal::delete_all_images()?;
+                terminal::clear_images(self.graphics)?;
                 // continue 'outer -> load new file
             }
             ExitReason::GoBack => {
                 // jump_stack is guaranteed non-empty here (inner loop checks)
                 let entry = self.jump_stack.pop().expect("GoBack with empty stack");
-                debug!("go back: returning to {}", entry.path.display());
-                self.input = InputSource::File(entry.path.clone());
-                self.filename = self.input.display_name().to_string();
                 self.scroll_carry = entry.y_offset;
-                if self.watch {
-                    self.watcher = Some(FileWatcher::new(&entry.path)?);
+                // Same-document entries are normally consumed by
+                // resolve_jump; if one gets here, reload in place.
+                if let Some(path) = entry.path {
+                    debug!("go back: returning to {}", path.display());
+                    if self.watch {
+                        self.watcher = Some(FileWatcher::new(&path)?);
+                    }
+                    self.input = InputSource::File(path);
+                    self.filename = self.input.display_name().to_string();
                 }
-                terminal::delete_all_images()?;
+                terminal::clear_images(self.graphics)?;
                 // continue 'outer -> reload previous file
             }
         }
@@ -149,7 +209,63 @@ impl Session {
 
 #[cfg(test)]
 mod tests {
-    use super::scale_scroll;
+    use super::*;
+
+    fn make_session() -> Session {
+        Session {
+            layout: layout::compute_layout(80, 24, 800, 480, 6),
+            input: InputSource::File(PathBuf::from("/doc.md")),
+            filename: "doc.md".into(),
+            watcher: None,
+            jump_stack: Vec::new(),
+            scroll_carry: 0,
+            pending_flash: None,
+            pending_anchor: None,
+            watch: false,
+            graphics: Graphics::Kitty,
+            log_buffer: crate::log::LogBuffer::new(16),
+        }
+    }
+
+    #[test]
+    fn jump_within_pushes_local_entry() {
+        let mut session = make_session();
+        let effect = session.resolve_jump(Effect::JumpWithin(900), 120);
+        assert!(matches!(effect, Effect::JumpWithin(900)));
+        assert_eq!(session.jump_stack.len(), 1);
+        assert!(session.jump_stack[0].path.is_none());
+        assert_eq!(session.jump_stack[0].y_offset, 120);
+    }
+
+    #[test]
+    fn go_back_to_local_entry_scrolls_in_place() {
+        let mut session = make_session();
+        session.resolve_jump(Effect::JumpWithin(900), 120);
+        let effect = session.resolve_jump(Effect::GoBack, 900);
+        assert!(matches!(effect, Effect::ScrollAnchor(120)));
+        assert!(session.jump_stack.is_empty());
+    }
+
+    #[test]
+    fn go_back_to_file_entry_passes_through() {
+        let mut session = make_session();
+        session.jump_stack.push(JumpEntry {
+            path: Some(PathBuf::from("/other.md")),
+            y_offset: 40,
+        });
+        let effect = session.resolve_jump(Effect::GoBack, 0);
+        assert!(matches!(effect, Effect::GoBack));
+        assert_eq!(session.jump_stack.len(), 1);
+    }
+
+    #[test]
+    fn toggle_watch_off_drops_watcher() {
+        let mut session = make_session();
+        session.watch = true;
+        assert_eq!(session.toggle_watch(), "watch: off");
+        assert!(!session.watch);
+        assert!(session.watcher.is_none());
+    }
 
     #[test]
     fn scale_scroll_zoom_in_doubles_offset() {
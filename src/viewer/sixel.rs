@@ -0,0 +1,198 @@
+//! DEC Sixel encoding of composited viewport bitmaps.
+//!
+//! Sixel has at most a few hundred palette registers, so colors are reduced
+//! first: pixels are bucketed to 15-bit RGB, the most frequent buckets become
+//! the palette and every other bucket maps to its nearest palette entry.
+//! Rendered documents are a handful of flat colors plus anti-aliasing ramps
+//! between them, which survives this almost unchanged.
+//!
+//! The image is then written band by band (six pixel rows each), one
+//! run-length encoded pass per color present in the band.
+
+use std::cmp::Reverse;
+use std::fmt::Write;
+
+use super::composite::Canvas;
+
+/// Palette registers used. 256 is the common terminal maximum; one is left
+/// spare for terminals that reserve register 0.
+const MAX_COLORS: usize = 255;
+
+/// Runs at least this long are written as `!<n><char>`.
+const MIN_RUN: usize = 4;
+
+/// 15-bit RGB bucket of an RGBA pixel. Alpha is ignored: tiles are opaque.
+fn bucket(px: &[u8]) -> usize {
+    ((px[0] as usize >> 3) << 10) | ((px[1] as usize >> 3) << 5) | (px[2] as usize >> 3)
+}
+
+fn bucket_rgb(b: usize) -> [u32; 3] {
+    [(b >> 10) as u32 & 31, (b >> 5) as u32 & 31, b as u32 & 31]
+}
+
+/// Build the palette and the bucket → register map.
+fn quantize(data: &[u8]) -> (Vec<usize>, Vec<u8>) {
+    let mut counts = vec![0u32; 1 << 15];
+    for px in data.chunks_exact(4) {
+        counts[bucket(px)] += 1;
+    }
+    let mut used: Vec<usize> = (0..counts.len()).filter(|&b| counts[b] > 0).collect();
+    used.sort_by_key(|&b| Reverse(counts[b]));
+    let split = used.len().min(MAX_COLORS);
+    let (palette, rest) = used.split_at(split);
+
+    let mut map = vec![0u8; 1 << 15];
+    for (reg, &b) in palette.iter().enumerate() {
+        map[b] = reg as u8;
+    }
+    for &b in rest {
+        let [r, g, bl] = bucket_rgb(b);
+        let nearest = palette.iter().enumerate().min_by_key(|&(_, &p)| {
+            let [pr, pg, pb] = bucket_rgb(p);
+            r.abs_diff(pr).pow(2) + g.abs_diff(pg).pow(2) + bl.abs_diff(pb).pow(2)
+        });
+        if let Some((reg, _)) = nearest {
+            map[b] = reg as u8;
+        }
+    }
+    (palette.to_vec(), map)
+}
+
+/// Encode `canvas` as a complete Sixel sequence (DCS ... ST) with 1:1 pixel
+/// aspect ratio.
+pub(super) fn encode(canvas: &Canvas) -> String {
+    let (w, h) = (canvas.width as usize, canvas.height as usize);
+    let mut out = String::new();
+    let _ = write!(out, "\x1bPq\"1;1;{w};{h}");
+    if w == 0 || h == 0 {
+        out.push_str("\x1b\\");
+        return out;
+    }
+
+    let (palette, map) = quantize(&canvas.data);
+    for (reg, &b) in palette.iter().enumerate() {
+        let [r, g, bl] = bucket_rgb(b).map(|c| c * 100 / 31);
+        let _ = write!(out, "#{reg};2;{r};{g};{bl}");
+    }
+
+    // Per-register sixel bits of the current band, allocated on first use.
+    let mut bits: Vec<Vec<u8>> = vec![Vec::new(); palette.len()];
+    let mut present: Vec<usize> = Vec::new();
+    for band in 0..h.div_ceil(6) {
+        for dy in 0..6.min(h - band * 6) {
+            let row = (band * 6 + dy) * w * 4;
+            for x in 0..w {
+                let i = row + x * 4;
+                let reg = map[bucket(&canvas.data[i..i + 4])] as usize;
+                if bits[reg].is_empty() {
+                    bits[reg].resize(w, 0);
+                    present.push(reg);
+                }
+                bits[reg][x] |= 1 << dy;
+            }
+        }
+        for (n, &reg) in present.iter().enumerate() {
+            if n > 0 {
+                out.push('$');
+            }
+            let _ = write!(out, "#{reg}");
+            push_runs(&mut out, &bits[reg]);
+            bits[reg].clear();
+        }
+        present.clear();
+        out.push('-');
+    }
+    out.push_str("\x1b\\");
+    out
+}
+
+/// Append one color pass, run-length encoded. Trailing empty columns are
+/// dropped: the following `$` returns to the band start anyway.
+fn push_runs(out: &mut String, cols: &[u8]) {
+    let len = cols.iter().rposition(|&c| c != 0).map_or(0, |i| i + 1);
+    let mut x = 0;
+    while x < len {
+        let v = cols[x];
+        let run = cols[x..len].iter().take_while(|&&c| c == v).count();
+        let ch = (b'?' + v) as char;
+        if run >= MIN_RUN {
+            let _ = write!(out, "!{run}{ch}");
+        } else {
+            (0..run).for_each(|_| out.push(ch));
+        }
+        x += run;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn canvas(width: u32, height: u32, f: impl Fn(u32, u32) -> [u8; 4]) -> Canvas {
+        let mut data = Vec::new();
+        for y in 0..height {
+            for x in 0..width {
+                data.extend_from_slice(&f(x, y));
+            }
+        }
+        Canvas {
+            width,
+            height,
+            data,
+        }
+    }
+
+    #[test]
+    fn encode_solid_band() {
+        let c = canvas(10, 6, |_, _| [255, 0, 0, 255]);
+        assert_eq!(encode(&c), "\x1bPq\"1;1;10;6#0;2;100;0;0#0!10~-\x1b\\");
+    }
+
+    #[test]
+    fn encode_partial_band_and_two_colors() {
+        // Row 0 white, rows 1..3 black; 3 rows → one band with bits 0..2.
+        let c = canvas(2, 3, |_, y| {
+            if y == 0 {
+                [255, 255, 255, 255]
+            } else {
+                [0, 0, 0, 255]
+            }
+        });
+        let s = encode(&c);
+        assert!(s.starts_with("\x1bPq\"1;1;2;3"));
+        // Black is more frequent → register 0.
+        assert!(s.contains("#0;2;0;0;0#1;2;100;100;100"));
+        // Passes follow first appearance in the band: white (bit 0 → '@'),
+        // then black (bits 1|2 = 6 → 'E').
+        assert!(s.contains("#1@@$#0EE-"), "{s:?}");
+    }
+
+    #[test]
+    fn encode_bands_are_separated() {
+        let c = canvas(1, 7, |_, _| [0, 0, 0, 255]);
+        assert!(encode(&c).ends_with("#0~-#0@-\x1b\\"));
+    }
+
+    #[test]
+    fn runs_drop_trailing_empty_columns() {
+        let mut s = String::new();
+        push_runs(&mut s, &[1, 1, 1, 1, 1, 0, 2, 0, 0]);
+        assert_eq!(s, "!5@?A");
+    }
+
+    #[test]
+    fn quantize_caps_palette_and_maps_nearest() {
+        // 512 distinct buckets, more than there are registers.
+        let mut data = Vec::new();
+        for r in 0..32u8 {
+            for g in 0..16u8 {
+                data.extend_from_slice(&[r << 3, g << 4, 0, 255]);
+            }
+        }
+        let (palette, map) = quantize(&data);
+        assert_eq!(palette.len(), MAX_COLORS);
+        for px in data.chunks_exact(4) {
+            assert!((map[bucket(px)] as usize) < palette.len());
+        }
+    }
+}
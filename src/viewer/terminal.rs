@@ -8,12 +8,17 @@ use crossterm::{
     terminal,
 };
 use log::{debug, warn};
+use std::fmt;
 use std::io::{self, Write, stdout};
 use std::os::unix::io::AsRawFd;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::time::{Duration, Instant};
 
 use super::display_state::{DisplayState, PlacementSlot};
+use super::graphics::Graphics;
 use super::layout::{Layout, ScrollState};
+use super::placeholder;
+use super::transfer;
 use crate::frame::VisibleTiles;
 
 // ---------------------------------------------------------------------------
@@ -45,7 +50,7 @@ impl RawGuard {
         }
         self.cleaned = true;
         let mut out = stdout();
-        let _ = write!(out, "\x1b_Ga=d,d=A,q=2\x1b\\");
+        let _ = kitty(&mut out, format_args!("a=d,d=A,q=2"));
         let _ = out.execute(cursor::Show);
         if self.mouse {
             let _ = out.execute(DisableMouseCapture);
@@ -65,42 +70,47 @@ impl Drop for RawGuard {
 // Kitty protocol helpers
 // ---------------------------------------------------------------------------
 
-/// Send PNG data via temp file (a=t: transfer only, no display).
+/// Whether Kitty commands are wrapped for tmux; see [`set_tmux_passthrough`].
+static TMUX_PASSTHROUGH: AtomicBool = AtomicBool::new(false);
+
+/// Wrap every Kitty graphics command in tmux's DCS passthrough
+/// (`ESC P tmux; ... ESC \`, honoured with `set -g allow-passthrough on`)
+/// so it reaches the outer terminal instead of being dropped by tmux.
+/// Set once at startup, before anything is drawn.
+pub(super) fn set_tmux_passthrough(on: bool) {
+    TMUX_PASSTHROUGH.store(on, Ordering::Relaxed);
+}
+
+/// Write the Kitty graphics command `ESC _ G <body> ESC \`.
+pub(super) fn kitty(out: &mut impl Write, body: fmt::Arguments) -> io::Result<()> {
+    write_kitty(out, body, TMUX_PASSTHROUGH.load(Ordering::Relaxed))
+}
+
+/// Inside tmux passthrough every ESC of the wrapped sequence is doubled;
+/// `body` itself never contains one.
+fn write_kitty(out: &mut impl Write, body: fmt::Arguments, passthrough: bool) -> io::Result<()> {
+    if passthrough {
+        write!(out, "\x1bPtmux;\x1b\x1b_G{body}\x1b\x1b\\\x1b\\")
+    } else {
+        write!(out, "\x1b_G{body}\x1b\\")
+    }
+}
+
+/// Send PNG data (a=t: transfer only, no display).
 ///
-/// Writes `png_data` to a temp file, then sends a single Kitty escape
-/// sequence with `t=t` (temp file transfer). Kitty reads and deletes
-/// the file, avoiding base64-encoding the full payload through the pty.
+/// The data goes through the medium chosen at startup (see
+/// [`super::transfer`]): a temp file or shared memory object the terminal
+/// reads and deletes, or base64 chunks through the pty.
 pub(super) fn send_image(png_data: &[u8], image_id: u32) -> io::Result<()> {
     let start = Instant::now();
-
-    let mut tmp = tempfile::Builder::new()
-        .prefix("tty-graphics-protocol.")
-        .tempfile()?;
-    tmp.write_all(png_data)?;
-    tmp.flush()?;
-
-    // Get path, then close handle and disarm auto-delete.
-    // Kitty deletes the file after reading (t=t).
-    let path = tmp
-        .path()
-        .to_str()
-        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "temp path not UTF-8"))?
-        .to_string();
-    let _ = tmp.into_temp_path().keep();
-
-    let encoded_path = BASE64.encode(path.as_bytes());
     let mut out = stdout();
-    write!(
-        out,
-        "\x1b_Ga=t,f=100,i={image_id},t=t,q=2;{encoded_path}\x1b\\"
-    )?;
+    transfer::transmit(&mut out, &format!("a=t,f=100,i={image_id}"), png_data)?;
     out.flush()?;
 
     debug!(
-        "kgp: send_image id={image_id} ({} bytes) tmpfile {:.1}ms [{}]",
+        "kgp: send_image id={image_id} ({} bytes) {:.1}ms",
         png_data.len(),
         start.elapsed().as_secs_f64() * 1000.0,
-        path,
     );
     Ok(())
 }
@@ -116,17 +126,48 @@ pub(super) fn send_raw_image(
 ) -> io::Result<()> {
     let encoded = BASE64.encode(rgba);
     let mut out = stdout();
-    write!(
-        out,
-        "\x1b_Ga=t,f=32,s={width},v={height},i={image_id},t=d,q=2;{encoded}\x1b\\"
+    kitty(
+        &mut out,
+        format_args!("a=t,f=32,s={width},v={height},i={image_id},t=d,q=2;{encoded}"),
     )?;
     out.flush()
 }
 
+/// Place a transmitted image at the cursor at its natural size without
+/// moving the cursor (`C=1`), then emit `rows` newlines (the cell rows the
+/// image covers) so text continues below it and the image scrolls with the
+/// scrollback.
+pub(super) fn place_inline(image_id: u32, rows: u16) -> io::Result<()> {
+    let mut out = stdout();
+    out.write_all(b"\r")?;
+    kitty(&mut out, format_args!("a=p,i={image_id},C=1,q=2"))?;
+    for _ in 0..rows {
+        out.write_all(b"\n")?;
+    }
+    out.flush()
+}
+
+/// Like [`place_inline`], but through a virtual placement of `cols`×`rows`
+/// cells and one line of Unicode placeholders per row, which tmux keeps in
+/// its scrollback like any other text.
+pub(super) fn place_inline_placeholders(image_id: u32, cols: u16, rows: u16) -> io::Result<()> {
+    let mut out = stdout();
+    kitty(
+        &mut out,
+        format_args!("a=p,U=1,i={image_id},c={cols},r={rows},q=2"),
+    )?;
+    for row in 0..rows {
+        out.write_all(b"\r")?;
+        placeholder::write_row(&mut out, image_id, row, 0, cols)?;
+        out.write_all(b"\n")?;
+    }
+    out.flush()
+}
+
 /// Delete image data and placements
 pub(super) fn delete_image(image_id: u32) -> io::Result<()> {
     let mut out = stdout();
-    write!(out, "\x1b_Ga=d,d=I,i={image_id},q=2\x1b\\")?;
+    kitty(&mut out, format_args!("a=d,d=I,i={image_id},q=2"))?;
     out.flush()
 }
 
@@ -145,7 +186,7 @@ pub(super) fn delete_image(image_id: u32) -> io::Result<()> {
 ///   fresh `DisplayState` for the next iteration.
 pub(super) fn delete_all_images() -> io::Result<()> {
     let mut out = stdout();
-    write!(out, "\x1b_Ga=d,d=A,q=2\x1b\\")?;
+    kitty(&mut out, format_args!("a=d,d=A,q=2"))?;
     out.flush()
 }
 
@@ -154,7 +195,7 @@ pub(super) fn delete_images_by_ids(ids: &[u32]) -> io::Result<()> {
     debug!("kgp: delete_images_by_ids ({} images)", ids.len());
     let mut out = stdout();
     for &id in ids {
-        write!(out, "\x1b_Ga=d,d=I,i={id},q=2\x1b\\")?;
+        kitty(&mut out, format_args!("a=d,d=I,i={id},q=2"))?;
     }
     out.flush()
 }
@@ -166,6 +207,73 @@ pub(super) fn clear_screen() -> io::Result<()> {
     out.flush()
 }
 
+/// Remove every image from the screen. Kitty images are deleted by id
+/// space; Sixel and iTerm2 pixels live in the text cells and go with a
+/// screen clear, as does the text-mode document. Unicode placeholders need
+/// both.
+pub(super) fn clear_images(graphics: Graphics) -> io::Result<()> {
+    match graphics {
+        Graphics::Kitty => delete_all_images(),
+        Graphics::KittyPlaceholder => delete_all_images().and_then(|()| clear_screen()),
+        Graphics::Sixel | Graphics::Iterm2 | Graphics::Text => clear_screen(),
+    }
+}
+
+/// OSC 1337 inline image of `png`, shown at exactly `width`×`height` pixels
+/// without moving the cursor.
+pub(super) fn iterm2_image(png: &[u8], width: u32, height: u32) -> String {
+    format!(
+        "\x1b]1337;File=inline=1;size={};width={width}px;height={height}px;\
+         preserveAspectRatio=0;doNotMoveCursor=1:{}\x07",
+        png.len(),
+        BASE64.encode(png)
+    )
+}
+
+/// Paint a composited frame (a Sixel or OSC 1337 image sequence) at the
+/// top-left of the screen.
+///
+/// Image rows below `height_px` are cleared first, so a shorter frame (end
+/// of document, smaller reload) leaves nothing of the previous one behind.
+pub(super) fn draw_frame(image: &str, height_px: u32, layout: &Layout) -> io::Result<()> {
+    let mut out = stdout();
+    let covered = height_px.div_ceil(layout.cell_h as u32) as u16;
+    for row in covered..layout.image_rows {
+        out.queue(cursor::MoveTo(0, row))?;
+        out.queue(terminal::Clear(terminal::ClearType::CurrentLine))?;
+    }
+    out.queue(cursor::MoveTo(0, 0))?;
+    out.write_all(image.as_bytes())?;
+    out.flush()
+}
+
+/// Print Unicode placeholder cells in columns `start_col..start_col + cols`
+/// of the image area. `slices` are `(image_id, first_image_row, rows)`,
+/// stacked from the top; the columns of rows below them are blanked.
+pub(super) fn draw_placeholders(
+    slices: &[(u32, u16, u16)],
+    start_col: u16,
+    cols: u16,
+    layout: &Layout,
+) -> io::Result<()> {
+    let mut out = stdout();
+    let mut screen_row = 0;
+    for &(image_id, first, rows) in slices {
+        let rows = rows.min(layout.image_rows.saturating_sub(screen_row));
+        for r in 0..rows {
+            out.queue(cursor::MoveTo(start_col, screen_row + r))?;
+            placeholder::write_row(&mut out, image_id, first + r, 0, cols)?;
+        }
+        screen_row += rows;
+    }
+    let blank = " ".repeat(cols as usize);
+    for row in screen_row..layout.image_rows {
+        out.queue(cursor::MoveTo(start_col, row))?;
+        out.write_all(blank.as_bytes())?;
+    }
+    out.flush()
+}
+
 /// Parameters for placing tile images via Kitty Graphics Protocol.
 pub(super) struct PlaceParams {
     pub start_col: u16,
@@ -214,9 +322,11 @@ pub(super) fn place_tiles(
             let slot = make_slot(*idx);
             let pid = loaded.track_placement(&mut out, slot, id)?;
             out.queue(cursor::MoveTo(params.start_col, 0))?;
-            write!(
-                out,
-                "\x1b_Ga=p,i={id},p={pid},x=0,y={src_y},w={w},h={src_h},c={cols},r={rows},C=1,q=2\x1b\\",
+            kitty(
+                &mut out,
+                format_args!(
+                    "a=p,i={id},p={pid},x=0,y={src_y},w={w},h={src_h},c={cols},r={rows},C=1,q=2",
+                ),
             )?;
         }
         VisibleTiles::Split {
@@ -243,16 +353,20 @@ pub(super) fn place_tiles(
             let top_slot = make_slot(*top_idx);
             let top_pid = loaded.track_placement(&mut out, top_slot, top_id)?;
             out.queue(cursor::MoveTo(params.start_col, 0))?;
-            write!(
-                out,
-                "\x1b_Ga=p,i={top_id},p={top_pid},x=0,y={top_src_y},w={w},h={top_src_h},c={cols},r={top_rows},C=1,q=2\x1b\\",
+            kitty(
+                &mut out,
+                format_args!(
+                    "a=p,i={top_id},p={top_pid},x=0,y={top_src_y},w={w},h={top_src_h},c={cols},r={top_rows},C=1,q=2",
+                ),
             )?;
             let bot_slot = make_slot(*bot_idx);
             let bot_pid = loaded.track_placement(&mut out, bot_slot, bot_id)?;
             out.queue(cursor::MoveTo(params.start_col, top_rows))?;
-            write!(
-                out,
-                "\x1b_Ga=p,i={bot_id},p={bot_pid},x=0,y=0,w={w},h={bot_src_h},c={cols},r={bot_rows},C=1,q=2\x1b\\",
+            kitty(
+                &mut out,
+                format_args!(
+                    "a=p,i={bot_id},p={bot_pid},x=0,y=0,w={w},h={bot_src_h},c={cols},r={bot_rows},C=1,q=2",
+                ),
             )?;
         }
     }
@@ -579,12 +693,14 @@ fn place_rects_in_region(
         let primary_slot = PlacementSlot::OverlayPrimary(tile_idx, rect_idx);
         let primary_pid = loaded.track_placement(out, primary_slot, full_id)?;
         out.queue(cursor::MoveTo(e.col, e.row))?;
-        write!(
+        kitty(
             out,
-            "\x1b_Ga=p,i={full_id},p={primary_pid},w={src_w},h={src_h},X={x_off},Y={y_off},c={cols},r=1,C=1,z=1,q=2\x1b\\",
-            x_off = e.x_off,
-            y_off = e.y_off,
-            cols = e.cols,
+            format_args!(
+                "a=p,i={full_id},p={primary_pid},w={src_w},h={src_h},X={x_off},Y={y_off},c={cols},r=1,C=1,z=1,q=2",
+                x_off = e.x_off,
+                y_off = e.y_off,
+                cols = e.cols,
+            ),
         )?;
 
         // 2nd placement: overflow into next row (if any)
@@ -605,11 +721,13 @@ fn place_rects_in_region(
             let overflow_slot = PlacementSlot::OverlayOverflow(tile_idx, rect_idx);
             let overflow_pid = loaded.track_placement(out, overflow_slot, ov_id)?;
             out.queue(cursor::MoveTo(e.col, next_row))?;
-            write!(
+            kitty(
                 out,
-                "\x1b_Ga=p,i={ov_id},p={overflow_pid},w={ov_w},h={ov_h},X={x_off},c={cols},r=1,C=1,z=1,q=2\x1b\\",
-                x_off = e.x_off,
-                cols = e.cols,
+                format_args!(
+                    "a=p,i={ov_id},p={overflow_pid},w={ov_w},h={ov_h},X={x_off},c={cols},r=1,C=1,z=1,q=2",
+                    x_off = e.x_off,
+                    cols = e.cols,
+                ),
             )?;
         }
     }
@@ -721,6 +839,50 @@ pub(super) fn draw_loading_screen(
     out.flush()
 }
 
+/// Draw the compile error screen: `lines` from the top (the first one in
+/// red), then the status bar. Lines that do not fit are counted instead.
+pub(super) fn draw_error_screen(
+    layout: &Layout,
+    filename: &str,
+    lines: &[String],
+    live: bool,
+) -> io::Result<()> {
+    let mut out = stdout();
+    out.queue(terminal::Clear(terminal::ClearType::All))?;
+    let rows = layout.status_row as usize;
+    let (shown, hidden) = if lines.len() > rows {
+        (rows.saturating_sub(1), lines.len() - rows.saturating_sub(1))
+    } else {
+        (lines.len(), 0)
+    };
+    for (row, line) in lines.iter().take(shown).enumerate() {
+        out.queue(cursor::MoveTo(0, row as u16))?;
+        if row == 0 {
+            write!(out, "{}", line.as_str().red().bold())?;
+        } else {
+            write!(out, "{line}")?;
+        }
+    }
+    if hidden > 0 {
+        out.queue(cursor::MoveTo(0, shown as u16))?;
+        write!(
+            out,
+            "{}",
+            format!("\u{2026} {hidden} more line(s)").dark_grey()
+        )?;
+    }
+
+    out.queue(cursor::MoveTo(0, layout.status_row))?;
+    let total_cols = (layout.sidebar_cols + layout.image_cols) as usize;
+    let hint = if live { " (reloads on change)" } else { "" };
+    let msg = format!(" {filename} | Compile error{hint}  q:quit");
+    let padded = format!("{:<width$}", msg, width = total_cols);
+    let truncated: String = padded.chars().take(total_cols).collect();
+    write!(out, "{}", truncated.on_dark_red().white())?;
+    out.queue(style::ResetColor)?;
+    out.flush()
+}
+
 /// Draw command input bar on the status row (`:input_` prompt).
 pub(super) fn draw_command_bar(layout: &Layout, input: &str) -> io::Result<()> {
     let mut out = stdout();
@@ -890,7 +1052,8 @@ pub(super) fn check_tty() -> anyhow::Result<()> {
         anyhow::bail!(
             "mlux viewer requires an interactive terminal.\n\
              \n\
-             Supported terminals: Kitty, Ghostty, WezTerm\n\
+             Supported terminals: Kitty, Ghostty, WezTerm (Kitty graphics);\n\
+             foot, xterm, mlterm (Sixel); iTerm2 (see --graphics)\n\
              To render to a file, use: mlux render <input.md> -o output.png"
         );
     }
@@ -901,6 +1064,26 @@ pub(super) fn check_tty() -> anyhow::Result<()> {
 mod tests {
     use super::*;
 
+    #[test]
+    fn kitty_commands_wrap_for_tmux_passthrough() {
+        let mut plain = Vec::new();
+        write_kitty(&mut plain, format_args!("a=d,d=A,q=2"), false).unwrap();
+        assert_eq!(plain, b"\x1b_Ga=d,d=A,q=2\x1b\\");
+        let mut wrapped = Vec::new();
+        write_kitty(&mut wrapped, format_args!("a=d,d=A,q=2"), true).unwrap();
+        assert_eq!(wrapped, b"\x1bPtmux;\x1b\x1b_Ga=d,d=A,q=2\x1b\x1b\\\x1b\\");
+    }
+
+    #[test]
+    fn iterm2_image_sequence() {
+        let seq = iterm2_image(b"PNG", 30, 20);
+        assert_eq!(
+            seq,
+            "\x1b]1337;File=inline=1;size=3;width=30px;height=20px;\
+             preserveAspectRatio=0;doNotMoveCursor=1:UE5H\x07"
+        );
+    }
+
     #[test]
     fn parse_osc11_4digit() {
         // xterm-style 4-digit channels
@@ -7,10 +7,7 @@ use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
 
 use super::display_state::DisplayState;
 use super::effect::{Effect, RenderOp, ViewerMode};
-use super::keymap::{
-    InputAccumulator, map_command_key, map_grep_key, map_key_event, map_log_key, map_toc_key,
-    map_url_key,
-};
+use super::keymap::{InputAccumulator, Keymap, map_command_key};
 use super::layout::{self, Layout, ScrollState};
 use super::query::DocumentQuery;
 use super::viewport::{ViewContext, Viewport};
@@ -29,6 +26,7 @@ pub(super) struct TestHarness {
     doc: TiledDocument,
     markdown: String,
     acc: InputAccumulator,
+    keymap: Keymap,
     filename: String,
     render_ops: Vec<RenderOp>,
     scroll_step: u32,
@@ -96,6 +94,7 @@ impl TestHarness {
             doc,
             markdown: md.to_string(),
             acc: InputAccumulator::new(),
+            keymap: Keymap::default(),
             filename: "test.md".to_string(),
             render_ops: Vec::new(),
             scroll_step,
@@ -118,7 +117,7 @@ impl TestHarness {
             && self.viewport.flash.take().is_some();
 
         let mut effects = match &mut self.viewport.mode {
-            ViewerMode::Normal => match map_key_event(key, &mut self.acc) {
+            ViewerMode::Normal => match self.keymap.map_key_event(key, &mut self.acc) {
                 Some(action) => {
                     let mut ctx = super::mode_normal::NormalCtx {
                         scroll: &self.viewport.scroll,
@@ -135,14 +134,14 @@ impl TestHarness {
                 }
                 None => vec![],
             },
-            ViewerMode::Grep(gs) => match map_grep_key(key) {
+            ViewerMode::Grep(gs) => match self.keymap.map_grep_key(key) {
                 Some(a) => {
                     let visible_count = (self.layout.status_row - 1) as usize;
                     super::mode_grep::handle(a, gs, &doc, visible_count, max_y)
                 }
                 None => vec![],
             },
-            ViewerMode::InlineSearch(is) => match super::keymap::map_inline_search_key(key) {
+            ViewerMode::InlineSearch(is) => match self.keymap.map_inline_search_key(key) {
                 Some(a) => super::mode_inline_search::handle(a, is, &doc, max_y),
                 None => vec![],
             },
@@ -150,21 +149,21 @@ impl TestHarness {
                 Some(a) => super::mode_command::handle(a, cs),
                 None => vec![],
             },
-            ViewerMode::Toc(ts) => match map_toc_key(key) {
+            ViewerMode::Toc(ts) => match self.keymap.map_toc_key(key) {
                 Some(a) => {
                     let visible_count = (self.layout.status_row - 1) as usize;
                     super::mode_toc::handle(a, ts, doc.visual_lines, visible_count, max_y)
                 }
                 None => vec![],
             },
-            ViewerMode::UrlPicker(up) => match map_url_key(key) {
+            ViewerMode::UrlPicker(up) => match self.keymap.map_url_key(key) {
                 Some(a) => {
                     let visible_count = (self.layout.status_row - 1) as usize;
-                    super::mode_url::handle(a, up, visible_count, None)
+                    super::mode_url::handle(a, up, visible_count, None, &doc, max_y)
                 }
                 None => vec![],
             },
-            ViewerMode::Log(ls) => match map_log_key(key, ls.search_mode) {
+            ViewerMode::Log(ls) => match self.keymap.map_log_key(key, ls.search_mode) {
                 Some(a) => {
                     let visible_count = (self.layout.status_row - 1) as usize;
                     let total_cols = (self.layout.sidebar_cols + self.layout.image_cols) as usize;
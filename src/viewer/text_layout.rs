@@ -0,0 +1,973 @@
+//! Markdown reflowed into styled terminal rows (text mode).
+//!
+//! The layout is a pure function of the Markdown and the terminal width.
+//! Every row that shows document text also gets a [`VisualLine`] whose
+//! `y_px` is the row index and whose block range is the top-level block it
+//! came from, exactly like the visual lines extracted from a rendered frame.
+//! The viewer modes that work on visual lines — search, TOC, URL picker,
+//! yank — therefore run unchanged on a text document via `DocumentQuery`.
+
+use std::ops::Range;
+
+use pulldown_cmark::{Alignment, BlockQuoteKind, CodeBlockKind, Event, Parser, Tag, TagEnd};
+use unicode_width::UnicodeWidthChar;
+
+use crate::compile::parser_options;
+use crate::frame::VisualLine;
+
+/// List markers by nesting depth, as in the themes' `list(marker: ...)`.
+const BULLETS: [&str; 3] = ["• ", "‣ ", "– "];
+
+/// What a cell shows; the viewer maps roles to theme colours.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(super) enum Role {
+    Text,
+    Heading(u8),
+    Emph,
+    Code,
+    Link,
+    /// Block quote text and other secondary text.
+    Quote,
+    /// Box drawing, rules and the block quote bar.
+    Border,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(super) struct Style {
+    pub role: Role,
+    pub bold: bool,
+    pub italic: bool,
+    pub strike: bool,
+}
+
+impl Style {
+    const fn plain(role: Role) -> Self {
+        Self {
+            role,
+            bold: false,
+            italic: false,
+            strike: false,
+        }
+    }
+}
+
+/// One character on screen, with the Markdown byte offset it was read from
+/// (`None` for decoration such as list markers and borders).
+#[derive(Clone, Copy, Debug)]
+pub(super) struct Cell {
+    pub ch: char,
+    pub style: Style,
+    pub src: Option<usize>,
+}
+
+impl Cell {
+    fn deco(ch: char, role: Role) -> Self {
+        Self {
+            ch,
+            style: Style::plain(role),
+            src: None,
+        }
+    }
+
+    fn width(&self) -> usize {
+        self.ch.width().unwrap_or(0)
+    }
+}
+
+pub(super) struct Row {
+    pub cells: Vec<Cell>,
+    /// Index into `TextDocument::visual_lines` for rows that show text.
+    pub line: Option<usize>,
+}
+
+pub(super) struct TextDocument {
+    pub rows: Vec<Row>,
+    pub visual_lines: Vec<VisualLine>,
+}
+
+/// Lay out `markdown` for a text area `width` columns wide.
+pub(super) fn layout(markdown: &str, width: usize) -> TextDocument {
+    let mut b = Builder::new(markdown, width.max(1));
+    for (event, range) in Parser::new_ext(markdown, parser_options()).into_offset_iter() {
+        b.event(event, range);
+    }
+    b.flush_inline();
+    TextDocument {
+        rows: b.rows,
+        visual_lines: b.visual_lines,
+    }
+}
+
+/// Markers in front of the rows of a container (quote bar, list marker).
+/// `first` goes on the first non-blank row, `rest` on all others.
+struct Prefix {
+    first: Vec<Cell>,
+    rest: Vec<Cell>,
+    used: bool,
+}
+
+impl Prefix {
+    fn new(first: Vec<Cell>, rest: Vec<Cell>) -> Self {
+        Self {
+            first,
+            rest,
+            used: false,
+        }
+    }
+
+    fn same(cells: Vec<Cell>) -> Self {
+        Self::new(cells.clone(), cells)
+    }
+}
+
+struct Table {
+    aligns: Vec<Alignment>,
+    rows: Vec<Vec<Vec<Cell>>>,
+    head_rows: usize,
+}
+
+#[derive(Default)]
+struct Spans {
+    bold: usize,
+    italic: usize,
+    strike: usize,
+    link: usize,
+    quote: usize,
+    heading: Option<u8>,
+    /// Inline position and target of each open image.
+    images: Vec<(usize, String)>,
+}
+
+struct Builder<'a> {
+    markdown: &'a str,
+    width: usize,
+    rows: Vec<Row>,
+    visual_lines: Vec<VisualLine>,
+    last_blank: bool,
+    prefixes: Vec<Prefix>,
+    inline: Vec<Cell>,
+    spans: Spans,
+    /// Source range of the enclosing top-level block.
+    block: Option<Range<usize>>,
+    depth: usize,
+    /// Next number of each open list (`None` = bullet list).
+    lists: Vec<Option<u64>>,
+    code: Option<(String, Vec<Cell>)>,
+    table: Option<Table>,
+    in_metadata: bool,
+    footnotes: Vec<String>,
+}
+
+impl<'a> Builder<'a> {
+    fn new(markdown: &'a str, width: usize) -> Self {
+        Self {
+            markdown,
+            width,
+            rows: Vec::new(),
+            visual_lines: Vec::new(),
+            last_blank: true,
+            prefixes: Vec::new(),
+            inline: Vec::new(),
+            spans: Spans::default(),
+            block: None,
+            depth: 0,
+            lists: Vec::new(),
+            code: None,
+            table: None,
+            in_metadata: false,
+            footnotes: Vec::new(),
+        }
+    }
+
+    fn event(&mut self, event: Event, range: Range<usize>) {
+        match event {
+            Event::Start(tag) => {
+                if self.depth == 0 {
+                    self.block = Some(range.clone());
+                }
+                self.depth += 1;
+                self.start(tag);
+            }
+            Event::End(tag) => {
+                self.end(tag);
+                self.depth = self.depth.saturating_sub(1);
+            }
+            Event::Text(_) if self.in_metadata => {}
+            Event::Text(t) => match &mut self.code {
+                Some((_, cells)) => {
+                    let style = Style::plain(Role::Code);
+                    for (ch, src) in t.chars().zip(source_offsets(self.markdown, &range, &t)) {
+                        cells.push(Cell { ch, style, src });
+                    }
+                }
+                None => self.push_text(&t, &range, None),
+            },
+            Event::Code(t) | Event::InlineMath(t) => self.push_text(&t, &range, Some(Role::Code)),
+            Event::DisplayMath(t) => {
+                self.flush_inline();
+                self.push_text(t.trim(), &range, Some(Role::Code));
+                self.flush_inline();
+            }
+            Event::FootnoteReference(label) => {
+                let n = self.footnote_number(&label);
+                for ch in format!("[{n}]").chars() {
+                    self.inline.push(Cell {
+                        ch,
+                        style: Style::plain(Role::Link),
+                        src: Some(range.start),
+                    });
+                }
+            }
+            Event::SoftBreak => self.inline.push(Cell::deco(' ', Role::Text)),
+            Event::HardBreak => self.inline.push(Cell::deco('\n', Role::Text)),
+            Event::TaskListMarker(done) => {
+                let mark = if done { "☑ " } else { "☐ " };
+                self.inline
+                    .extend(mark.chars().map(|ch| Cell::deco(ch, Role::Text)));
+            }
+            Event::Rule => {
+                if self.depth == 0 {
+                    self.block = Some(range);
+                }
+                self.flush_inline();
+                self.gap();
+                let rule = vec![Cell::deco('─', Role::Border); self.avail()];
+                self.emit(rule, false);
+                self.gap();
+            }
+            Event::Html(_) | Event::InlineHtml(_) => {}
+        }
+    }
+
+    fn start(&mut self, tag: Tag) {
+        match tag {
+            Tag::Paragraph => self.gap(),
+            Tag::Heading { level, .. } => {
+                self.gap();
+                self.spans.heading = Some(level as u8);
+            }
+            Tag::BlockQuote(kind) => {
+                self.flush_inline();
+                self.gap();
+                self.prefixes
+                    .push(Prefix::same(deco_cells("▌ ", Role::Link)));
+                self.spans.quote += 1;
+                if let Some(kind) = kind {
+                    let mut title = deco_cells(alert_title(kind), Role::Link);
+                    title.iter_mut().for_each(|c| c.style.bold = true);
+                    self.emit(title, true);
+                }
+            }
+            Tag::CodeBlock(kind) => {
+                self.flush_inline();
+                self.gap();
+                let lang = match kind {
+                    CodeBlockKind::Fenced(info) => {
+                        info.split_whitespace().next().unwrap_or("").to_string()
+                    }
+                    CodeBlockKind::Indented => String::new(),
+                };
+                self.code = Some((lang, Vec::new()));
+            }
+            Tag::List(start) => {
+                self.flush_inline();
+                if self.lists.is_empty() {
+                    self.gap();
+                }
+                self.lists.push(start);
+            }
+            Tag::Item => {
+                self.flush_inline();
+                let depth = self.lists.len().saturating_sub(1);
+                let marker = match self.lists.last_mut() {
+                    Some(Some(n)) => {
+                        *n += 1;
+                        format!("{}. ", *n - 1)
+                    }
+                    _ => BULLETS[depth % BULLETS.len()].to_string(),
+                };
+                let indent = marker.chars().count();
+                self.prefixes.push(Prefix::new(
+                    deco_cells(&marker, Role::Text),
+                    deco_cells(&" ".repeat(indent), Role::Text),
+                ));
+            }
+            Tag::FootnoteDefinition(label) => {
+                self.flush_inline();
+                self.gap();
+                let marker = format!("[{}] ", self.footnote_number(&label));
+                let indent = marker.chars().count();
+                self.prefixes.push(Prefix::new(
+                    deco_cells(&marker, Role::Link),
+                    deco_cells(&" ".repeat(indent), Role::Text),
+                ));
+                self.spans.quote += 1;
+            }
+            Tag::Table(aligns) => {
+                self.flush_inline();
+                self.gap();
+                self.table = Some(Table {
+                    aligns,
+                    rows: Vec::new(),
+                    head_rows: 0,
+                });
+            }
+            Tag::TableHead | Tag::TableRow => {
+                if let Some(t) = &mut self.table {
+                    t.rows.push(Vec::new());
+                }
+            }
+            Tag::TableCell => self.inline.clear(),
+            Tag::Emphasis => self.spans.italic += 1,
+            Tag::Strong => self.spans.bold += 1,
+            Tag::Strikethrough => self.spans.strike += 1,
+            Tag::Link { .. } => self.spans.link += 1,
+            Tag::Image { dest_url, .. } => {
+                self.inline.extend(deco_cells("[image: ", Role::Quote));
+                self.spans
+                    .images
+                    .push((self.inline.len(), dest_url.to_string()));
+            }
+            Tag::MetadataBlock(_) => self.in_metadata = true,
+            Tag::HtmlBlock
+            | Tag::DefinitionList
+            | Tag::DefinitionListTitle
+            | Tag::DefinitionListDefinition => {}
+        }
+    }
+
+    fn end(&mut self, tag: TagEnd) {
+        match tag {
+            TagEnd::Paragraph => {
+                self.flush_inline();
+            }
+            TagEnd::Heading(level) => {
+                let w = self.flush_inline();
+                if w > 0 && level as u8 <= 2 {
+                    let ch = if level as u8 == 1 { '━' } else { '─' };
+                    self.emit(vec![Cell::deco(ch, Role::Heading(level as u8)); w], false);
+                }
+                self.spans.heading = None;
+            }
+            TagEnd::BlockQuote(_) => {
+                self.flush_inline();
+                self.prefixes.pop();
+                self.spans.quote -= 1;
+            }
+            TagEnd::CodeBlock => {
+                if let Some((lang, cells)) = self.code.take() {
+                    self.code_box(&lang, cells);
+                }
+            }
+            TagEnd::List(_) => {
+                self.flush_inline();
+                self.lists.pop();
+            }
+            TagEnd::Item => {
+                self.flush_inline();
+                self.prefixes.pop();
+            }
+            TagEnd::FootnoteDefinition => {
+                self.flush_inline();
+                self.prefixes.pop();
+                self.spans.quote -= 1;
+            }
+            TagEnd::Table => {
+                if let Some(t) = self.table.take() {
+                    self.table_box(t);
+                }
+            }
+            TagEnd::TableHead => {
+                if let Some(t) = &mut self.table {
+                    t.head_rows = t.rows.len();
+                }
+            }
+            TagEnd::TableCell => {
+                let mut cells = std::mem::take(&mut self.inline);
+                if let Some(t) = &mut self.table {
+                    if t.head_rows == 0 && t.rows.len() == 1 {
+                        cells.iter_mut().for_each(|c| c.style.bold = true);
+                    }
+                    if let Some(row) = t.rows.last_mut() {
+                        row.push(cells);
+                    }
+                }
+            }
+            TagEnd::Emphasis => self.spans.italic -= 1,
+            TagEnd::Strong => self.spans.bold -= 1,
+            TagEnd::Strikethrough => self.spans.strike -= 1,
+            TagEnd::Link => self.spans.link -= 1,
+            TagEnd::Image => {
+                if let Some((start, url)) = self.spans.images.pop() {
+                    if self.inline.len() == start {
+                        self.inline.extend(deco_cells(&url, Role::Quote));
+                    }
+                    self.inline.push(Cell::deco(']', Role::Quote));
+                }
+            }
+            TagEnd::MetadataBlock(_) => {
+                self.in_metadata = false;
+                self.front_matter();
+            }
+            TagEnd::TableRow
+            | TagEnd::HtmlBlock
+            | TagEnd::DefinitionList
+            | TagEnd::DefinitionListTitle
+            | TagEnd::DefinitionListDefinition => {}
+        }
+    }
+
+    fn style(&self) -> Style {
+        let s = &self.spans;
+        let role = if s.link > 0 {
+            Role::Link
+        } else if !s.images.is_empty() {
+            Role::Quote
+        } else if let Some(level) = s.heading {
+            Role::Heading(level)
+        } else if s.italic > 0 {
+            Role::Emph
+        } else if s.quote > 0 {
+            Role::Quote
+        } else {
+            Role::Text
+        };
+        Style {
+            role,
+            bold: s.bold > 0 || s.heading.is_some(),
+            italic: s.italic > 0,
+            strike: s.strike > 0,
+        }
+    }
+
+    fn push_text(&mut self, text: &str, range: &Range<usize>, role: Option<Role>) {
+        let mut style = self.style();
+        if let Some(role) = role {
+            style.role = role;
+        }
+        let offsets = source_offsets(self.markdown, range, text);
+        for (ch, src) in text.chars().zip(offsets) {
+            self.inline.push(Cell { ch, style, src });
+        }
+    }
+
+    fn footnote_number(&mut self, label: &str) -> usize {
+        match self.footnotes.iter().position(|l| l == label) {
+            Some(i) => i + 1,
+            None => {
+                self.footnotes.push(label.to_string());
+                self.footnotes.len()
+            }
+        }
+    }
+
+    /// Prefix cells for the next row; a non-blank row uses up first markers.
+    fn prefix(&mut self, consume: bool) -> Vec<Cell> {
+        let mut cells = Vec::new();
+        for p in &mut self.prefixes {
+            if consume && !p.used {
+                p.used = true;
+                cells.extend_from_slice(&p.first);
+            } else {
+                cells.extend_from_slice(&p.rest);
+            }
+        }
+        cells
+    }
+
+    /// Columns left for content after the container prefixes.
+    fn avail(&self) -> usize {
+        let used: usize = self
+            .prefixes
+            .iter()
+            .flat_map(|p| &p.rest)
+            .map(Cell::width)
+            .sum();
+        self.width.saturating_sub(used).max(1)
+    }
+
+    /// Append a row. `text` rows get a visual line.
+    fn emit(&mut self, content: Vec<Cell>, text: bool) {
+        let mut cells = self.prefix(true);
+        let src = content.iter().find_map(|c| c.src);
+        cells.extend(content);
+        let line = text.then(|| {
+            let y = self.rows.len() as u32;
+            self.visual_lines.push(VisualLine {
+                y_pt: y as f64,
+                y_px: y,
+                md_block_range: self.block.clone(),
+                md_offset: src.or(self.block.as_ref().map(|b| b.start)),
+                diff_status: None,
+            });
+            self.visual_lines.len() - 1
+        });
+        self.rows.push(Row { cells, line });
+        self.last_blank = false;
+    }
+
+    /// Separate blocks with one blank row.
+    fn gap(&mut self) {
+        if self.last_blank {
+            return;
+        }
+        let cells = self.prefix(false);
+        self.rows.push(Row { cells, line: None });
+        self.last_blank = true;
+    }
+
+    /// Wrap and emit pending inline text; returns the widest row's width.
+    fn flush_inline(&mut self) -> usize {
+        if self.inline.is_empty() {
+            return 0;
+        }
+        let cells = std::mem::take(&mut self.inline);
+        let mut widest = 0;
+        for line in wrap(&cells, self.avail()) {
+            widest = widest.max(line.iter().map(Cell::width).sum());
+            let text = !line.is_empty();
+            self.emit(line, text);
+        }
+        widest
+    }
+
+    fn code_box(&mut self, lang: &str, cells: Vec<Cell>) {
+        let inner = self.avail().saturating_sub(4).max(1);
+        let border = |ch| Cell::deco(ch, Role::Border);
+        let mut top = vec![border('╭'), border('─')];
+        if !lang.is_empty() {
+            top.push(border(' '));
+            top.extend(deco_cells(lang, Role::Border));
+            top.push(border(' '));
+        }
+        top.resize(inner + 3, border('─'));
+        top.push(border('╮'));
+        self.emit(top, false);
+
+        let mut lines: Vec<&[Cell]> = cells.split(|c| c.ch == '\n').collect();
+        if lines.len() > 1 && lines.last().is_some_and(|l| l.is_empty()) {
+            lines.pop();
+        }
+        let pad = Cell::deco(' ', Role::Code);
+        for line in lines {
+            let expanded: Vec<Cell> = line
+                .iter()
+                .flat_map(|&c| {
+                    let n = if c.ch == '\t' { 4 } else { 1 };
+                    let ch = if c.ch == '\t' { ' ' } else { c.ch };
+                    std::iter::repeat_n(Cell { ch, ..c }, n)
+                })
+                .collect();
+            for part in hard_wrap(&expanded, inner) {
+                let text = part.iter().any(|c| !c.ch.is_whitespace());
+                let used: usize = part.iter().map(Cell::width).sum();
+                let mut row = vec![border('│'), pad];
+                row.extend_from_slice(&part);
+                row.extend(std::iter::repeat_n(pad, inner.saturating_sub(used) + 1));
+                row.push(border('│'));
+                self.emit(row, text);
+            }
+        }
+
+        let mut bottom = vec![border('╰')];
+        bottom.resize(inner + 3, border('─'));
+        bottom.push(border('╯'));
+        self.emit(bottom, false);
+    }
+
+    fn table_box(&mut self, t: Table) {
+        let ncols = t.rows.iter().map(Vec::len).max().unwrap_or(0);
+        if ncols == 0 {
+            return;
+        }
+        let mut widths = vec![1usize; ncols];
+        for row in &t.rows {
+            for (i, cell) in row.iter().enumerate() {
+                widths[i] = widths[i].max(cell.iter().map(Cell::width).sum());
+            }
+        }
+        // Shrink the widest column until the table fits.
+        let budget = self.avail().saturating_sub(3 * ncols + 1);
+        while widths.iter().sum::<usize>() > budget {
+            let (i, &w) = widths
+                .iter()
+                .enumerate()
+                .max_by_key(|&(_, w)| *w)
+                .expect("ncols > 0");
+            if w <= 1 {
+                break;
+            }
+            widths[i] -= 1;
+        }
+
+        let rule = |left, mid, right| {
+            let mut cells = vec![Cell::deco(left, Role::Border)];
+            for (i, &w) in widths.iter().enumerate() {
+                if i > 0 {
+                    cells.push(Cell::deco(mid, Role::Border));
+                }
+                cells.extend(std::iter::repeat_n(Cell::deco('─', Role::Border), w + 2));
+            }
+            cells.push(Cell::deco(right, Role::Border));
+            cells
+        };
+        self.emit(rule('┌', '┬', '┐'), false);
+        for (r, row) in t.rows.iter().enumerate() {
+            if r > 0 && r == t.head_rows {
+                self.emit(rule('├', '┼', '┤'), false);
+            }
+            let wrapped: Vec<Vec<Vec<Cell>>> = (0..ncols)
+                .map(|i| row.get(i).map_or_else(Vec::new, |c| wrap(c, widths[i])))
+                .collect();
+            let height = wrapped.iter().map(Vec::len).max().unwrap_or(0).max(1);
+            for l in 0..height {
+                let mut cells = Vec::new();
+                let mut text = false;
+                for (i, lines) in wrapped.iter().enumerate() {
+                    cells.push(Cell::deco('│', Role::Border));
+                    cells.push(Cell::deco(' ', Role::Text));
+                    let content = lines.get(l).map_or(&[][..], Vec::as_slice);
+                    text |= !content.is_empty();
+                    let space = widths[i].saturating_sub(content.iter().map(Cell::width).sum());
+                    let left = match t.aligns.get(i) {
+                        Some(Alignment::Right) => space,
+                        Some(Alignment::Center) => space / 2,
+                        _ => 0,
+                    };
+                    cells.extend(std::iter::repeat_n(Cell::deco(' ', Role::Text), left));
+                    cells.extend_from_slice(content);
+                    cells.extend(std::iter::repeat_n(
+                        Cell::deco(' ', Role::Text),
+                        space - left + 1,
+                    ));
+                }
+                cells.push(Cell::deco('│', Role::Border));
+                self.emit(cells, text);
+            }
+        }
+        self.emit(rule('└', '┴', '┘'), false);
+    }
+
+    fn front_matter(&mut self) {
+        let Some(fm) = crate::compile::front_matter(self.markdown) else {
+            return;
+        };
+        if let Some(title) = &fm.title {
+            let mut cells = deco_cells(title, Role::Heading(1));
+            cells.iter_mut().for_each(|c| c.style.bold = true);
+            for line in wrap(&cells, self.avail()) {
+                self.emit(line, true);
+            }
+        }
+        let details: Vec<&str> = [&fm.author, &fm.date]
+            .into_iter()
+            .flatten()
+            .map(String::as_str)
+            .collect();
+        if !details.is_empty() {
+            let cells = deco_cells(&details.join(" · "), Role::Quote);
+            for line in wrap(&cells, self.avail()) {
+                self.emit(line, true);
+            }
+        }
+        if !fm.tags.is_empty() {
+            let tags: Vec<String> = fm.tags.iter().map(|t| format!("#{t}")).collect();
+            let cells = deco_cells(&tags.join(" "), Role::Emph);
+            for line in wrap(&cells, self.avail()) {
+                self.emit(line, true);
+            }
+        }
+        self.gap();
+    }
+}
+
+fn deco_cells(s: &str, role: Role) -> Vec<Cell> {
+    s.chars().map(|ch| Cell::deco(ch, role)).collect()
+}
+
+fn alert_title(kind: BlockQuoteKind) -> &'static str {
+    match kind {
+        BlockQuoteKind::Note => "Note",
+        BlockQuoteKind::Tip => "Tip",
+        BlockQuoteKind::Important => "Important",
+        BlockQuoteKind::Warning => "Warning",
+        BlockQuoteKind::Caution => "Caution",
+    }
+}
+
+/// How far ahead in the source to look for a text character that does not
+/// appear verbatim (escapes, entities, stripped quote markers).
+const SOURCE_LOOKAHEAD: usize = 32;
+
+/// Source byte offset of each character of `text`, an event's text read
+/// from `markdown[range]`. Characters are matched in order, skipping source
+/// bytes that the parser dropped (`\` escapes, `> ` prefixes, backticks);
+/// a character with no match nearby (a decoded entity) gets `None`.
+fn source_offsets(markdown: &str, range: &Range<usize>, text: &str) -> Vec<Option<usize>> {
+    let src = markdown.get(range.clone()).unwrap_or("");
+    let mut pos = 0;
+    text.chars()
+        .map(|ch| {
+            let found = src[pos..]
+                .char_indices()
+                .take(SOURCE_LOOKAHEAD)
+                .find(|&(_, c)| c == ch);
+            found.map(|(i, c)| {
+                let at = pos + i;
+                pos = at + c.len_utf8();
+                range.start + at
+            })
+        })
+        .collect()
+}
+
+/// Greedy word wrap. Breaks at spaces and around wide (CJK) characters,
+/// splits words longer than `width`, and honours hard breaks (`'\n'`).
+fn wrap(cells: &[Cell], width: usize) -> Vec<Vec<Cell>> {
+    let width = width.max(1);
+    let mut lines = Vec::new();
+    let mut line: Vec<Cell> = Vec::new();
+    let mut line_w = 0;
+    let mut finish = |line: &mut Vec<Cell>, line_w: &mut usize| {
+        while line.last().is_some_and(|c| c.ch == ' ') {
+            line.pop();
+        }
+        lines.push(std::mem::take(line));
+        *line_w = 0;
+    };
+    for word in words(cells) {
+        if word[0].ch == '\n' {
+            finish(&mut line, &mut line_w);
+            continue;
+        }
+        let w: usize = word.iter().map(Cell::width).sum();
+        if word[0].ch == ' ' {
+            if line_w == 0 {
+                continue;
+            }
+            if line_w + w > width {
+                finish(&mut line, &mut line_w);
+            } else {
+                line.extend_from_slice(word);
+                line_w += w;
+            }
+            continue;
+        }
+        if line_w > 0 && line_w + w > width {
+            finish(&mut line, &mut line_w);
+        }
+        if w > width {
+            for &c in word {
+                if line_w > 0 && line_w + c.width() > width {
+                    finish(&mut line, &mut line_w);
+                }
+                line.push(c);
+                line_w += c.width();
+            }
+        } else {
+            line.extend_from_slice(word);
+            line_w += w;
+        }
+    }
+    if !line.is_empty() {
+        finish(&mut line, &mut line_w);
+    }
+    lines
+}
+
+/// Split at every column limit, keeping all characters (code lines).
+fn hard_wrap(cells: &[Cell], width: usize) -> Vec<Vec<Cell>> {
+    let mut lines = vec![Vec::new()];
+    let mut line_w = 0;
+    for &c in cells {
+        if line_w > 0 && line_w + c.width() > width {
+            lines.push(Vec::new());
+            line_w = 0;
+        }
+        lines.last_mut().expect("non-empty").push(c);
+        line_w += c.width();
+    }
+    lines
+}
+
+/// Wrap units: a hard break, a run of spaces, a wide character, or a run of
+/// other characters.
+fn words(cells: &[Cell]) -> Vec<&[Cell]> {
+    let kind = |c: &Cell| match c.ch {
+        '\n' => 0,
+        ' ' => 1,
+        _ if c.width() > 1 => 2,
+        _ => 3,
+    };
+    let mut out = Vec::new();
+    let mut start = 0;
+    for i in 1..=cells.len() {
+        let split = i == cells.len() || {
+            let (a, b) = (kind(&cells[i - 1]), kind(&cells[i]));
+            a != b || a == 0 || a == 2
+        };
+        if split {
+            out.push(&cells[start..i]);
+            start = i;
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn text(row: &Row) -> String {
+        row.cells.iter().map(|c| c.ch).collect()
+    }
+
+    fn texts(doc: &TextDocument) -> Vec<String> {
+        doc.rows.iter().map(text).collect()
+    }
+
+    #[test]
+    fn paragraphs_wrap_at_width() {
+        let doc = layout("one two three four\n\nfive", 9);
+        assert_eq!(texts(&doc), ["one two", "three", "four", "", "five"]);
+    }
+
+    #[test]
+    fn long_words_are_split() {
+        let doc = layout("abcdefghij", 4);
+        assert_eq!(texts(&doc), ["abcd", "efgh", "ij"]);
+    }
+
+    #[test]
+    fn cjk_breaks_between_characters() {
+        let doc = layout("日本語の文章", 6);
+        assert_eq!(texts(&doc), ["日本語", "の文章"]);
+    }
+
+    #[test]
+    fn visual_lines_map_rows_to_blocks() {
+        let md = "# Title\n\nalpha beta\n";
+        let doc = layout(md, 6);
+        // Title, underline, blank, alpha, beta
+        assert_eq!(texts(&doc), ["Title", "━━━━━", "", "alpha", "beta"]);
+        assert_eq!(doc.visual_lines.len(), 3);
+        assert_eq!(doc.visual_lines[0].y_px, 0);
+        assert_eq!(doc.visual_lines[1].y_px, 3);
+        assert_eq!(doc.visual_lines[1].md_block_range, Some(9..20));
+        assert_eq!(doc.visual_lines[2].md_offset, Some(15));
+        assert_eq!(doc.rows[4].line, Some(2));
+        assert_eq!(doc.rows[1].line, None);
+    }
+
+    #[test]
+    fn cells_carry_source_offsets() {
+        let md = "a \\*b\\* `c` &amp;";
+        let doc = layout(md, 40);
+        let srcs: Vec<(char, Option<usize>)> =
+            doc.rows[0].cells.iter().map(|c| (c.ch, c.src)).collect();
+        assert_eq!(srcs[0], ('a', Some(0)));
+        assert_eq!(srcs[2], ('*', Some(3)));
+        assert_eq!(srcs[3], ('b', Some(4)));
+        assert_eq!(srcs[6], ('c', Some(9)));
+        assert_eq!(srcs[8], ('&', Some(12)));
+    }
+
+    #[test]
+    fn inline_styles() {
+        let doc = layout("**b** *i* [l](u) `c`", 40);
+        let style = |i: usize| doc.rows[0].cells[i].style;
+        assert!(style(0).bold);
+        assert_eq!(style(2).role, Role::Emph);
+        assert!(style(2).italic);
+        assert_eq!(style(4).role, Role::Link);
+        assert_eq!(style(6).role, Role::Code);
+    }
+
+    #[test]
+    fn lists_indent_continuation_rows() {
+        let doc = layout("- one two\n  - six\n1. four\n", 8);
+        assert_eq!(texts(&doc), ["• one", "  two", "  ‣ six", "", "1. four"]);
+    }
+
+    #[test]
+    fn block_quote_has_a_bar_on_every_row() {
+        let doc = layout("> one two\n>\n> three\n", 7);
+        assert_eq!(texts(&doc), ["▌ one", "▌ two", "▌ ", "▌ three"]);
+        assert_eq!(doc.rows[0].cells[2].style.role, Role::Quote);
+    }
+
+    #[test]
+    fn code_block_is_boxed() {
+        let doc = layout("```rust\nfn x() {}\n\n```\n", 16);
+        assert_eq!(
+            texts(&doc),
+            [
+                "╭─ rust ───────╮",
+                "│ fn x() {}    │",
+                "│              │",
+                "╰──────────────╯",
+            ]
+        );
+        assert_eq!(doc.visual_lines.len(), 1);
+        assert_eq!(doc.rows[1].line, Some(0));
+        assert_eq!(doc.rows[1].cells[2].src, Some(8));
+    }
+
+    #[test]
+    fn table_is_boxed_and_aligned() {
+        let md = "| a | bb |\n|---|---:|\n| ccc | d |\n";
+        let doc = layout(md, 40);
+        assert_eq!(
+            texts(&doc),
+            [
+                "┌─────┬────┐",
+                "│ a   │ bb │",
+                "├─────┼────┤",
+                "│ ccc │  d │",
+                "└─────┴────┘",
+            ]
+        );
+        assert!(doc.rows[1].cells[2].style.bold);
+        assert!(!doc.rows[3].cells[2].style.bold);
+        assert_eq!(doc.visual_lines.len(), 2);
+    }
+
+    #[test]
+    fn wide_table_shrinks_and_wraps() {
+        let md = "| aaaa bbbb | c |\n|---|---|\n";
+        let doc = layout(md, 12);
+        assert_eq!(
+            texts(&doc),
+            [
+                "┌──────┬───┐",
+                "│ aaaa │ c │",
+                "│ bbbb │   │",
+                "└──────┴───┘"
+            ]
+        );
+    }
+
+    #[test]
+    fn footnotes_are_numbered() {
+        let doc = layout("x[^n]\n\n[^n]: note\n", 20);
+        assert_eq!(texts(&doc), ["x[1]", "", "[1] note"]);
+    }
+
+    #[test]
+    fn image_shows_alt_or_target() {
+        let doc = layout("![cat](c.png) ![](d.png)", 40);
+        assert_eq!(texts(&doc), ["[image: cat] [image: d.png]"]);
+    }
+
+    #[test]
+    fn front_matter_title() {
+        let doc = layout("---\ntitle: Hello\nauthor: Me\n---\n\nbody\n", 20);
+        assert_eq!(texts(&doc), ["Hello", "Me", "", "body"]);
+    }
+
+    #[test]
+    fn hard_break_starts_a_new_row() {
+        let doc = layout("a  \nb", 20);
+        assert_eq!(texts(&doc), ["a", "b"]);
+    }
+}
@@ -0,0 +1,410 @@
+//! Text-mode event loop: the document as styled ANSI text instead of images.
+//!
+//! For terminals without any image protocol (`--graphics text`, or what
+//! `auto` picks when the probe finds none). The Markdown is reflowed to the
+//! terminal width by [`super::text_layout`]; scroll offsets and visual line
+//! positions count rows (`Layout::cell_h == 1`), so the key handlers, mode
+//! screens and effects are exactly those of the image viewer.
+
+use crossterm::{
+    QueueableCommand, cursor,
+    event::{self, Event},
+    style::{Attribute, Color, Print, SetAttribute, SetBackgroundColor, SetForegroundColor},
+    terminal::{Clear, ClearType},
+};
+use log::info;
+use std::io::{self, Write, stdout};
+use std::time::{Duration, Instant};
+
+use crate::app_context::AppContext;
+use crate::frame::{HIGHLIGHT_ACTIVE_RGBA, HIGHLIGHT_RGBA};
+use crate::theme::TextColors;
+
+use super::display_state::DisplayState;
+use super::effect::{self, ExitReason, RenderOp, ViewerMode};
+use super::graphics::Graphics;
+use super::keymap::{InputAccumulator, Keymap};
+use super::layout::{self, Layout, ScrollState};
+use super::mode_grep::SearchMatch;
+use super::query::DocumentQuery;
+use super::scroll::ScrollStrategy;
+use super::session::Session;
+use super::text_layout::{self, Role, TextDocument};
+use super::viewport::{ViewContext, Viewport};
+use super::{MarkdownSource, apply_effects, key_effects, mouse_effects};
+
+/// Theme colours resolved to terminal colours.
+struct Palette {
+    text: Color,
+    headings: [Color; 6],
+    emph: Color,
+    code_bg: Color,
+    link: Color,
+    quote: Color,
+    border: Color,
+    gutter_bg: Color,
+    gutter_fg: Color,
+}
+
+impl Palette {
+    fn new(colors: &TextColors, sidebar: (&str, &str)) -> Self {
+        Self {
+            text: hex(colors.text),
+            headings: colors.headings.map(hex),
+            emph: hex(colors.emph),
+            code_bg: hex(colors.code_bg),
+            link: hex(colors.link),
+            quote: hex(colors.quote),
+            border: hex(colors.border),
+            gutter_bg: hex(sidebar.0),
+            gutter_fg: hex(sidebar.1),
+        }
+    }
+
+    fn fg(&self, role: Role) -> Color {
+        match role {
+            Role::Text | Role::Code => self.text,
+            Role::Heading(level) => self.headings[(level.clamp(1, 6) - 1) as usize],
+            Role::Emph => self.emph,
+            Role::Link => self.link,
+            Role::Quote => self.quote,
+            Role::Border => self.border,
+        }
+    }
+}
+
+/// `#rrggbb` → RGB colour (black if malformed; theme colours are constants).
+fn hex(s: &str) -> Color {
+    let channel = |i: usize| {
+        s.get(i..i + 2)
+            .and_then(|h| u8::from_str_radix(h, 16).ok())
+            .unwrap_or(0)
+    };
+    Color::Rgb {
+        r: channel(1),
+        g: channel(3),
+        b: channel(5),
+    }
+}
+
+fn rgb([r, g, b, _]: [u8; 4]) -> Color {
+    Color::Rgb { r, g, b }
+}
+
+/// Search matches to highlight, with the index of the current one.
+/// Matches are in Markdown order, so lookups are binary searches.
+struct Highlights<'a> {
+    matches: &'a [SearchMatch],
+    current: usize,
+}
+
+impl Highlights<'_> {
+    fn from_viewport(vp: &Viewport) -> Option<Highlights<'_>> {
+        match &vp.mode {
+            ViewerMode::InlineSearch(is) if !is.matches.is_empty() => Some(Highlights {
+                matches: &is.matches,
+                current: is.current_idx,
+            }),
+            _ if vp.highlights_visible => vp.last_search.as_ref().map(|ls| Highlights {
+                matches: &ls.matches,
+                current: ls.current_idx,
+            }),
+            _ => None,
+        }
+    }
+
+    /// `Some(is_current)` if the source byte `src` is inside a match.
+    fn at(&self, src: usize) -> Option<bool> {
+        let i = self.matches.partition_point(|m| m.md_range.start <= src);
+        let idx = i.checked_sub(1)?;
+        self.matches[idx]
+            .md_range
+            .contains(&src)
+            .then_some(idx == self.current)
+    }
+}
+
+/// Run the viewer in text mode until quit. Mirrors the outer/inner loop
+/// structure of [`super::run`], minus tiles, rendering and zoom.
+pub(super) fn run(
+    app: &AppContext,
+    session: &mut Session,
+    keymap: &Keymap,
+    source: &mut MarkdownSource,
+) -> anyhow::Result<()> {
+    let theme = crate::theme::resolve_theme_name(&app.config.theme, app.detected_light, true);
+    let palette = Palette::new(
+        crate::theme::text_colors(theme),
+        crate::theme::sidebar_colors(theme),
+    );
+    let scroll_strategy = ScrollStrategy::from_mode(app.config.viewer.scroll_mode);
+
+    loop {
+        let markdown = source.load(session)?;
+        let doc = text_layout::layout(&markdown, session.layout.image_cols as usize);
+        let (_, content_index) = crate::compile::markdown_to_typst(&markdown, None);
+        let total = doc.rows.len() as u32;
+        let (vp_w, vp_h) = layout::vp_dims(&session.layout, u32::MAX, total);
+        let max_scroll = total.saturating_sub(vp_h);
+        info!(
+            "text layout: {} rows, {} lines",
+            total,
+            doc.visual_lines.len()
+        );
+
+        let query = DocumentQuery::new(&markdown, &doc.visual_lines, &content_index, 0);
+        if let Some(anchor) = session.pending_anchor.take() {
+            match query.find_anchor(&anchor) {
+                Some(vl_idx) => {
+                    session.scroll_carry = layout::visual_line_offset(
+                        &doc.visual_lines,
+                        max_scroll,
+                        (vl_idx + 1) as u32,
+                    );
+                }
+                None => session.pending_flash = Some(format!("No heading for #{anchor}")),
+            }
+        }
+
+        let mut vp = Viewport {
+            mode: ViewerMode::Normal,
+            scroll: ScrollState::new(
+                session.scroll_carry.min(max_scroll),
+                total,
+                vp_w,
+                vp_h,
+                app.config.viewer.scroll_animation,
+            ),
+            display: DisplayState::new_with_start_id(0, 0, Graphics::Text),
+            flash: session.pending_flash.take(),
+            dirty: false,
+            last_search: None,
+            highlights_visible: true,
+            pending_zoom_delta: 0,
+        };
+        let mut acc = InputAccumulator::new();
+        draw(&doc, &palette, &mut vp, session, &query, acc.peek())?;
+
+        let mut last_render = Instant::now();
+        let mut last_tick = Instant::now();
+        let exit = loop {
+            let now = Instant::now();
+            let dt = now.duration_since(last_tick).min(Duration::from_millis(64));
+            last_tick = now;
+            if vp.scroll.tick(dt) {
+                vp.dirty = true;
+            }
+
+            let timeout = if vp.dirty || vp.scroll.is_animating() {
+                app.config
+                    .viewer
+                    .frame_budget
+                    .saturating_sub(last_render.elapsed())
+            } else if source.is_live(session) {
+                app.config.viewer.watch_interval
+            } else {
+                Duration::from_secs(86400)
+            };
+
+            if event::poll(timeout)? {
+                let effects = match event::read()? {
+                    Event::Key(key_event) => key_effects(
+                        key_event,
+                        &mut vp,
+                        &mut acc,
+                        keymap,
+                        &scroll_strategy,
+                        app,
+                        session,
+                        &query,
+                        max_scroll,
+                    ),
+                    Event::Mouse(me) if app.config.viewer.mouse => {
+                        mouse_effects(me, &mut vp, app, session, &query, max_scroll)
+                    }
+                    Event::Resize(new_cols, new_rows) => {
+                        break ExitReason::Resize { new_cols, new_rows };
+                    }
+                    _ => continue,
+                };
+                match apply_effects(effects, &mut vp, session, &query, acc.peek())? {
+                    // The text does not scale; stay on this build.
+                    Some(ExitReason::SetScale { .. }) => {
+                        vp.flash = Some("zoom: not available in text mode".into());
+                        vp.dirty = true;
+                    }
+                    Some(reason) => break reason,
+                    None => {}
+                }
+                continue;
+            }
+
+            // Ctrl+wheel zoom has nothing to rebuild either.
+            vp.pending_zoom_delta = 0;
+            if vp.dirty {
+                draw(&doc, &palette, &mut vp, session, &query, acc.peek())?;
+                vp.dirty = false;
+            }
+            last_render = Instant::now();
+
+            if source.changed(session) {
+                info!("file change detected, reloading");
+                break ExitReason::Reload;
+            }
+        };
+
+        if session.handle_exit(exit, vp.scroll.y_offset, app.config.viewer.sidebar_cols)? {
+            return Ok(());
+        }
+    }
+}
+
+/// Draw the visible rows and the status bar. Full-screen modes (grep, TOC,
+/// URL picker, log) own the screen and are left alone.
+fn draw(
+    doc: &TextDocument,
+    palette: &Palette,
+    vp: &mut Viewport,
+    session: &Session,
+    query: &DocumentQuery,
+    acc_value: Option<u32>,
+) -> anyhow::Result<()> {
+    let bottom = match vp.mode {
+        ViewerMode::Normal | ViewerMode::InlineSearch(_) => RenderOp::DrawStatusBar,
+        ViewerMode::Command(_) => RenderOp::DrawModeScreen,
+        _ => return Ok(()),
+    };
+    let highlights = Highlights::from_viewport(vp);
+    draw_rows(
+        doc,
+        &session.layout,
+        vp.scroll.y_offset as usize,
+        palette,
+        highlights.as_ref(),
+    )?;
+    let ctx = ViewContext {
+        layout: &session.layout,
+        acc_value,
+        filename: &session.filename,
+        jump_stack: &session.jump_stack,
+        doc: query,
+        log_buffer: &session.log_buffer,
+    };
+    effect::execute_render_ops(vec![bottom], vp, &ctx)?;
+    Ok(())
+}
+
+fn draw_rows(
+    doc: &TextDocument,
+    layout: &Layout,
+    top: usize,
+    palette: &Palette,
+    highlights: Option<&Highlights>,
+) -> io::Result<()> {
+    let mut out = stdout();
+    let gutter = layout.sidebar_cols as usize;
+    for screen_row in 0..layout.image_rows {
+        out.queue(cursor::MoveTo(0, screen_row))?;
+        out.queue(SetAttribute(Attribute::Reset))?;
+        let Some(row) = doc.rows.get(top + screen_row as usize) else {
+            out.queue(Clear(ClearType::CurrentLine))?;
+            continue;
+        };
+        if gutter > 0 {
+            let number = row.line.map(|i| (i + 1).to_string()).unwrap_or_default();
+            out.queue(SetBackgroundColor(palette.gutter_bg))?;
+            out.queue(SetForegroundColor(palette.gutter_fg))?;
+            out.queue(Print(format!(
+                "{:>w$} ",
+                number,
+                w = gutter.saturating_sub(1)
+            )))?;
+        }
+
+        let mut current = None;
+        for cell in &row.cells {
+            let mark = cell.src.and_then(|src| highlights.and_then(|h| h.at(src)));
+            let key = (cell.style, mark);
+            if current != Some(key) {
+                current = Some(key);
+                out.queue(SetAttribute(Attribute::Reset))?;
+                let (fg, bg) = match mark {
+                    Some(active) => {
+                        let bg = if active {
+                            HIGHLIGHT_ACTIVE_RGBA
+                        } else {
+                            HIGHLIGHT_RGBA
+                        };
+                        (Color::Black, rgb(bg))
+                    }
+                    None if cell.style.role == Role::Code => {
+                        (palette.fg(cell.style.role), palette.code_bg)
+                    }
+                    None => (palette.fg(cell.style.role), Color::Reset),
+                };
+                out.queue(SetForegroundColor(fg))?;
+                out.queue(SetBackgroundColor(bg))?;
+                if cell.style.bold {
+                    out.queue(SetAttribute(Attribute::Bold))?;
+                }
+                if cell.style.italic {
+                    out.queue(SetAttribute(Attribute::Italic))?;
+                }
+                if cell.style.strike {
+                    out.queue(SetAttribute(Attribute::CrossedOut))?;
+                }
+                if cell.style.role == Role::Link {
+                    out.queue(SetAttribute(Attribute::Underlined))?;
+                }
+            }
+            out.queue(Print(cell.ch))?;
+        }
+        out.queue(SetAttribute(Attribute::Reset))?;
+        out.queue(Clear(ClearType::UntilNewLine))?;
+    }
+    out.flush()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn search_match(range: std::ops::Range<usize>) -> SearchMatch {
+        SearchMatch {
+            md_line: 1,
+            visual_line_idx: 0,
+            context: String::new(),
+            col_start: 0,
+            col_end: 0,
+            md_range: range,
+        }
+    }
+
+    #[test]
+    fn highlights_find_matches_by_source_offset() {
+        let matches = [search_match(2..5), search_match(10..12)];
+        let h = Highlights {
+            matches: &matches,
+            current: 1,
+        };
+        assert_eq!(h.at(1), None);
+        assert_eq!(h.at(2), Some(false));
+        assert_eq!(h.at(4), Some(false));
+        assert_eq!(h.at(5), None);
+        assert_eq!(h.at(11), Some(true));
+        assert_eq!(h.at(12), None);
+    }
+
+    #[test]
+    fn hex_colors() {
+        assert_eq!(
+            hex("#cdd6f4"),
+            Color::Rgb {
+                r: 0xcd,
+                g: 0xd6,
+                b: 0xf4
+            }
+        );
+        assert_eq!(hex("#zzzzzz"), Color::Rgb { r: 0, g: 0, b: 0 });
+    }
+}
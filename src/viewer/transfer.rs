@@ -0,0 +1,336 @@
+//! Kitty transfer media: how uploaded image data reaches the terminal.
+//!
+//! A tile PNG sent directly (`t=d`) is base64-encoded through the tty, a
+//! third larger than the PNG and parsed by the terminal on the way. A
+//! terminal on the same machine can instead read it from a temporary file
+//! (`t=t`) or a POSIX shared memory object (`t=s`), deleting it afterwards;
+//! only the name goes through the tty. Over SSH neither exists on the
+//! terminal's side, so direct transfer is the fallback.
+//!
+//! `viewer.transfer = "auto"` probes once at startup: a 1×1 image is staged
+//! in shared memory and in a temp file, and the terminal is asked (`a=q`)
+//! to load each. The first medium it answers `OK` for wins; an error reply
+//! to both means direct transfer. If the terminal sends no Kitty reply at
+//! all (tmux may not relay it), an SSH session gets direct transfer and
+//! anything else the temp file.
+
+use base64::{Engine as _, engine::general_purpose::STANDARD as BASE64};
+use log::{info, warn};
+use std::ffi::CString;
+use std::io::{self, Write};
+use std::sync::atomic::{AtomicU8, AtomicU32, Ordering};
+
+use super::{graphics, terminal};
+use crate::config::TransferMode;
+
+/// Base64 bytes per direct-transfer chunk (the protocol's limit).
+const CHUNK: usize = 4096;
+
+/// Image ids of the probe queries (`a=q` never stores them).
+const SHM_QUERY_ID: u32 = 32;
+const FILE_QUERY_ID: u32 = 33;
+
+/// The medium in use; see [`set`].
+static CURRENT: AtomicU8 = AtomicU8::new(Transfer::File as u8);
+
+/// How image data is handed to the terminal.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[repr(u8)]
+pub(super) enum Transfer {
+    Direct,
+    File,
+    Shm,
+}
+
+impl Transfer {
+    /// Resolve the configured mode, probing the terminal for `Auto`.
+    /// Must be called in raw mode, before the event loop reads input.
+    pub(super) fn resolve(mode: TransferMode) -> Self {
+        let transfer = match mode {
+            TransferMode::Direct => Self::Direct,
+            TransferMode::File => Self::File,
+            TransferMode::Shm => Self::Shm,
+            TransferMode::Auto => match probe() {
+                Some(t) => t,
+                None if is_ssh_session() => {
+                    warn!("transfer probe got no reply, using direct transfer over SSH");
+                    Self::Direct
+                }
+                None => {
+                    warn!("transfer probe got no reply, assuming a local terminal");
+                    Self::File
+                }
+            },
+        };
+        info!("transfer: {transfer:?} (configured {mode:?})");
+        transfer
+    }
+}
+
+/// Use `transfer` for every upload from now on. Set once at startup.
+pub(super) fn set(transfer: Transfer) {
+    CURRENT.store(transfer as u8, Ordering::Relaxed);
+}
+
+fn current() -> Transfer {
+    match CURRENT.load(Ordering::Relaxed) {
+        0 => Transfer::Direct,
+        1 => Transfer::File,
+        _ => Transfer::Shm,
+    }
+}
+
+/// Send `data` with the transmit command `keys` (e.g. `a=t,f=100,i=7`)
+/// through the current medium.
+pub(super) fn transmit(out: &mut impl Write, keys: &str, data: &[u8]) -> io::Result<()> {
+    match current() {
+        Transfer::File => {
+            let path = stage_file(data)?;
+            let name = BASE64.encode(path.as_bytes());
+            terminal::kitty(out, format_args!("{keys},t=t,q=2;{name}"))
+        }
+        Transfer::Shm => {
+            let path = stage_shm(data)?;
+            let name = BASE64.encode(path.as_bytes());
+            let size = data.len();
+            terminal::kitty(out, format_args!("{keys},t=s,S={size},q=2;{name}"))
+        }
+        Transfer::Direct => send_direct(out, keys, data),
+    }
+}
+
+/// Base64 `data` through the tty in chunks; all but the last carry `m=1`.
+fn send_direct(out: &mut impl Write, keys: &str, data: &[u8]) -> io::Result<()> {
+    let encoded = BASE64.encode(data);
+    let mut chunks = encoded.as_bytes().chunks(CHUNK).peekable();
+    let mut first = true;
+    // An empty payload still needs its one command.
+    if chunks.peek().is_none() {
+        return terminal::kitty(out, format_args!("{keys},t=d,q=2;"));
+    }
+    while let Some(chunk) = chunks.next() {
+        let more = u8::from(chunks.peek().is_some());
+        // Chunks are slices of base64, hence ASCII.
+        let chunk = std::str::from_utf8(chunk).expect("base64 is ASCII");
+        if first {
+            terminal::kitty(out, format_args!("{keys},t=d,m={more},q=2;{chunk}"))?;
+            first = false;
+        } else {
+            terminal::kitty(out, format_args!("m={more},q=2;{chunk}"))?;
+        }
+    }
+    Ok(())
+}
+
+/// Write `data` to a temp file the terminal reads and deletes. Kitty only
+/// deletes files whose path contains `tty-graphics-protocol`.
+fn stage_file(data: &[u8]) -> io::Result<String> {
+    let mut tmp = tempfile::Builder::new()
+        .prefix("tty-graphics-protocol.")
+        .tempfile()?;
+    tmp.write_all(data)?;
+    tmp.flush()?;
+
+    // Get path, then close handle and disarm auto-delete.
+    let path = tmp
+        .path()
+        .to_str()
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "temp path not UTF-8"))?
+        .to_string();
+    let _ = tmp.into_temp_path().keep();
+    Ok(path)
+}
+
+/// Copy `data` into a new shared memory object the terminal reads and
+/// unlinks. Names stay under macOS's 31-byte limit.
+fn stage_shm(data: &[u8]) -> io::Result<String> {
+    static SEQ: AtomicU32 = AtomicU32::new(0);
+    let name = format!(
+        "/mlux-{}-{}",
+        std::process::id(),
+        SEQ.fetch_add(1, Ordering::Relaxed)
+    );
+    let c_name = CString::new(name.clone()).expect("shm name has no NUL");
+    let fd = unsafe {
+        libc::shm_open(
+            c_name.as_ptr(),
+            libc::O_CREAT | libc::O_EXCL | libc::O_RDWR,
+            0o600 as libc::mode_t,
+        )
+    };
+    if fd < 0 {
+        return Err(io::Error::last_os_error());
+    }
+    // macOS cannot write(2) to shared memory, so map it.
+    let result = unsafe { fill_shm(fd, data) };
+    unsafe { libc::close(fd) };
+    if let Err(e) = result {
+        unsafe { libc::shm_unlink(c_name.as_ptr()) };
+        return Err(e);
+    }
+    Ok(name)
+}
+
+/// Size the shared memory object `fd` to `data` and copy it in.
+///
+/// # Safety
+/// `fd` must be an open, writable shared memory object.
+unsafe fn fill_shm(fd: libc::c_int, data: &[u8]) -> io::Result<()> {
+    if unsafe { libc::ftruncate(fd, data.len() as libc::off_t) } != 0 {
+        return Err(io::Error::last_os_error());
+    }
+    if data.is_empty() {
+        return Ok(());
+    }
+    let ptr = unsafe {
+        libc::mmap(
+            std::ptr::null_mut(),
+            data.len(),
+            libc::PROT_READ | libc::PROT_WRITE,
+            libc::MAP_SHARED,
+            fd,
+            0,
+        )
+    };
+    if ptr == libc::MAP_FAILED {
+        return Err(io::Error::last_os_error());
+    }
+    unsafe {
+        std::ptr::copy_nonoverlapping(data.as_ptr(), ptr.cast::<u8>(), data.len());
+        libc::munmap(ptr, data.len());
+    }
+    Ok(())
+}
+
+/// Remove a staged shared memory object the terminal did not consume.
+fn unlink_shm(name: &str) {
+    if let Ok(c_name) = CString::new(name) {
+        unsafe { libc::shm_unlink(c_name.as_ptr()) };
+    }
+}
+
+fn is_ssh_session() -> bool {
+    ["SSH_CONNECTION", "SSH_TTY"]
+        .iter()
+        .any(|v| std::env::var_os(v).is_some_and(|v| !v.is_empty()))
+}
+
+fn probe() -> Option<Transfer> {
+    // One black RGB pixel.
+    const PIXEL: &[u8] = &[0, 0, 0];
+    let query = |out: &mut Vec<u8>, id: u32, medium: char, name: &str| {
+        let name = BASE64.encode(name.as_bytes());
+        let _ = terminal::kitty(
+            out,
+            format_args!("i={id},s=1,v=1,a=q,f=24,t={medium};{name}"),
+        );
+    };
+
+    let shm = stage_shm(PIXEL)
+        .inspect_err(|e| warn!("cannot stage shared memory for transfer probe: {e}"))
+        .ok();
+    let file = stage_file(PIXEL)
+        .inspect_err(|e| warn!("cannot stage temp file for transfer probe: {e}"))
+        .ok();
+    let mut request = Vec::new();
+    if let Some(name) = &shm {
+        query(&mut request, SHM_QUERY_ID, 's', name);
+    }
+    if let Some(path) = &file {
+        query(&mut request, FILE_QUERY_ID, 't', path);
+    }
+    let reply = graphics::query_tty(&request);
+
+    // Whatever the terminal did not consume.
+    if let Some(name) = &shm {
+        unlink_shm(name);
+    }
+    if let Some(path) = &file {
+        let _ = std::fs::remove_file(path);
+    }
+    parse_probe(&reply?)
+}
+
+/// The terminal's reply to the query with image id `id`: `Some(true)` for
+/// `OK`, `Some(false)` for an error.
+fn query_reply(buf: &[u8], id: u32) -> Option<bool> {
+    let prefix = format!("\x1b_Gi={id};");
+    let start = buf
+        .windows(prefix.len())
+        .position(|w| w == prefix.as_bytes())?
+        + prefix.len();
+    Some(buf[start..].starts_with(b"OK"))
+}
+
+/// Shared memory if the terminal loaded it, else the temp file; direct
+/// transfer if it answered but loaded neither. `None` without any reply.
+fn parse_probe(buf: &[u8]) -> Option<Transfer> {
+    let shm = query_reply(buf, SHM_QUERY_ID);
+    let file = query_reply(buf, FILE_QUERY_ID);
+    match (shm, file) {
+        (Some(true), _) => Some(Transfer::Shm),
+        (_, Some(true)) => Some(Transfer::File),
+        (None, None) => None,
+        _ => Some(Transfer::Direct),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn probe_prefers_shared_memory() {
+        let buf = b"\x1b_Gi=32;OK\x1b\\\x1b_Gi=33;OK\x1b\\\x1b[?62;22c";
+        assert_eq!(parse_probe(buf), Some(Transfer::Shm));
+    }
+
+    #[test]
+    fn probe_falls_back_to_file_then_direct() {
+        let file_only = b"\x1b_Gi=32;EBADF:shm\x1b\\\x1b_Gi=33;OK\x1b\\\x1b[?62c";
+        assert_eq!(parse_probe(file_only), Some(Transfer::File));
+        let remote = b"\x1b_Gi=32;ENOENT\x1b\\\x1b_Gi=33;ENOENT\x1b\\\x1b[?62c";
+        assert_eq!(parse_probe(remote), Some(Transfer::Direct));
+    }
+
+    #[test]
+    fn probe_without_kitty_reply_is_inconclusive() {
+        assert_eq!(parse_probe(b"\x1b[?62;22c"), None);
+        assert_eq!(parse_probe(b""), None);
+    }
+
+    #[test]
+    fn direct_transfer_is_chunked() {
+        let data = vec![7u8; CHUNK]; // 4/3 CHUNK of base64: two chunks
+        let mut out = Vec::new();
+        send_direct(&mut out, "a=t,f=100,i=9", &data).unwrap();
+        let out = String::from_utf8(out).unwrap();
+        let commands: Vec<&str> = out.split("\x1b_G").skip(1).collect();
+        assert_eq!(commands.len(), 2);
+        assert!(commands[0].starts_with("a=t,f=100,i=9,t=d,m=1,q=2;"));
+        assert!(commands[1].starts_with("m=0,q=2;"));
+        let payload: String = commands
+            .iter()
+            .map(|c| c.split_once(';').unwrap().1.trim_end_matches("\x1b\\"))
+            .collect();
+        assert_eq!(BASE64.decode(payload).unwrap(), data);
+    }
+
+    #[test]
+    fn staged_shm_holds_the_data() {
+        let name = match stage_shm(b"tile") {
+            Ok(name) => name,
+            // Some sandboxes have no /dev/shm.
+            Err(_) => return,
+        };
+        let c_name = CString::new(name.clone()).unwrap();
+        let fd = unsafe { libc::shm_open(c_name.as_ptr(), libc::O_RDONLY, 0) };
+        assert!(fd >= 0);
+        let mut buf = [0u8; 4];
+        let n = unsafe { libc::read(fd, buf.as_mut_ptr().cast(), buf.len()) };
+        unsafe { libc::close(fd) };
+        unlink_shm(&name);
+        assert_eq!(n, 4);
+        assert_eq!(&buf, b"tile");
+    }
+}
@@ -99,7 +99,9 @@ impl Viewport {
                 self.scroll.animator.restart_ease_in_if_settled(was_settled);
                 self.dirty = true;
             }
-            Effect::ScrollAnchor(y) => {
+            // JumpWithin's jump-stack push happens in the mod.rs effect loop
+            // (needs Session access); the scroll itself is a plain anchor jump.
+            Effect::ScrollAnchor(y) | Effect::JumpWithin(y) => {
                 // iOS scroll-to-top semantics: cancel residual momentum,
                 // pin the anchor to the current sub-pixel position, then
                 // push a single (y - current) impulse so velocity-based
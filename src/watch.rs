@@ -1,10 +1,12 @@
-//! File watcher — monitors a file for changes via notify (inotify on Linux).
+//! File watcher — monitors files for changes via notify (inotify on Linux).
 //!
 //! notify::RecommendedWatcher runs callbacks on an internal thread.
 //! FileWatcher bridges change notifications to the main thread via mpsc::channel.
 
-use std::path::Path;
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
 use std::sync::mpsc;
+use std::time::Duration;
 
 use anyhow::Result;
 use notify::{RecommendedWatcher, RecursiveMode, Watcher};
@@ -20,14 +22,31 @@ impl FileWatcher {
     /// Linux inotify loses the watch on rename (atomic save), so we watch
     /// the parent directory (NonRecursive) and filter events by path.
     pub fn new(path: &Path) -> Result<Self> {
-        let canonical = path.canonicalize()?;
-        let target = canonical.clone();
+        Self::new_multi(&[path.to_path_buf()])
+    }
+
+    /// Create a FileWatcher that reports changes to any of the given files.
+    ///
+    /// Each distinct parent directory is watched once.
+    pub fn new_multi(paths: &[PathBuf]) -> Result<Self> {
+        let targets: HashSet<PathBuf> = paths
+            .iter()
+            .map(|p| p.canonicalize())
+            .collect::<std::io::Result<_>>()?;
+        let parents: HashSet<PathBuf> = targets
+            .iter()
+            .map(|p| {
+                p.parent()
+                    .map(Path::to_path_buf)
+                    .ok_or_else(|| anyhow::anyhow!("cannot watch root path"))
+            })
+            .collect::<Result<_>>()?;
         let (tx, rx) = mpsc::channel();
 
         let mut watcher = RecommendedWatcher::new(
             move |res: Result<notify::Event, notify::Error>| {
                 if let Ok(event) = res {
-                    let dominated = event.paths.iter().any(|p| p == &target);
+                    let dominated = event.paths.iter().any(|p| targets.contains(p));
                     if dominated && event.kind.is_modify() {
                         let _ = tx.send(());
                     }
@@ -35,10 +54,9 @@ impl FileWatcher {
             },
             notify::Config::default(),
         )?;
-        let parent = canonical
-            .parent()
-            .ok_or_else(|| anyhow::anyhow!("cannot watch root path"))?;
-        watcher.watch(parent, RecursiveMode::NonRecursive)?;
+        for parent in &parents {
+            watcher.watch(parent, RecursiveMode::NonRecursive)?;
+        }
 
         Ok(Self {
             rx,
@@ -55,4 +73,15 @@ impl FileWatcher {
         }
         changed
     }
+
+    /// Block until a file changes or `timeout` passes. Returns true on a
+    /// change; notifications already queued are collapsed as in
+    /// [`has_changed`](Self::has_changed).
+    pub fn wait(&self, timeout: Duration) -> bool {
+        if self.rx.recv_timeout(timeout).is_err() {
+            return false;
+        }
+        self.has_changed();
+        true
+    }
 }
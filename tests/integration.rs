@@ -292,6 +292,39 @@ fn test_source_map_blockquote() {
     assert_eq!(yanked, "> Quote line 1\n> Quote line 2");
 }
 
+#[test]
+fn test_source_map_github_alert() {
+    let md = "> [!NOTE]\n> Alert body\n\nAfter\n";
+    let vlines = source_map_pipeline(md);
+    let body = vlines
+        .iter()
+        .position(|vl| vl.md_block_range.as_ref().is_some_and(|r| r.start == 0))
+        .expect("alert should map to its source block");
+    let yanked = yank_lines(md, &vlines, body, body);
+    assert_eq!(yanked, "> [!NOTE]\n> Alert body");
+}
+
+#[test]
+fn test_source_map_html_details() {
+    let md = "<details>\n<summary>More</summary>\n\nHidden body\n\n</details>\n";
+    let vlines = source_map_pipeline(md);
+    let summary = vlines
+        .iter()
+        .position(|vl| vl.md_block_range.as_ref().is_some_and(|r| r.start == 0))
+        .expect("summary should map to the opening HTML block");
+    let yanked = yank_lines(md, &vlines, summary, summary);
+    assert_eq!(yanked, "<details>\n<summary>More</summary>");
+    let body = vlines
+        .iter()
+        .position(|vl| {
+            vl.md_block_range
+                .as_ref()
+                .is_some_and(|r| &md[r.clone()] == "Hidden body\n")
+        })
+        .expect("paragraph inside <details> should map to its own block");
+    assert!(body > summary);
+}
+
 #[test]
 fn test_source_map_full_document() {
     let md =
@@ -703,6 +736,32 @@ fn test_list_items_are_individual_visual_lines() {
     );
 }
 
+#[test]
+fn test_source_map_aligned_table() {
+    let md = "| Left | Mid | Right |\n|:-----|:---:|------:|\n| 1 | 2 | 3 |\n";
+    let vlines = source_map_pipeline(md);
+    assert!(!vlines.is_empty(), "aligned table should compile");
+    let yanked = yank_lines(md, &vlines, 0, 0);
+    assert_eq!(yanked, md.trim_end());
+}
+
+#[test]
+fn test_source_map_footnote_definition() {
+    let md = "Body text[^n].\n\n[^n]: The note.\n";
+    let vlines = source_map_pipeline(md);
+    let def_start = md.find("[^n]:").unwrap();
+    let def_vl = vlines
+        .iter()
+        .position(|vl| {
+            vl.md_block_range
+                .as_ref()
+                .is_some_and(|r| r.contains(&def_start))
+        })
+        .expect("footnote definition should map to a visual line");
+    let yanked = yank_lines(md, &vlines, def_vl, def_vl);
+    assert_eq!(yanked, "[^n]: The note.");
+}
+
 #[test]
 fn test_table_rows_are_individual_visual_lines() {
     let md = "| H1 | H2 |\n|---|---|\n| A | B |\n| C | D |\n| E | F |\n";
@@ -895,7 +954,7 @@ fn test_mermaid_diagram_renders() {
     let diagrams = mlux::compile::extract_diagrams(md);
     assert_eq!(diagrams.len(), 1, "should extract 1 mermaid diagram");
 
-    let rendered =
+    let (rendered, _) =
         mlux::compile::render_diagrams(&diagrams, mlux::theme::mermaid_colors("catppuccin"));
     assert_eq!(rendered.len(), 1, "should render 1 diagram to SVG");
 
@@ -960,7 +1019,7 @@ fn test_mermaid_fixture_renders() {
         diagrams.len()
     );
 
-    let rendered =
+    let (rendered, _) =
         mlux::compile::render_diagrams(&diagrams, mlux::theme::mermaid_colors("catppuccin"));
     assert_eq!(
         rendered.len(),
@@ -7,8 +7,8 @@
 use mlux::compile::FontCache;
 use mlux::frame::VisibleTiles;
 use mlux::log::LogBuffer;
-use mlux::pipeline::{BuildParams, build_tiled_document};
-use mlux::renderer::{build_dump, build_renderer, build_renderer_blocking};
+use mlux::pipeline::{BuildParams, PageSetup, Paper, build_tiled_document};
+use mlux::renderer::{build_dump, build_pdf, build_renderer, build_renderer_blocking};
 
 const DEFAULT_SIDEBAR_WIDTH_PT: f64 = 40.0;
 
@@ -198,6 +198,40 @@ fn test_fork_renderer_logs_forwarded(log_buf: &LogBuffer) {
     );
 }
 
+fn test_fork_build_pdf() {
+    let font_cache: &'static FontCache = Box::leak(Box::new(FontCache::new()));
+    let params = BuildParams {
+        theme_spec: "catppuccin".into(),
+        detected_light: false,
+        markdown: "# Hello\n\nSome **bold** text.\n".into(),
+        base_dir: None,
+        file_path: None,
+        width_pt: 400.0,
+        sidebar_width_pt: DEFAULT_SIDEBAR_WIDTH_PT,
+        tile_height_pt: 500.0,
+        ppi: 144.0,
+        scale: 1.0,
+        fonts: font_cache,
+        allow_remote_images: false,
+        fast_png: false,
+    };
+    let page = PageSetup {
+        paper: Paper::Letter,
+        margin_pt: 50.0,
+        print: true,
+    };
+
+    // Start rayon's global pool in this process first: the forked export
+    // must not depend on its threads, which do not survive fork.
+    build_tiled_document(&params).unwrap();
+
+    // Throwaway log buffer -- forwarded logs not inspected here
+    let log_buf = LogBuffer::new(1024);
+    let pdf = build_pdf(&params, &page, true, &log_buf).unwrap();
+    assert!(pdf.bytes.starts_with(b"%PDF"), "output is not a PDF");
+    assert_eq!(pdf.page_count, 1);
+}
+
 fn main() {
     // Initialize the global logger so that child processes inherit it via fork
     // COW. The returned LogBuffer shares the same Arc as RingLog's internal
@@ -223,4 +257,8 @@ fn main() {
     eprint!("test test_renderer::test_fork_renderer_logs_forwarded ... ");
     test_fork_renderer_logs_forwarded(&log_buffer);
     eprintln!("ok");
+
+    eprint!("test test_renderer::test_fork_build_pdf ... ");
+    test_fork_build_pdf();
+    eprintln!("ok");
 }
@@ -36,6 +36,25 @@
   stroke: (left: 3pt * scale + rgb("#1e66f5")),
   text(fill: rgb("#6c6f85"), it.body))
 
+// GitHub alerts (> [!NOTE] etc.): icon + title in the kind's color,
+// left border like a quote. Blue / Green / Mauve / Yellow / Red.
+#let alert-styles = (
+  note: ("i", "Note", rgb("#1e66f5")),
+  tip: ("✓", "Tip", rgb("#40a02b")),
+  important: ("!", "Important", rgb("#8839ef")),
+  warning: ("!", "Warning", rgb("#df8e1d")),
+  caution: ("×", "Caution", rgb("#d20f39")),
+)
+#let alert(kind, body) = {
+  let (icon, title, color) = alert-styles.at(kind)
+  block(inset: (left: 16pt * scale, y: 8pt * scale), stroke: (left: 3pt * scale + color), {
+    text(fill: color, weight: "bold")[#box(baseline: 0.15em, circle(radius: 0.5em,
+      stroke: 1pt * scale + color, align(center + horizon, text(size: 0.75em, icon)))) #title]
+    parbreak()
+    body
+  })
+}
+
 // Tables
 #set table(stroke: 0.5pt * scale + rgb("#acb0be"), inset: 8pt * scale,
   fill: (_, y) => if y == 0 { rgb("#ccd0da") } else { none })
@@ -69,5 +88,39 @@
 // Image placeholder: Surface 2 border
 #let image-placeholder(path) = block(stroke: 0.5pt * scale + rgb("#acb0be"), inset: 8pt * scale, radius: 4pt * scale)[Image: #path]
 
+// Front matter header: Mauve title, Subtext 0 author · date, Teal tags on Surface 0
+#let front-matter(title: none, author: none, date: none, tags: ()) = block(below: 1.4em, {
+  if title != none {
+    block(below: 0.6em, text(24pt * scale, weight: "bold", fill: rgb("#8839ef"), title))
+  }
+  let meta = (author, date).filter(it => it != none)
+  if meta.len() > 0 {
+    block(below: 0.6em, text(fill: rgb("#6c6f85"), meta.join([ · ])))
+  }
+  if tags.len() > 0 {
+    tags.map(t => box(fill: rgb("#ccd0da"), inset: (x: 0.4em), outset: (y: 0.25em),
+      radius: 3pt * scale, text(size: 0.85em, fill: rgb("#179299"), [\##t]))).join(h(0.5em))
+  }
+})
+
+// Task list checkbox: Green filled box when done, Overlay 0 outline otherwise
+#let task-box(checked) = box(width: 0.8em, height: 0.8em, baseline: 0.05em,
+  radius: 2pt * scale, stroke: 1pt * scale + if checked { rgb("#40a02b") } else { rgb("#9ca0b0") },
+  fill: if checked { rgb("#40a02b") } else { none },
+  if checked { align(center + horizon, text(size: 0.7em, weight: "bold", fill: rgb("#eff1f5"), "✓")) })
+
+// Footnotes: Blue markers, Subtext 0 definition text with a hanging number
+#let footnote-ref(num) = super(text(fill: rgb("#1e66f5"), num))
+#let footnote-def(num, body) = block(above: 0.9em, text(size: 0.85em, fill: rgb("#6c6f85"),
+  grid(columns: (auto, 1fr), column-gutter: 0.5em, super(text(fill: rgb("#1e66f5"), num)), body)))
+
+// HTML elements: <kbd> keycap and <code> on Surface 0; <details> is always
+// expanded, its <summary> a Mauve header
+#let kbd(body) = box(fill: rgb("#ccd0da"), stroke: 0.5pt * scale + rgb("#9ca0b0"),
+  inset: (x: 0.35em), outset: (y: 0.2em), radius: 3pt * scale, text(size: 0.85em, body))
+#let html-code(body) = box(fill: rgb("#ccd0da"), inset: (x: 0.3em), outset: (y: 0.15em),
+  radius: 3pt * scale, text(font: ("DejaVu Sans Mono", "Noto Sans JP"), size: 0.85em, body))
+#let summary(body) = block(below: 0.8em, text(weight: "bold", fill: rgb("#8839ef"), [▾#h(0.4em)#body]))
+
 // Code block: Catppuccin Latte syntax highlighting
 #set raw(theme: "catppuccin-latte.tmTheme")
@@ -36,6 +36,25 @@
   stroke: (left: 3pt * scale + rgb("#89b4fa")),
   text(fill: rgb("#a6adc8"), it.body))
 
+// GitHub alerts (> [!NOTE] etc.): icon + title in the kind's color,
+// left border like a quote. Blue / Green / Mauve / Yellow / Red.
+#let alert-styles = (
+  note: ("i", "Note", rgb("#89b4fa")),
+  tip: ("✓", "Tip", rgb("#a6e3a1")),
+  important: ("!", "Important", rgb("#cba6f7")),
+  warning: ("!", "Warning", rgb("#f9e2af")),
+  caution: ("×", "Caution", rgb("#f38ba8")),
+)
+#let alert(kind, body) = {
+  let (icon, title, color) = alert-styles.at(kind)
+  block(inset: (left: 16pt * scale, y: 8pt * scale), stroke: (left: 3pt * scale + color), {
+    text(fill: color, weight: "bold")[#box(baseline: 0.15em, circle(radius: 0.5em,
+      stroke: 1pt * scale + color, align(center + horizon, text(size: 0.75em, icon)))) #title]
+    parbreak()
+    body
+  })
+}
+
 // Tables
 #set table(stroke: 0.5pt * scale + rgb("#585b70"), inset: 8pt * scale,
   fill: (_, y) => if y == 0 { rgb("#313244") } else { none })
@@ -69,5 +88,39 @@
 // Image placeholder: Surface 2 border
 #let image-placeholder(path) = block(stroke: 0.5pt * scale + rgb("#585b70"), inset: 8pt * scale, radius: 4pt * scale)[Image: #path]
 
+// Front matter header: Mauve title, Subtext 0 author · date, Teal tags on Surface 0
+#let front-matter(title: none, author: none, date: none, tags: ()) = block(below: 1.4em, {
+  if title != none {
+    block(below: 0.6em, text(24pt * scale, weight: "bold", fill: rgb("#cba6f7"), title))
+  }
+  let meta = (author, date).filter(it => it != none)
+  if meta.len() > 0 {
+    block(below: 0.6em, text(fill: rgb("#a6adc8"), meta.join([ · ])))
+  }
+  if tags.len() > 0 {
+    tags.map(t => box(fill: rgb("#313244"), inset: (x: 0.4em), outset: (y: 0.25em),
+      radius: 3pt * scale, text(size: 0.85em, fill: rgb("#94e2d5"), [\##t]))).join(h(0.5em))
+  }
+})
+
+// Task list checkbox: Green filled box when done, Overlay 0 outline otherwise
+#let task-box(checked) = box(width: 0.8em, height: 0.8em, baseline: 0.05em,
+  radius: 2pt * scale, stroke: 1pt * scale + if checked { rgb("#a6e3a1") } else { rgb("#6c7086") },
+  fill: if checked { rgb("#a6e3a1") } else { none },
+  if checked { align(center + horizon, text(size: 0.7em, weight: "bold", fill: rgb("#1e1e2e"), "✓")) })
+
+// Footnotes: Blue markers, Subtext 0 definition text with a hanging number
+#let footnote-ref(num) = super(text(fill: rgb("#89b4fa"), num))
+#let footnote-def(num, body) = block(above: 0.9em, text(size: 0.85em, fill: rgb("#a6adc8"),
+  grid(columns: (auto, 1fr), column-gutter: 0.5em, super(text(fill: rgb("#89b4fa"), num)), body)))
+
+// HTML elements: <kbd> keycap and <code> on Surface 0; <details> is always
+// expanded, its <summary> a Mauve header
+#let kbd(body) = box(fill: rgb("#313244"), stroke: 0.5pt * scale + rgb("#6c7086"),
+  inset: (x: 0.35em), outset: (y: 0.2em), radius: 3pt * scale, text(size: 0.85em, body))
+#let html-code(body) = box(fill: rgb("#313244"), inset: (x: 0.3em), outset: (y: 0.15em),
+  radius: 3pt * scale, text(font: ("DejaVu Sans Mono", "Noto Sans JP"), size: 0.85em, body))
+#let summary(body) = block(below: 0.8em, text(weight: "bold", fill: rgb("#cba6f7"), [▾#h(0.4em)#body]))
+
 // Code block: Catppuccin Mocha syntax highlighting
 #set raw(theme: "catppuccin-mocha.tmTheme")